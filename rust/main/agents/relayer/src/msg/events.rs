@@ -0,0 +1,168 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hyperlane_core::{HyperlaneMessage, PendingOperationStatus, TxOutcome, H256, H512, U256};
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::settings::matching_list::{MatchInfo, MatchingList};
+
+/// How many events are buffered for a subscriber that falls behind before it
+/// starts missing some
+const OPERATION_EVENTS_CAPACITY: usize = 1_000;
+
+/// A step in the lifecycle of a message being relayed
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationEvent {
+    pub message_id: H256,
+    pub origin: u32,
+    pub destination: u32,
+    pub sender: H256,
+    pub recipient: H256,
+    /// Only used to match the event against `bodyprefix` rules
+    #[serde(skip)]
+    pub body: Vec<u8>,
+    pub app_context: Option<String>,
+    /// Seconds since the unix epoch
+    pub timestamp: u64,
+    #[serde(flatten)]
+    pub kind: OperationEventKind,
+}
+
+impl OperationEvent {
+    /// Check if the message of the event matches any of the rules of a filter.
+    /// - `default`: What to return if the matching list is empty.
+    pub fn matches(&self, filter: &MatchingList, default: bool) -> bool {
+        filter.matches(
+            MatchInfo {
+                src_msg_id: self.message_id,
+                src_domain: self.origin,
+                src_addr: &self.sender,
+                dst_domain: self.destination,
+                dst_addr: &self.recipient,
+                body: &self.body,
+            },
+            default,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum OperationEventKind {
+    /// The operation moved to another queue or was requeued with a new status
+    StatusChanged { status: PendingOperationStatus },
+    /// The transaction delivering the message landed on the destination. The
+    /// gas used is the message's share of the transaction's gas.
+    Submitted {
+        tx_hash: H512,
+        gas_used: U256,
+        executed: bool,
+    },
+    /// The message is delivered and past the reorg window
+    Confirmed {
+        tx_hash: Option<H512>,
+        gas_used: Option<U256>,
+    },
+}
+
+impl OperationEventKind {
+    /// The name of the event, as serialized in its `event` field
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::StatusChanged { .. } => "status_changed",
+            Self::Submitted { .. } => "submitted",
+            Self::Confirmed { .. } => "confirmed",
+        }
+    }
+}
+
+/// Broadcasts the lifecycle events of the relayed messages to the subscribers
+/// of the `/events` endpoint. Events are dropped when nobody is subscribed.
+#[derive(Debug, Clone)]
+pub struct OperationEvents(broadcast::Sender<OperationEvent>);
+
+impl Default for OperationEvents {
+    fn default() -> Self {
+        Self(broadcast::channel(OPERATION_EVENTS_CAPACITY).0)
+    }
+}
+
+impl OperationEvents {
+    pub fn subscribe(&self) -> broadcast::Receiver<OperationEvent> {
+        self.0.subscribe()
+    }
+
+    pub fn publish(
+        &self,
+        message: &HyperlaneMessage,
+        app_context: Option<&str>,
+        kind: OperationEventKind,
+    ) {
+        // Avoid copying the message for nothing
+        if self.0.receiver_count() == 0 {
+            return;
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let event = OperationEvent {
+            message_id: message.id(),
+            origin: message.origin,
+            destination: message.destination,
+            sender: message.sender,
+            recipient: message.recipient,
+            body: message.body.clone(),
+            app_context: app_context.map(str::to_owned),
+            timestamp,
+            kind,
+        };
+        // Sending only fails if the subscribers went away in the meantime
+        let _ = self.0.send(event);
+    }
+
+    pub fn publish_submitted(
+        &self,
+        message: &HyperlaneMessage,
+        app_context: Option<&str>,
+        outcome: &TxOutcome,
+    ) {
+        self.publish(
+            message,
+            app_context,
+            OperationEventKind::Submitted {
+                tx_hash: outcome.transaction_id,
+                gas_used: outcome.gas_used,
+                executed: outcome.executed,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_events_are_only_built_for_subscribers() {
+        let events = OperationEvents::default();
+        let message = HyperlaneMessage::default();
+        let status = OperationEventKind::StatusChanged {
+            status: PendingOperationStatus::ReadyToSubmit,
+        };
+        // Nobody is subscribed, so this is a no-op
+        events.publish(&message, None, status.clone());
+
+        let mut rx = events.subscribe();
+        events.publish(&message, Some("app"), status);
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event.message_id, message.id());
+        assert_eq!(event.app_context.as_deref(), Some("app"));
+        assert!(rx.try_recv().is_err());
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], event.kind.name());
+        assert_eq!(json["status"], "ReadyToSubmit");
+        assert!(json.get("body").is_none());
+    }
+}
@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use derive_new::new;
+use ethers::utils::hex;
+use hyperlane_core::HyperlaneMessage;
+use tokio::sync::RwLock;
+use tracing::debug;
+
+use super::blacklist::AddressBlacklist;
+use crate::settings::matching_list::MatchingList;
+
+/// Filters shared by every message processor. The whole set is swapped at
+/// once when it is updated at runtime, so processors never see a partially
+/// applied update.
+pub type SharedMessageFilters = Arc<RwLock<MessageFilters>>;
+
+/// The filters that decide which messages are relayed
+#[derive(Debug, Clone, Default, new)]
+pub struct MessageFilters {
+    /// A matching list of messages that should be whitelisted.
+    pub whitelist: MatchingList,
+    /// A matching list of messages that should be blacklisted.
+    pub blacklist: MatchingList,
+    /// Addresses that messages may not interact with.
+    pub address_blacklist: AddressBlacklist,
+}
+
+impl MessageFilters {
+    /// Returns true if the message passes the whitelist, the blacklist and the
+    /// address blacklist.
+    pub fn allows(&self, msg: &HyperlaneMessage) -> bool {
+        // Skip if not whitelisted.
+        if !self.whitelist.msg_matches(msg, true) {
+            debug!(?msg, whitelist=?self.whitelist, "Message not whitelisted, skipping");
+            return false;
+        }
+
+        // Skip if the message is blacklisted
+        if self.blacklist.msg_matches(msg, false) {
+            debug!(?msg, blacklist=?self.blacklist, "Message blacklisted, skipping");
+            return false;
+        }
+
+        // Skip if the message involves a blacklisted address
+        if let Some(blacklisted_address) = self.address_blacklist.find_blacklisted_address(msg) {
+            debug!(
+                ?msg,
+                blacklisted_address = hex::encode(blacklisted_address),
+                "Message involves blacklisted address, skipping"
+            );
+            return false;
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::H256;
+
+    use super::*;
+
+    #[test]
+    fn test_allows() {
+        let sender = H256::random();
+        let message = HyperlaneMessage {
+            sender,
+            ..Default::default()
+        };
+        assert!(MessageFilters::default().allows(&message));
+
+        let blacklist: MatchingList = serde_json::from_value(serde_json::json!([
+            { "senderaddress": format!("{sender:?}") }
+        ]))
+        .unwrap();
+        let filters = MessageFilters {
+            blacklist,
+            ..Default::default()
+        };
+        assert!(!filters.allows(&message));
+
+        let filters = MessageFilters {
+            address_blacklist: AddressBlacklist::new(vec![sender.as_bytes().to_vec()]),
+            ..Default::default()
+        };
+        assert!(!filters.allows(&message));
+    }
+}
@@ -9,7 +9,13 @@ use hyperlane_core::{
 };
 use tracing::{debug, error, trace};
 
-use self::policies::{GasPaymentPolicyMinimum, GasPaymentPolicyNone};
+use self::{
+    policies::{
+        GasPaymentPolicyAllOf, GasPaymentPolicyAnyOf, GasPaymentPolicyMinimum,
+        GasPaymentPolicyNone, GasPaymentPolicyUsdValue,
+    },
+    price_source::build_token_price_source,
+};
 use crate::{
     msg::gas_payment::policies::GasPaymentPolicyOnChainFeeQuoting,
     settings::{
@@ -18,6 +24,7 @@ use crate::{
 };
 
 mod policies;
+pub(crate) mod price_source;
 
 pub const GAS_EXPENDITURE_LOG_MESSAGE: &str = "Recording gas expenditure for message";
 
@@ -61,25 +68,39 @@ impl GasPaymentEnforcer {
     ) -> Self {
         let policies = policy_configs
             .into_iter()
-            .map(|cfg| {
-                let p: Box<dyn GasPaymentPolicy> = match cfg.policy {
-                    GasPaymentEnforcementPolicy::None => Box::new(GasPaymentPolicyNone),
-                    GasPaymentEnforcementPolicy::Minimum { payment } => {
-                        Box::new(GasPaymentPolicyMinimum::new(payment))
-                    }
-                    GasPaymentEnforcementPolicy::OnChainFeeQuoting {
-                        gas_fraction_numerator: n,
-                        gas_fraction_denominator: d,
-                    } => Box::new(GasPaymentPolicyOnChainFeeQuoting::new(n, d)),
-                };
-                (p, cfg.matching_list)
-            })
+            .map(|cfg| (build_policy(cfg.policy), cfg.matching_list))
             .collect();
 
         Self { policies, db }
     }
 }
 
+fn build_policy(policy: GasPaymentEnforcementPolicy) -> Box<dyn GasPaymentPolicy> {
+    match policy {
+        GasPaymentEnforcementPolicy::None => Box::new(GasPaymentPolicyNone),
+        GasPaymentEnforcementPolicy::Minimum { payment } => {
+            Box::new(GasPaymentPolicyMinimum::new(payment))
+        }
+        GasPaymentEnforcementPolicy::OnChainFeeQuoting {
+            gas_fraction_numerator: n,
+            gas_fraction_denominator: d,
+        } => Box::new(GasPaymentPolicyOnChainFeeQuoting::new(n, d)),
+        GasPaymentEnforcementPolicy::UsdValue {
+            margin,
+            price_source,
+        } => Box::new(GasPaymentPolicyUsdValue::new(
+            margin,
+            build_token_price_source(price_source),
+        )),
+        GasPaymentEnforcementPolicy::AllOf(policies) => Box::new(GasPaymentPolicyAllOf::new(
+            policies.into_iter().map(build_policy).collect(),
+        )),
+        GasPaymentEnforcementPolicy::AnyOf(policies) => Box::new(GasPaymentPolicyAnyOf::new(
+            policies.into_iter().map(build_policy).collect(),
+        )),
+    }
+}
+
 impl GasPaymentEnforcer {
     /// Returns Some(gas_limit) if the enforcer has approved the transaction or
     /// None if the transaction is not approved.
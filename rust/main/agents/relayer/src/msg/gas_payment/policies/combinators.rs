@@ -0,0 +1,240 @@
+use async_trait::async_trait;
+use derive_new::new;
+use eyre::Result;
+
+use hyperlane_core::{
+    HyperlaneMessage, InterchainGasExpenditure, InterchainGasPayment, TxCostEstimate, U256,
+};
+use tracing::warn;
+
+use crate::msg::gas_payment::GasPaymentPolicy;
+
+/// Met when every one of the policies is met. The gas limit is the smallest
+/// one approved, so that no policy ends up approving more gas than it allows.
+#[derive(Debug, new)]
+pub struct GasPaymentPolicyAllOf {
+    policies: Vec<Box<dyn GasPaymentPolicy>>,
+}
+
+#[async_trait]
+impl GasPaymentPolicy for GasPaymentPolicyAllOf {
+    async fn message_meets_gas_payment_requirement(
+        &self,
+        message: &HyperlaneMessage,
+        current_payment: &InterchainGasPayment,
+        current_expenditure: &InterchainGasExpenditure,
+        tx_cost_estimate: &TxCostEstimate,
+    ) -> Result<Option<U256>> {
+        let mut gas_limit: Option<U256> = None;
+        for policy in &self.policies {
+            let Some(approved) = policy
+                .message_meets_gas_payment_requirement(
+                    message,
+                    current_payment,
+                    current_expenditure,
+                    tx_cost_estimate,
+                )
+                .await?
+            else {
+                return Ok(None);
+            };
+            gas_limit = Some(gas_limit.map_or(approved, |limit| limit.min(approved)));
+        }
+        Ok(gas_limit)
+    }
+}
+
+/// Met when at least one of the policies is met. The gas limit is the largest
+/// one approved by the policies that are met. A policy that fails is treated as
+/// not met, so the check only fails if every policy does.
+#[derive(Debug, new)]
+pub struct GasPaymentPolicyAnyOf {
+    policies: Vec<Box<dyn GasPaymentPolicy>>,
+}
+
+#[async_trait]
+impl GasPaymentPolicy for GasPaymentPolicyAnyOf {
+    async fn message_meets_gas_payment_requirement(
+        &self,
+        message: &HyperlaneMessage,
+        current_payment: &InterchainGasPayment,
+        current_expenditure: &InterchainGasExpenditure,
+        tx_cost_estimate: &TxCostEstimate,
+    ) -> Result<Option<U256>> {
+        let mut gas_limit: Option<U256> = None;
+        let mut first_err = None;
+        let mut failed = 0;
+        for policy in &self.policies {
+            match policy
+                .message_meets_gas_payment_requirement(
+                    message,
+                    current_payment,
+                    current_expenditure,
+                    tx_cost_estimate,
+                )
+                .await
+            {
+                Ok(Some(approved)) => {
+                    gas_limit = Some(gas_limit.map_or(approved, |limit| limit.max(approved)));
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    warn!(
+                        ?policy,
+                        ?err,
+                        "Gas payment policy failed, treating it as not met"
+                    );
+                    failed += 1;
+                    first_err.get_or_insert(err);
+                }
+            }
+        }
+        match first_err {
+            Some(err) if failed == self.policies.len() => Err(err),
+            _ => Ok(gas_limit),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::H256;
+
+    use super::*;
+    use crate::msg::gas_payment::{
+        policies::{
+            GasPaymentPolicyMinimum, GasPaymentPolicyNone, GasPaymentPolicyOnChainFeeQuoting,
+            GasPaymentPolicyUsdValue,
+        },
+        price_source::StaticTokenPriceSource,
+    };
+
+    fn cost_estimate() -> TxCostEstimate {
+        TxCostEstimate {
+            gas_limit: U256::from(1000u32),
+            gas_price: 1u32.into(),
+            l2_gas_limit: None,
+        }
+    }
+
+    fn payment(payment: u32, gas_amount: u32) -> InterchainGasPayment {
+        InterchainGasPayment {
+            message_id: H256::zero(),
+            destination: 0,
+            payment: payment.into(),
+            gas_amount: gas_amount.into(),
+        }
+    }
+
+    fn minimum(payment: u32) -> Box<dyn GasPaymentPolicy> {
+        Box::new(GasPaymentPolicyMinimum::new(payment.into()))
+    }
+
+    fn on_chain_fee_quoting() -> Box<dyn GasPaymentPolicy> {
+        Box::new(GasPaymentPolicyOnChainFeeQuoting::new(1, 1))
+    }
+
+    /// Fails since no token price is known
+    fn usd_value_without_prices() -> Box<dyn GasPaymentPolicy> {
+        Box::new(GasPaymentPolicyUsdValue::new(
+            1.,
+            Box::new(StaticTokenPriceSource::new(Default::default())),
+        ))
+    }
+
+    async fn try_evaluate(
+        policy: &dyn GasPaymentPolicy,
+        payment: InterchainGasPayment,
+    ) -> Result<Option<U256>> {
+        policy
+            .message_meets_gas_payment_requirement(
+                &HyperlaneMessage::default(),
+                &payment,
+                &InterchainGasExpenditure {
+                    message_id: H256::zero(),
+                    gas_used: U256::zero(),
+                    tokens_used: U256::zero(),
+                },
+                &cost_estimate(),
+            )
+            .await
+    }
+
+    async fn evaluate(
+        policy: &dyn GasPaymentPolicy,
+        payment: InterchainGasPayment,
+    ) -> Option<U256> {
+        try_evaluate(policy, payment).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_all_of() {
+        let policy = GasPaymentPolicyAllOf::new(vec![minimum(100), on_chain_fee_quoting()]);
+
+        // Both are met; the on-chain quote approves the 1500 gas paid for but the
+        // minimum only the estimate
+        assert_eq!(
+            evaluate(&policy, payment(100, 1500)).await,
+            Some(U256::from(1000u32))
+        );
+        // The minimum isn't met
+        assert_eq!(evaluate(&policy, payment(99, 1500)).await, None);
+        // The on-chain quote isn't met
+        assert_eq!(evaluate(&policy, payment(100, 999)).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_any_of() {
+        let policy = GasPaymentPolicyAnyOf::new(vec![minimum(100), on_chain_fee_quoting()]);
+
+        assert_eq!(
+            evaluate(&policy, payment(100, 1500)).await,
+            Some(U256::from(1500u32))
+        );
+        assert_eq!(
+            evaluate(&policy, payment(99, 1500)).await,
+            Some(U256::from(1500u32))
+        );
+        assert_eq!(
+            evaluate(&policy, payment(100, 999)).await,
+            Some(U256::from(1000u32))
+        );
+        assert_eq!(evaluate(&policy, payment(99, 999)).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_any_of_treats_failing_policies_as_not_met() {
+        let policy = GasPaymentPolicyAnyOf::new(vec![usd_value_without_prices(), minimum(100)]);
+
+        assert_eq!(
+            evaluate(&policy, payment(100, 0)).await,
+            Some(U256::from(1000u32))
+        );
+        assert_eq!(evaluate(&policy, payment(99, 0)).await, None);
+
+        // Only fails if every policy fails
+        let policy = GasPaymentPolicyAnyOf::new(vec![
+            usd_value_without_prices(),
+            usd_value_without_prices(),
+        ]);
+        assert!(try_evaluate(&policy, payment(100, 0)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_nested() {
+        // minimum(100) && (minimum(200) || none)
+        let policy = GasPaymentPolicyAllOf::new(vec![
+            minimum(100),
+            Box::new(GasPaymentPolicyAnyOf::new(vec![
+                minimum(200),
+                Box::new(GasPaymentPolicyNone),
+            ])),
+        ]);
+
+        assert_eq!(
+            evaluate(&policy, payment(100, 0)).await,
+            Some(U256::from(1000u32))
+        );
+        assert_eq!(evaluate(&policy, payment(99, 0)).await, None);
+    }
+}
@@ -0,0 +1,229 @@
+use async_trait::async_trait;
+use derive_new::new;
+use eyre::{eyre, Result};
+
+use hyperlane_core::{
+    FixedPointNumber, HyperlaneMessage, InterchainGasExpenditure, InterchainGasPayment,
+    TxCostEstimate, U256,
+};
+use tracing::debug;
+
+use crate::{
+    msg::gas_payment::{price_source::TokenPriceSource, GasPaymentPolicy},
+    settings::TokenPrice,
+};
+
+/// Compares the origin payment with the estimated destination cost after
+/// converting both into USD, so the requirement is the same on every route.
+#[derive(Debug, new)]
+pub struct GasPaymentPolicyUsdValue {
+    /// The payment must be worth at least this multiple of the estimated cost
+    margin: f64,
+    prices: Box<dyn TokenPriceSource>,
+}
+
+impl GasPaymentPolicyUsdValue {
+    async fn price(&self, domain: u32) -> Result<TokenPrice> {
+        if self.prices.is_stale().await {
+            return Err(eyre!("Token prices are stale"));
+        }
+        self.prices
+            .price(domain)
+            .await?
+            .ok_or_else(|| eyre!("No token price known for domain {domain}"))
+    }
+}
+
+fn usd_value(amount: U256, price: &TokenPrice) -> f64 {
+    amount.to_f64_lossy() / 10f64.powi(price.decimals as i32) * price.price
+}
+
+#[async_trait]
+impl GasPaymentPolicy for GasPaymentPolicyUsdValue {
+    async fn message_meets_gas_payment_requirement(
+        &self,
+        message: &HyperlaneMessage,
+        current_payment: &InterchainGasPayment,
+        current_expenditure: &InterchainGasExpenditure,
+        tx_cost_estimate: &TxCostEstimate,
+    ) -> Result<Option<U256>> {
+        let origin_price = self.price(message.origin).await?;
+        let destination_price = self.price(message.destination).await?;
+
+        let tx_cost: U256 = (FixedPointNumber::try_from(tx_cost_estimate.gas_limit)?
+            * tx_cost_estimate.gas_price.clone())
+        .try_into()?;
+        let payment_usd = usd_value(current_payment.payment, &origin_price);
+        let spent_usd = usd_value(current_expenditure.tokens_used, &destination_price);
+        let cost_usd = usd_value(tx_cost, &destination_price);
+
+        debug!(
+            payment_usd,
+            spent_usd,
+            cost_usd,
+            margin = self.margin,
+            "Comparing USD value of gas payment and transaction cost"
+        );
+        if payment_usd - spent_usd >= cost_usd * self.margin {
+            Ok(Some(tx_cost_estimate.gas_limit))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use hyperlane_core::H256;
+
+    use super::*;
+    use crate::msg::gas_payment::price_source::{FileTokenPriceSource, StaticTokenPriceSource};
+
+    const ORIGIN: u32 = 1;
+    const DESTINATION: u32 = 2;
+
+    fn policy(margin: f64) -> GasPaymentPolicyUsdValue {
+        GasPaymentPolicyUsdValue::new(
+            margin,
+            Box::new(StaticTokenPriceSource::new(HashMap::from([
+                // $2000 per token with 18 decimals
+                (
+                    ORIGIN,
+                    TokenPrice {
+                        price: 2000.,
+                        decimals: 18,
+                    },
+                ),
+                // $0.5 per token with 6 decimals
+                (
+                    DESTINATION,
+                    TokenPrice {
+                        price: 0.5,
+                        decimals: 6,
+                    },
+                ),
+            ]))),
+        )
+    }
+
+    fn message() -> HyperlaneMessage {
+        HyperlaneMessage {
+            origin: ORIGIN,
+            destination: DESTINATION,
+            ..Default::default()
+        }
+    }
+
+    fn payment(payment: u64) -> InterchainGasPayment {
+        InterchainGasPayment {
+            message_id: H256::zero(),
+            destination: DESTINATION,
+            payment: payment.into(),
+            gas_amount: U256::zero(),
+        }
+    }
+
+    fn expenditure(tokens_used: u64) -> InterchainGasExpenditure {
+        InterchainGasExpenditure {
+            message_id: H256::zero(),
+            gas_used: U256::zero(),
+            tokens_used: tokens_used.into(),
+        }
+    }
+
+    // 100k gas at 20 units per gas costs 2 destination tokens, i.e. $1
+    fn cost_estimate() -> TxCostEstimate {
+        TxCostEstimate {
+            gas_limit: U256::from(100_000u64),
+            gas_price: 20u32.into(),
+            l2_gas_limit: None,
+        }
+    }
+
+    // $1 worth of origin tokens
+    const ONE_USD_OF_ORIGIN: u64 = 500_000_000_000_000;
+
+    async fn meets_requirement(
+        policy: &GasPaymentPolicyUsdValue,
+        payment: u64,
+        tokens_used: u64,
+    ) -> Option<U256> {
+        policy
+            .message_meets_gas_payment_requirement(
+                &message(),
+                &self::payment(payment),
+                &expenditure(tokens_used),
+                &cost_estimate(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_payment_covers_cost_times_margin() {
+        let policy = policy(1.5);
+        assert_eq!(
+            meets_requirement(&policy, ONE_USD_OF_ORIGIN * 2, 0).await,
+            Some(cost_estimate().gas_limit)
+        );
+        assert_eq!(
+            meets_requirement(&policy, ONE_USD_OF_ORIGIN * 7 / 5, 0).await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_accounts_for_expenditure() {
+        let policy = policy(1.);
+        assert_eq!(
+            meets_requirement(&policy, ONE_USD_OF_ORIGIN * 2, 0).await,
+            Some(cost_estimate().gas_limit)
+        );
+        // Just over $1 worth of destination tokens was already spent
+        assert_eq!(
+            meets_requirement(&policy, ONE_USD_OF_ORIGIN * 2, 2_000_001).await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_price_is_an_error() {
+        let policy = policy(1.);
+        let message = HyperlaneMessage {
+            destination: 3,
+            ..message()
+        };
+        assert!(policy
+            .message_meets_gas_payment_requirement(
+                &message,
+                &payment(ONE_USD_OF_ORIGIN),
+                &expenditure(0),
+                &cost_estimate(),
+            )
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stale_prices_are_an_error() {
+        // Never read, so its prices are stale
+        let policy = GasPaymentPolicyUsdValue::new(
+            1.,
+            Box::new(FileTokenPriceSource::new(
+                "prices.json".into(),
+                std::time::Duration::from_secs(60),
+            )),
+        );
+        assert!(policy
+            .message_meets_gas_payment_requirement(
+                &message(),
+                &payment(ONE_USD_OF_ORIGIN * 2),
+                &expenditure(0),
+                &cost_estimate(),
+            )
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,207 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use derive_new::new;
+use eyre::{Context, Result};
+use tokio::{sync::RwLock, time::sleep};
+use tracing::{debug, warn};
+
+use crate::settings::{TokenPrice, TokenPriceSourceConf};
+
+/// Prices that weren't refreshed for this many refresh intervals are stale
+const STALE_AFTER_REFRESH_INTERVALS: u32 = 3;
+
+/// Provides the USD price of the native token of a chain
+#[async_trait]
+pub trait TokenPriceSource: Debug + Send + Sync {
+    /// Returns the price of the native token of `domain`, or None if it isn't
+    /// known.
+    async fn price(&self, domain: u32) -> Result<Option<TokenPrice>>;
+
+    /// Whether the prices are too old to be relied on
+    async fn is_stale(&self) -> bool {
+        false
+    }
+}
+
+#[async_trait]
+impl<T: TokenPriceSource + ?Sized> TokenPriceSource for Arc<T> {
+    async fn price(&self, domain: u32) -> Result<Option<TokenPrice>> {
+        (**self).price(domain).await
+    }
+
+    async fn is_stale(&self) -> bool {
+        (**self).is_stale().await
+    }
+}
+
+/// Builds the price source of the `conf`. File backed sources are refreshed
+/// by a background task, so this must be called from within a tokio runtime.
+pub fn build_token_price_source(conf: TokenPriceSourceConf) -> Box<dyn TokenPriceSource> {
+    match conf {
+        TokenPriceSourceConf::Static(prices) => Box::new(StaticTokenPriceSource::new(prices)),
+        TokenPriceSourceConf::File {
+            path,
+            refresh_interval,
+        } => {
+            let source = Arc::new(FileTokenPriceSource::new(path, refresh_interval));
+            source.spawn_refresh();
+            Box::new(source)
+        }
+    }
+}
+
+/// Prices that are set once in the config
+#[derive(Debug, new)]
+pub struct StaticTokenPriceSource {
+    prices: HashMap<u32, TokenPrice>,
+}
+
+#[async_trait]
+impl TokenPriceSource for StaticTokenPriceSource {
+    async fn price(&self, domain: u32) -> Result<Option<TokenPrice>> {
+        Ok(self.prices.get(&domain).copied())
+    }
+}
+
+/// Prices read from a JSON file that maps domain ids to prices, e.g.
+/// `{"1": {"price": 2500.5, "decimals": 18}}`. The file is read again every
+/// refresh interval, so it can be kept up to date by an external process.
+#[derive(Debug, new)]
+pub struct FileTokenPriceSource {
+    path: PathBuf,
+    refresh_interval: Duration,
+    /// The prices last read from the file, and when they were read
+    #[new(default)]
+    prices: RwLock<Option<(Instant, HashMap<u32, TokenPrice>)>>,
+}
+
+impl FileTokenPriceSource {
+    async fn read_prices(&self) -> Result<HashMap<u32, TokenPrice>> {
+        let contents = tokio::fs::read_to_string(&self.path)
+            .await
+            .with_context(|| format!("Reading token prices from {}", self.path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Parsing token prices from {}", self.path.display()))
+    }
+
+    /// Reads the prices from the file again. The last known prices are kept if
+    /// the file can't be read, e.g. because it is being rewritten.
+    async fn refresh(&self) {
+        match self.read_prices().await {
+            Ok(prices) => {
+                debug!(path = %self.path.display(), "Refreshed token prices");
+                *self.prices.write().await = Some((Instant::now(), prices));
+            }
+            Err(err) => warn!(?err, "Failed to refresh token prices"),
+        }
+    }
+
+    /// Refreshes the prices every refresh interval, until the source is
+    /// dropped
+    pub fn spawn_refresh(self: &Arc<Self>) {
+        let source = Arc::downgrade(self);
+        let refresh_interval = self.refresh_interval;
+        tokio::spawn(async move {
+            while let Some(source) = source.upgrade() {
+                source.refresh().await;
+                drop(source);
+                sleep(refresh_interval).await;
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl TokenPriceSource for FileTokenPriceSource {
+    async fn price(&self, domain: u32) -> Result<Option<TokenPrice>> {
+        Ok(self
+            .prices
+            .read()
+            .await
+            .as_ref()
+            .and_then(|(_, prices)| prices.get(&domain).copied()))
+    }
+
+    async fn is_stale(&self) -> bool {
+        self.prices
+            .read()
+            .await
+            .as_ref()
+            .map_or(true, |(read_at, _)| {
+                read_at.elapsed() > self.refresh_interval * STALE_AFTER_REFRESH_INTERVALS
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_token_price_source_refreshes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prices.json");
+        std::fs::write(&path, r#"{"1": {"price": 2000}}"#).unwrap();
+
+        let source = FileTokenPriceSource::new(path.clone(), Duration::from_secs(60));
+        assert!(source.is_stale().await);
+        source.refresh().await;
+        assert!(!source.is_stale().await);
+        assert_eq!(
+            source.price(1).await.unwrap(),
+            Some(TokenPrice {
+                price: 2000.,
+                decimals: 18
+            })
+        );
+        assert_eq!(source.price(2).await.unwrap(), None);
+
+        std::fs::write(&path, r#"{"1": {"price": 2100, "decimals": 6}}"#).unwrap();
+        source.refresh().await;
+        assert_eq!(
+            source.price(1).await.unwrap(),
+            Some(TokenPrice {
+                price: 2100.,
+                decimals: 6
+            })
+        );
+
+        // The last known prices are kept if the file can't be read anymore
+        std::fs::remove_file(&path).unwrap();
+        source.refresh().await;
+        assert_eq!(source.price(1).await.unwrap().unwrap().price, 2100.);
+    }
+
+    #[tokio::test]
+    async fn test_file_token_price_source_refreshes_periodically_and_goes_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prices.json");
+        std::fs::write(&path, r#"{"1": {"price": 2000}}"#).unwrap();
+
+        let source = Arc::new(FileTokenPriceSource::new(
+            path.clone(),
+            Duration::from_millis(10),
+        ));
+        source.spawn_refresh();
+        sleep(Duration::from_millis(25)).await;
+        assert_eq!(source.price(1).await.unwrap().unwrap().price, 2000.);
+
+        // Picked up without reading any price in between
+        std::fs::write(&path, r#"{"1": {"price": 2100}}"#).unwrap();
+        sleep(Duration::from_millis(25)).await;
+        assert_eq!(source.price(1).await.unwrap().unwrap().price, 2100.);
+        assert!(!source.is_stale().await);
+
+        // Prices that can't be refreshed anymore become stale
+        std::fs::remove_file(&path).unwrap();
+        sleep(Duration::from_millis(50)).await;
+        assert!(source.is_stale().await);
+    }
+}
@@ -1,15 +1,16 @@
+use std::future::Future;
+
 use async_trait::async_trait;
 use derive_more::Deref;
-use futures_util::future::join_all;
+use futures_util::stream::{FuturesUnordered, StreamExt};
 
 use derive_new::new;
 use eyre::Context;
-use itertools::{Either, Itertools};
-use tracing::{info, instrument};
+use tracing::{debug, info, instrument};
 
-use hyperlane_core::{HyperlaneMessage, InterchainSecurityModule, ModuleType, H256, U256};
+use hyperlane_core::{HyperlaneMessage, ModuleType, H256};
 
-use super::{MessageMetadataBuilder, MetadataBuilder};
+use super::{base::IsmWithMetadataAndType, MessageMetadataBuilder, MetadataBuilder};
 
 /// Bytes used to store one member of the (start, end) range tuple
 /// Copied from `AggregationIsmMetadata.sol`
@@ -28,21 +29,6 @@ struct SubModuleMetadata {
     metadata: Vec<u8>,
 }
 
-#[derive(Debug)]
-struct IsmAndMetadata {
-    ism: Box<dyn InterchainSecurityModule>,
-    meta: SubModuleMetadata,
-}
-
-impl IsmAndMetadata {
-    fn new(ism: Box<dyn InterchainSecurityModule>, index: usize, metadata: Vec<u8>) -> Self {
-        Self {
-            ism,
-            meta: SubModuleMetadata::new(index, metadata),
-        }
-    }
-}
-
 impl AggregationIsmMetadataBuilder {
     fn format_metadata(metadatas: &mut [SubModuleMetadata], ism_count: usize) -> Vec<u8> {
         // See test solidity implementation of this fn at:
@@ -74,44 +60,56 @@ impl AggregationIsmMetadataBuilder {
         buffer
     }
 
-    fn n_cheapest_metas(
-        mut metas_and_gas: Vec<(SubModuleMetadata, U256)>,
-        n: usize,
-    ) -> Vec<SubModuleMetadata> {
-        // Sort by gas cost in ascending order
-        metas_and_gas.sort_by(|(_, gas_1), (_, gas_2)| gas_1.cmp(gas_2));
-        // Take the cheapest n (the aggregation ISM threshold)
-        let mut cheapest: Vec<_> = metas_and_gas[..n].into();
-        // Sort by index in ascending order, to match the order expected by the smart contract
-        cheapest.sort_by(|(meta_1, _), (meta_2, _)| meta_1.index.cmp(&meta_2.index));
-        cheapest.into_iter().map(|(meta, _)| meta).collect()
+    /// Builds the metadata of a sub-module and checks that it verifies.
+    /// Returns the ISM and its module type if it has no valid metadata.
+    async fn build_sub_module_metadata(
+        &self,
+        index: usize,
+        ism_address: H256,
+        message: &HyperlaneMessage,
+    ) -> Result<SubModuleMetadata, (H256, Option<ModuleType>)> {
+        let IsmWithMetadataAndType {
+            ism,
+            metadata,
+            module_type,
+        } = self
+            .base
+            .build_ism_and_metadata(ism_address, message)
+            .await
+            .map_err(|_| (ism_address, None))?;
+        let metadata = metadata.ok_or((ism_address, Some(module_type)))?;
+        match ism.dry_run_verify(message, &metadata).await {
+            Ok(Some(_)) => Ok(SubModuleMetadata::new(index, metadata)),
+            _ => Err((ism_address, Some(module_type))),
+        }
     }
 
-    async fn cheapest_valid_metas(
-        sub_modules: Vec<IsmAndMetadata>,
-        message: &HyperlaneMessage,
+    /// Drives the sub-module builds concurrently until `threshold` of them
+    /// succeed. The builds still in flight are then dropped, which cancels
+    /// them.
+    ///
+    /// This trades gas for latency: the metadata of the first sub-modules to
+    /// build is used, even if a slower one would be cheaper to verify.
+    async fn collect_until_threshold<T, E>(
+        mut builds: FuturesUnordered<impl Future<Output = Result<T, E>>>,
         threshold: usize,
-        err_isms: Vec<(H256, Option<ModuleType>)>,
-    ) -> Option<Vec<SubModuleMetadata>> {
-        let gas_cost_results: Vec<_> = join_all(
-            sub_modules
-                .iter()
-                .map(|module| module.ism.dry_run_verify(message, &(module.meta.metadata))),
-        )
-        .await;
-        // Filter out the ISMs with a gas cost estimate
-        let metas_and_gas: Vec<_> = sub_modules
-            .into_iter()
-            .zip(gas_cost_results.into_iter())
-            .filter_map(|(module, gas_cost)| gas_cost.ok().flatten().map(|gc| (module.meta, gc)))
-            .collect();
-
-        let metas_and_gas_count = metas_and_gas.len();
-        if metas_and_gas_count < threshold {
-            info!(?err_isms, %metas_and_gas_count, %threshold, message_id=?message.id(), "Could not fetch all metadata, ISM metadata count did not reach aggregation threshold");
-            return None;
+    ) -> (Vec<T>, Vec<E>) {
+        let mut oks = Vec::with_capacity(threshold);
+        let mut errs = Vec::new();
+        while oks.len() < threshold {
+            match builds.next().await {
+                Some(Ok(ok)) => oks.push(ok),
+                Some(Err(err)) => errs.push(err),
+                None => break,
+            }
+        }
+        if !builds.is_empty() {
+            debug!(
+                cancelled = builds.len(),
+                "Reached the aggregation threshold, cancelling the remaining sub-module builds"
+            );
         }
-        Some(Self::n_cheapest_metas(metas_and_gas, threshold))
+        (oks, errs)
     }
 }
 
@@ -129,36 +127,25 @@ impl MetadataBuilder for AggregationIsmMetadataBuilder {
         let (ism_addresses, threshold) = ism.modules_and_threshold(message).await.context(CTX)?;
         let threshold = threshold as usize;
 
-        let sub_modules_and_metas = join_all(
-            ism_addresses
-                .iter()
-                .map(|ism_address| self.base.build_ism_and_metadata(*ism_address, message)),
-        )
-        .await;
-
-        // Partitions things into
-        // 1. ok_sub_modules: ISMs with metadata with valid metadata
-        // 2. err_sub_modules: ISMs with invalid metadata
-        let (ok_sub_modules, err_sub_modules): (Vec<_>, Vec<_>) = sub_modules_and_metas
-            .into_iter()
-            .zip(ism_addresses.iter())
+        // Sub-modules are built concurrently, each bounded by the sub-module
+        // timeout, so a slow one can't hold up the others
+        let builds = ism_addresses
+            .iter()
             .enumerate()
-            .partition_map(|(index, (result, ism_address))| match result {
-                Ok(sub_module_and_meta) => match sub_module_and_meta.metadata {
-                    Some(metadata) => Either::Left(IsmAndMetadata::new(
-                        sub_module_and_meta.ism,
-                        index,
-                        metadata,
-                    )),
-                    None => Either::Right((*ism_address, Some(sub_module_and_meta.module_type))),
-                },
-                Err(_) => Either::Right((*ism_address, None)),
-            });
-        let maybe_aggregation_metadata =
-            Self::cheapest_valid_metas(ok_sub_modules, message, threshold, err_sub_modules)
-                .await
-                .map(|mut metas| Self::format_metadata(&mut metas, ism_addresses.len()));
-        Ok(maybe_aggregation_metadata)
+            .map(|(index, ism_address)| {
+                self.build_sub_module_metadata(index, *ism_address, message)
+            })
+            .collect::<FuturesUnordered<_>>();
+        let (mut metas, err_isms) = Self::collect_until_threshold(builds, threshold).await;
+
+        let metas_count = metas.len();
+        if metas_count < threshold {
+            info!(?err_isms, %metas_count, %threshold, message_id=?message.id(), "Could not fetch all metadata, ISM metadata count did not reach aggregation threshold");
+            return Ok(None);
+        }
+        // Sort by index in ascending order, to match the order expected by the smart contract
+        metas.sort_by_key(|meta| meta.index);
+        Ok(Some(Self::format_metadata(&mut metas, ism_addresses.len())))
     }
 }
 
@@ -236,28 +223,35 @@ mod test {
         );
     }
 
-    #[test]
-    fn test_n_cheapest_metas_works() {
-        let metas_and_gas = vec![
-            (
-                SubModuleMetadata::new(3, vec![]),
-                U256::from_dec_str("3").unwrap(),
-            ),
-            (
-                SubModuleMetadata::new(2, vec![]),
-                U256::from_dec_str("2").unwrap(),
-            ),
-            (
-                SubModuleMetadata::new(1, vec![]),
-                U256::from_dec_str("1").unwrap(),
-            ),
-        ];
-        assert_eq!(
-            AggregationIsmMetadataBuilder::n_cheapest_metas(metas_and_gas, 2),
-            vec![
-                SubModuleMetadata::new(1, vec![]),
-                SubModuleMetadata::new(2, vec![])
-            ]
+    #[tokio::test]
+    async fn test_collect_until_threshold_cancels_remaining_builds() {
+        use futures_util::future::{pending, ready, BoxFuture, FutureExt};
+
+        // One failed build, two successful ones, and one that never completes
+        let builds: FuturesUnordered<BoxFuture<'static, Result<u32, &str>>> = [
+            ready(Err("failed")).boxed(),
+            ready(Ok(1)).boxed(),
+            pending().boxed(),
+            ready(Ok(2)).boxed(),
+        ]
+        .into_iter()
+        .collect();
+        let (oks, errs) = tokio::time::timeout(
+            std::time::Duration::from_secs(1),
+            AggregationIsmMetadataBuilder::collect_until_threshold(builds, 2),
         )
+        .await
+        .expect("Builds past the threshold should be cancelled");
+        assert_eq!(oks, vec![1, 2]);
+        assert_eq!(errs, vec!["failed"]);
+
+        // Stops once all builds completed, even if the threshold isn't met
+        let builds: FuturesUnordered<BoxFuture<'static, Result<u32, &str>>> =
+            [ready(Ok(1)).boxed(), ready(Err("failed")).boxed()]
+                .into_iter()
+                .collect();
+        let (oks, errs) = AggregationIsmMetadataBuilder::collect_until_threshold(builds, 2).await;
+        assert_eq!(oks, vec![1]);
+        assert_eq!(errs, vec!["failed"]);
     }
 }
@@ -2,7 +2,7 @@
 #![allow(clippy::unnecessary_get_then_check)] // TODO: `rustc` 1.80.1 clippy issue
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     fmt::Debug,
     ops::Deref,
     str::FromStr,
@@ -17,6 +17,7 @@ use crate::{
         AggregationIsmMetadataBuilder, CcipReadIsmMetadataBuilder, NullMetadataBuilder,
         RoutingIsmMetadataBuilder,
     },
+    msg::quorum_health::{IsmQuorumHealth, QuorumHealthTracker},
     settings::matching_list::MatchingList,
 };
 use async_trait::async_trait;
@@ -25,12 +26,13 @@ use eyre::{Context, Result};
 use hyperlane_base::db::{HyperlaneDb, HyperlaneRocksDB};
 use hyperlane_base::{
     settings::{ChainConf, CheckpointSyncerConf},
-    CheckpointSyncer, CoreMetrics, MultisigCheckpointSyncer,
+    CheckpointCache, CheckpointSyncer, CoreMetrics, FallbackCheckpointSyncer,
+    MultisigCheckpointSyncer,
 };
 use hyperlane_core::{
     accumulator::merkle::Proof, AggregationIsm, CcipReadIsm, Checkpoint, HyperlaneDomain,
     HyperlaneMessage, InterchainSecurityModule, Mailbox, ModuleType, MultisigIsm, RoutingIsm,
-    ValidatorAnnounce, H160, H256,
+    ValidatorAnnounce, WeightedValidator, H160, H256,
 };
 
 use tokio::sync::RwLock;
@@ -42,6 +44,8 @@ pub enum MetadataBuilderError {
     UnsupportedModuleType(ModuleType),
     #[error("Exceeded max depth when building metadata ({0})")]
     MaxDepthExceeded(u32),
+    #[error("Timed out building metadata of a {0} sub-module after {1:?}")]
+    SubModuleTimeout(ModuleType, Duration),
 }
 
 #[derive(Debug)]
@@ -247,10 +251,16 @@ impl MessageMetadataBuilder {
 
         let metadata_builder: Box<dyn MetadataBuilder> = match module_type {
             ModuleType::MerkleRootMultisig => {
-                Box::new(MerkleRootMultisigMetadataBuilder::new(cloned))
+                Box::new(MerkleRootMultisigMetadataBuilder::new(cloned, false))
             }
             ModuleType::MessageIdMultisig => {
-                Box::new(MessageIdMultisigMetadataBuilder::new(cloned))
+                Box::new(MessageIdMultisigMetadataBuilder::new(cloned, false))
+            }
+            ModuleType::WeightedMerkleRootMultisig => {
+                Box::new(MerkleRootMultisigMetadataBuilder::new(cloned, true))
+            }
+            ModuleType::WeightedMessageIdMultisig => {
+                Box::new(MessageIdMultisigMetadataBuilder::new(cloned, true))
             }
             ModuleType::Routing => Box::new(RoutingIsmMetadataBuilder::new(cloned)),
             ModuleType::Aggregation => Box::new(AggregationIsmMetadataBuilder::new(cloned)),
@@ -258,13 +268,45 @@ impl MessageMetadataBuilder {
             ModuleType::CcipRead => Box::new(CcipReadIsmMetadataBuilder::new(cloned)),
             _ => return Err(MetadataBuilderError::UnsupportedModuleType(module_type).into()),
         };
-        let meta = metadata_builder
-            .build(ism_address, message)
+        let start = Instant::now();
+        // Sub-modules are bounded by a timeout, so a slow one doesn't hold up
+        // the metadata of the ISMs it is part of
+        let meta = if self.depth > 0 {
+            tokio::time::timeout(
+                self.sub_module_metadata_timeout,
+                metadata_builder.build(ism_address, message),
+            )
             .await
-            .context("When building metadata");
+            .unwrap_or_else(|_| {
+                Err(MetadataBuilderError::SubModuleTimeout(
+                    module_type,
+                    self.sub_module_metadata_timeout,
+                )
+                .into())
+            })
+        } else {
+            metadata_builder.build(ism_address, message).await
+        };
+        let result = match &meta {
+            Ok(Some(_)) => "built",
+            Ok(None) => "no_metadata",
+            Err(err) => match err.downcast_ref::<MetadataBuilderError>() {
+                Some(MetadataBuilderError::SubModuleTimeout(..)) => "timed_out",
+                _ => "failed",
+            },
+        };
+        self.metrics
+            .metadata_build_duration_seconds()
+            .with_label_values(&[
+                self.origin_domain().name(),
+                self.destination_domain().name(),
+                &module_type.to_string(),
+                result,
+            ])
+            .observe(start.elapsed().as_secs_f64());
         Ok(IsmWithMetadataAndType {
             ism,
-            metadata: meta?,
+            metadata: meta.context("When building metadata")?,
             module_type,
         })
     }
@@ -279,7 +321,11 @@ pub struct BaseMetadataBuilder {
     origin_prover_sync: Arc<RwLock<MerkleTreeBuilder>>,
     origin_validator_announce: Arc<dyn ValidatorAnnounce>,
     allow_local_checkpoint_syncers: bool,
+    /// How long building the metadata of a sub-module may take
+    sub_module_metadata_timeout: Duration,
     metrics: Arc<CoreMetrics>,
+    checkpoint_cache: Arc<CheckpointCache>,
+    quorum_health: Arc<QuorumHealthTracker>,
     db: HyperlaneRocksDB,
     app_context_classifier: IsmAwareAppContextClassifier,
     #[new(value = "7")]
@@ -375,35 +421,50 @@ impl BaseMetadataBuilder {
             .get_announced_storage_locations(validators)
             .await?;
 
-        // Only use the most recently announced location for now.
+        // Read from the most recently announced location that can be loaded. The
+        // validator's other locations are only loaded if reading from it fails.
         let mut checkpoint_syncers: HashMap<H160, Arc<dyn CheckpointSyncer>> = HashMap::new();
         for (&validator, validator_storage_locations) in validators.iter().zip(storage_locations) {
-            for storage_location in validator_storage_locations.iter().rev() {
-                let Ok(config) = CheckpointSyncerConf::from_str(storage_location) else {
-                    debug!(
-                        ?validator,
-                        ?storage_location,
-                        "Could not parse checkpoint syncer config for validator"
-                    );
-                    continue;
-                };
-
-                // If this is a LocalStorage based checkpoint syncer and it's not
-                // allowed, ignore it
-                if !self.allow_local_checkpoint_syncers
-                    && matches!(config, CheckpointSyncerConf::LocalStorage { .. })
-                {
-                    debug!(
-                        ?config,
-                        "Ignoring disallowed LocalStorage based checkpoint syncer"
-                    );
-                    continue;
-                }
+            let mut configs = validator_storage_locations
+                .iter()
+                .rev()
+                .filter_map(|storage_location| {
+                    let Ok(config) = CheckpointSyncerConf::from_str(storage_location) else {
+                        debug!(
+                            ?validator,
+                            ?storage_location,
+                            "Could not parse checkpoint syncer config for validator"
+                        );
+                        return None;
+                    };
+
+                    // If this is a LocalStorage based checkpoint syncer and it's not
+                    // allowed, ignore it
+                    if !self.allow_local_checkpoint_syncers
+                        && matches!(config, CheckpointSyncerConf::LocalStorage { .. })
+                    {
+                        debug!(
+                            ?config,
+                            "Ignoring disallowed LocalStorage based checkpoint syncer"
+                        );
+                        return None;
+                    }
+                    Some(config)
+                })
+                .collect::<VecDeque<_>>();
 
+            while let Some(config) = configs.pop_front() {
                 match config.build_and_validate(None).await {
                     Ok(checkpoint_syncer) => {
-                        // found the syncer for this validator
-                        checkpoint_syncers.insert(validator.into(), checkpoint_syncer.into());
+                        let checkpoint_syncer: Arc<dyn CheckpointSyncer> = if configs.is_empty() {
+                            checkpoint_syncer.into()
+                        } else {
+                            Arc::new(FallbackCheckpointSyncer::new(
+                                checkpoint_syncer.into(),
+                                configs.drain(..).collect(),
+                            ))
+                        };
+                        checkpoint_syncers.insert(validator.into(), checkpoint_syncer);
                         break;
                     }
                     Err(err) => {
@@ -432,6 +493,35 @@ impl BaseMetadataBuilder {
             checkpoint_syncers,
             self.metrics.clone(),
             app_context,
+            self.origin_domain.id(),
+            self.checkpoint_cache.clone(),
         ))
     }
+
+    /// Records the health of the validator set of a multisig ISM, to be served
+    /// by the relayer's API
+    pub async fn record_quorum_health(
+        &self,
+        ism_address: H256,
+        validators: &[WeightedValidator],
+        threshold_weight: u128,
+        checkpoint_syncer: &MultisigCheckpointSyncer,
+        app_context: Option<String>,
+    ) {
+        let addresses = validators.iter().map(|v| v.validator).collect::<Vec<_>>();
+        let latest_indices = checkpoint_syncer
+            .get_validator_latest_indices(&addresses)
+            .await;
+        self.quorum_health.record(IsmQuorumHealth::new(
+            self.origin_domain.id(),
+            self.destination_domain().id(),
+            ism_address,
+            app_context,
+            validators,
+            threshold_weight,
+            &latest_indices,
+            &checkpoint_syncer.storage_locations(),
+            self.highest_known_leaf_index().await,
+        ));
+    }
 }
@@ -8,7 +8,7 @@ use ethers::abi::Token;
 use eyre::{Context, Result};
 use hyperlane_base::MultisigCheckpointSyncer;
 use hyperlane_core::accumulator::merkle::Proof;
-use hyperlane_core::{HyperlaneMessage, MultisigSignedCheckpoint, H256};
+use hyperlane_core::{HyperlaneMessage, MultisigSignedCheckpoint, WeightedValidator, H256};
 use strum::Display;
 use tracing::{debug, info};
 
@@ -38,10 +38,16 @@ pub enum MetadataToken {
 
 #[async_trait]
 pub trait MultisigIsmMetadataBuilder: AsRef<MessageMetadataBuilder> + Send + Sync {
+    /// Whether the ISM weighs the signatures of its validators, rather than
+    /// counting them
+    fn weighted(&self) -> bool;
+
+    /// Validators of threshold-count multisig ISMs each weigh 1, and their
+    /// threshold weight is the threshold
     async fn fetch_metadata(
         &self,
-        validators: &[H256],
-        threshold: u8,
+        validators: &[WeightedValidator],
+        threshold_weight: u128,
         message: &HyperlaneMessage,
         checkpoint_syncer: &MultisigCheckpointSyncer,
     ) -> Result<Option<MultisigMetadata>>;
@@ -105,32 +111,57 @@ impl<T: MultisigIsmMetadataBuilder> MetadataBuilder for T {
             .await
             .context(CTX)?;
 
-        let (validators, threshold) = multisig_ism
-            .validators_and_threshold(message)
-            .await
-            .context(CTX)?;
+        let (validators, threshold_weight) = if self.weighted() {
+            multisig_ism
+                .validators_and_threshold_weight(message)
+                .await
+                .context(CTX)?
+        } else {
+            let (validators, threshold) = multisig_ism
+                .validators_and_threshold(message)
+                .await
+                .context(CTX)?;
+            (
+                WeightedValidator::with_unit_weights(&validators),
+                threshold.into(),
+            )
+        };
 
         if validators.is_empty() {
             info!("Could not fetch metadata: No validator set found for ISM");
             return Ok(None);
         }
 
+        let addresses = validators.iter().map(|v| v.validator).collect::<Vec<_>>();
         let checkpoint_syncer = self
             .as_ref()
-            .build_checkpoint_syncer(&validators, self.as_ref().app_context.clone())
+            .build_checkpoint_syncer(&addresses, self.as_ref().app_context.clone())
             .await
             .context(CTX)?;
 
-        if let Some(metadata) = self
-            .fetch_metadata(&validators, threshold, message, &checkpoint_syncer)
-            .await
-            .context(CTX)?
-        {
+        let metadata = self
+            .fetch_metadata(&validators, threshold_weight, message, &checkpoint_syncer)
+            .await;
+
+        // The latest indices were just fetched, so they are served from the cache.
+        // The health is recorded even if fetching the metadata failed, since
+        // unreachable validators are what it's meant to surface.
+        self.as_ref()
+            .record_quorum_health(
+                ism_address,
+                &validators,
+                threshold_weight,
+                &checkpoint_syncer,
+                self.as_ref().app_context.clone(),
+            )
+            .await;
+
+        if let Some(metadata) = metadata.context(CTX)? {
             debug!(hyp_message=?message, ?metadata.checkpoint, "Found checkpoint with quorum");
             Ok(Some(self.format_metadata(metadata)?))
         } else {
             info!(
-                hyp_message=?message, ?validators, threshold, ism=%multisig_ism.address(),
+                hyp_message=?message, ?validators, %threshold_weight, ism=%multisig_ism.address(),
                 "Could not fetch metadata: Unable to reach quorum"
             );
             Ok(None)
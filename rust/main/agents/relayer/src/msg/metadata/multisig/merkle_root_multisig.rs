@@ -6,7 +6,7 @@ use derive_new::new;
 
 use eyre::{Context, Result};
 use hyperlane_base::MultisigCheckpointSyncer;
-use hyperlane_core::{unwrap_or_none_result, HyperlaneMessage, H256};
+use hyperlane_core::{unwrap_or_none_result, HyperlaneMessage, WeightedValidator};
 use tracing::debug;
 
 use crate::msg::metadata::MessageMetadataBuilder;
@@ -14,9 +14,20 @@ use crate::msg::metadata::MessageMetadataBuilder;
 use super::base::{MetadataToken, MultisigIsmMetadataBuilder, MultisigMetadata};
 
 #[derive(Debug, Clone, Deref, new, AsRef)]
-pub struct MerkleRootMultisigMetadataBuilder(MessageMetadataBuilder);
+pub struct MerkleRootMultisigMetadataBuilder {
+    #[deref]
+    #[as_ref]
+    base: MessageMetadataBuilder,
+    /// Whether the ISM weighs the signatures of its validators
+    weighted: bool,
+}
+
 #[async_trait]
 impl MultisigIsmMetadataBuilder for MerkleRootMultisigMetadataBuilder {
+    fn weighted(&self) -> bool {
+        self.weighted
+    }
+
     fn token_layout(&self) -> Vec<MetadataToken> {
         vec![
             MetadataToken::CheckpointMerkleTreeHook,
@@ -30,8 +41,8 @@ impl MultisigIsmMetadataBuilder for MerkleRootMultisigMetadataBuilder {
 
     async fn fetch_metadata(
         &self,
-        validators: &[H256],
-        threshold: u8,
+        validators: &[WeightedValidator],
+        threshold_weight: u128,
         message: &HyperlaneMessage,
         checkpoint_syncer: &MultisigCheckpointSyncer,
     ) -> Result<Option<MultisigMetadata>> {
@@ -53,7 +64,7 @@ impl MultisigIsmMetadataBuilder for MerkleRootMultisigMetadataBuilder {
             checkpoint_syncer
                 .fetch_checkpoint_in_range(
                     validators,
-                    threshold as usize,
+                    threshold_weight,
                     leaf_index,
                     highest_leaf_index,
                     self.origin_domain(),
@@ -6,7 +6,7 @@ use derive_new::new;
 
 use eyre::{Context, Result};
 use hyperlane_base::MultisigCheckpointSyncer;
-use hyperlane_core::{unwrap_or_none_result, HyperlaneMessage, H256};
+use hyperlane_core::{unwrap_or_none_result, HyperlaneMessage, WeightedValidator};
 use tracing::{debug, warn};
 
 use crate::msg::metadata::MessageMetadataBuilder;
@@ -14,10 +14,20 @@ use crate::msg::metadata::MessageMetadataBuilder;
 use super::base::{MetadataToken, MultisigIsmMetadataBuilder, MultisigMetadata};
 
 #[derive(Debug, Clone, Deref, new, AsRef)]
-pub struct MessageIdMultisigMetadataBuilder(MessageMetadataBuilder);
+pub struct MessageIdMultisigMetadataBuilder {
+    #[deref]
+    #[as_ref]
+    base: MessageMetadataBuilder,
+    /// Whether the ISM weighs the signatures of its validators
+    weighted: bool,
+}
 
 #[async_trait]
 impl MultisigIsmMetadataBuilder for MessageIdMultisigMetadataBuilder {
+    fn weighted(&self) -> bool {
+        self.weighted
+    }
+
     fn token_layout(&self) -> Vec<MetadataToken> {
         vec![
             MetadataToken::CheckpointMerkleTreeHook,
@@ -29,8 +39,8 @@ impl MultisigIsmMetadataBuilder for MessageIdMultisigMetadataBuilder {
 
     async fn fetch_metadata(
         &self,
-        validators: &[H256],
-        threshold: u8,
+        validators: &[WeightedValidator],
+        threshold_weight: u128,
         message: &HyperlaneMessage,
         checkpoint_syncer: &MultisigCheckpointSyncer,
     ) -> Result<Option<MultisigMetadata>> {
@@ -48,9 +58,10 @@ impl MultisigIsmMetadataBuilder for MessageIdMultisigMetadataBuilder {
         );
 
         // Update the validator latest checkpoint metrics.
+        let addresses = validators.iter().map(|v| v.validator).collect::<Vec<_>>();
         let _ = checkpoint_syncer
             .get_validator_latest_checkpoints_and_update_metrics(
-                validators,
+                &addresses,
                 self.origin_domain(),
                 self.destination_domain(),
             )
@@ -58,7 +69,7 @@ impl MultisigIsmMetadataBuilder for MessageIdMultisigMetadataBuilder {
 
         let quorum_checkpoint = unwrap_or_none_result!(
             checkpoint_syncer
-                .fetch_checkpoint(validators, threshold as usize, leaf_index)
+                .fetch_checkpoint(validators, threshold_weight, leaf_index)
                 .await
                 .context(CTX)?,
             debug!("No quorum checkpoint found")
@@ -28,11 +28,16 @@
 //!   switch everyone to new one)
 
 pub(crate) mod blacklist;
+pub(crate) mod events;
+pub(crate) mod filters;
 pub(crate) mod gas_payment;
 pub(crate) mod metadata;
 pub(crate) mod op_queue;
 pub(crate) mod op_submitter;
 pub(crate) mod pending_message;
 pub(crate) mod processor;
+pub(crate) mod profitability;
+pub(crate) mod quorum_health;
+pub(crate) mod rate_limiter;
 
 pub use gas_payment::GAS_EXPENDITURE_LOG_MESSAGE;
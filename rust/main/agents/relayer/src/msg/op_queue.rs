@@ -1,14 +1,83 @@
-use std::{cmp::Reverse, collections::BinaryHeap, sync::Arc};
+use std::{cmp::Reverse, collections::BinaryHeap, sync::Arc, time::Instant};
 
 use derive_new::new;
-use hyperlane_core::{PendingOperation, PendingOperationStatus, QueueOperation};
+use hyperlane_core::{OperationLane, PendingOperation, PendingOperationStatus, QueueOperation};
 use prometheus::{IntGauge, IntGaugeVec};
 use tokio::sync::{broadcast::Receiver, Mutex};
 use tracing::{debug, info, instrument};
 
 use crate::settings::matching_list::MatchingList;
 
-pub type OperationPriorityQueue = Arc<Mutex<BinaryHeap<Reverse<QueueOperation>>>>;
+pub type OperationPriorityQueue = Arc<Mutex<LaneHeaps>>;
+
+/// The operations of a queue, with a heap per lane so that either lane can be
+/// popped from without going through the operations of the other
+#[derive(Debug, Default)]
+pub struct LaneHeaps {
+    premium: BinaryHeap<Reverse<QueueOperation>>,
+    standard: BinaryHeap<Reverse<QueueOperation>>,
+}
+
+impl LaneHeaps {
+    /// Push an operation onto the heap of its lane
+    pub fn push(&mut self, op: Reverse<QueueOperation>) {
+        self.lane_mut(op.0.lane()).push(op);
+    }
+
+    /// Pop the next operation in queue order, regardless of its lane
+    pub fn pop(&mut self) -> Option<Reverse<QueueOperation>> {
+        let lane = match (self.premium.peek(), self.standard.peek()) {
+            (Some(premium), Some(standard)) if premium >= standard => OperationLane::Premium,
+            (Some(_), None) => OperationLane::Premium,
+            _ => OperationLane::Standard,
+        };
+        self.lane_mut(lane).pop()
+    }
+
+    /// Pop the next operation of `lane`, if it is ready to be attempted
+    fn pop_ready(&mut self, lane: OperationLane, now: Instant) -> Option<QueueOperation> {
+        let heap = self.lane_mut(lane);
+        // The heap is ordered by next attempt, so if the next operation isn't
+        // ready, none is
+        let Reverse(next) = heap.peek()?;
+        if next
+            .next_attempt_after()
+            .map_or(false, |next_attempt| next_attempt > now)
+        {
+            return None;
+        }
+        heap.pop().map(|Reverse(op)| op)
+    }
+
+    pub fn len(&self) -> usize {
+        self.premium.len() + self.standard.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Reverse<QueueOperation>> {
+        self.premium.iter().chain(self.standard.iter())
+    }
+
+    /// Keep only the operations for which `f` returns true
+    pub fn retain(&mut self, mut f: impl FnMut(&Reverse<QueueOperation>) -> bool) {
+        self.premium.retain(&mut f);
+        self.standard.retain(f);
+    }
+
+    fn lanes_mut(&mut self) -> [&mut BinaryHeap<Reverse<QueueOperation>>; 2] {
+        [&mut self.premium, &mut self.standard]
+    }
+
+    fn lane_mut(&mut self, lane: OperationLane) -> &mut BinaryHeap<Reverse<QueueOperation>> {
+        match lane {
+            OperationLane::Premium => &mut self.premium,
+            OperationLane::Standard => &mut self.standard,
+        }
+    }
+}
 
 /// Queue of generic operations that can be submitted to a destination chain.
 /// Includes logic for maintaining queue metrics by the destination and `app_context` of an operation
@@ -19,9 +88,24 @@ pub struct OpQueue {
     retry_rx: Arc<Mutex<Receiver<MatchingList>>>,
     #[new(default)]
     pub queue: OperationPriorityQueue,
+    /// If set, this many premium operations are popped for every standard
+    /// one while there are ready operations in both lanes
+    #[new(default)]
+    premium_weight: Option<u32>,
+    /// Premium operations popped since the last standard one
+    #[new(default)]
+    premium_streak: u32,
 }
 
 impl OpQueue {
+    /// Serve operations from priority lanes, see `premium_weight`
+    pub fn with_premium_weight(self, premium_weight: Option<u32>) -> Self {
+        Self {
+            premium_weight,
+            ..self
+        }
+    }
+
     /// Push an element onto the queue and update metrics
     /// Arguments:
     /// - `op`: the operation to push onto the queue
@@ -47,12 +131,17 @@ impl OpQueue {
     pub async fn pop_many(&mut self, limit: usize) -> Vec<QueueOperation> {
         self.process_retry_requests().await;
         let mut queue = self.queue.lock().await;
-        let mut popped = vec![];
-        while let Some(Reverse(op)) = queue.pop() {
-            popped.push(op);
-            if popped.len() >= limit {
-                break;
+        let mut popped = match self.premium_weight {
+            Some(premium_weight) => {
+                pop_by_lane(&mut queue, limit, premium_weight, &mut self.premium_streak)
             }
+            None => vec![],
+        };
+        while popped.len() < limit {
+            let Some(Reverse(op)) = queue.pop() else {
+                break;
+            };
+            popped.push(op);
         }
         // This function is called very often by the op_submitter tasks, so only log when there are operations to pop
         // to avoid spamming the logs
@@ -79,21 +168,23 @@ impl OpQueue {
             return;
         }
         let mut queue = self.queue.lock().await;
-        let mut reprioritized_queue: BinaryHeap<_> = queue
-            .drain()
-            .map(|Reverse(mut op)| {
-                if message_retry_requests.iter().any(|r| r.op_matches(&op)) {
-                    info!(
-                        operation = %op,
-                        queue_label = %self.queue_metrics_label,
-                        "Retrying OpQueue operation"
-                    );
-                    op.reset_attempts()
-                }
-                Reverse(op)
-            })
-            .collect();
-        queue.append(&mut reprioritized_queue);
+        for lane in queue.lanes_mut() {
+            let mut reprioritized_lane: BinaryHeap<_> = lane
+                .drain()
+                .map(|Reverse(mut op)| {
+                    if message_retry_requests.iter().any(|r| r.op_matches(&op)) {
+                        info!(
+                            operation = %op,
+                            queue_label = %self.queue_metrics_label,
+                            "Retrying OpQueue operation"
+                        );
+                        op.reset_attempts()
+                    }
+                    Reverse(op)
+                })
+                .collect();
+            lane.append(&mut reprioritized_lane);
+        }
     }
 
     /// Get the metric associated with this operation
@@ -113,13 +204,45 @@ impl OpQueue {
     }
 }
 
+/// Pops up to `limit` of the operations that are ready to be attempted,
+/// serving `premium_weight` premium operations for every standard one. Within
+/// a lane, operations are popped in queue order.
+fn pop_by_lane(
+    queue: &mut LaneHeaps,
+    limit: usize,
+    premium_weight: u32,
+    premium_streak: &mut u32,
+) -> Vec<QueueOperation> {
+    let now = Instant::now();
+    let mut popped = Vec::with_capacity(limit);
+    while popped.len() < limit {
+        let (lane, other_lane) = if *premium_streak < premium_weight {
+            (OperationLane::Premium, OperationLane::Standard)
+        } else {
+            (OperationLane::Standard, OperationLane::Premium)
+        };
+        let Some(op) = queue
+            .pop_ready(lane, now)
+            .or_else(|| queue.pop_ready(other_lane, now))
+        else {
+            break;
+        };
+        match op.lane() {
+            OperationLane::Premium => *premium_streak += 1,
+            OperationLane::Standard => *premium_streak = 0,
+        }
+        popped.push(op);
+    }
+    popped
+}
+
 #[cfg(test)]
 pub mod test {
     use super::*;
     use hyperlane_core::{
         HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneDomainTechnicalStack,
-        HyperlaneDomainType, HyperlaneMessage, KnownHyperlaneDomain, PendingOperationResult,
-        TryBatchAs, TxOutcome, H256, U256,
+        HyperlaneDomainType, HyperlaneMessage, KnownHyperlaneDomain, OperationLane,
+        PendingOperationResult, SubmitterQueue, TryBatchAs, TxOutcome, H256, U256,
     };
     use serde::Serialize;
     use std::{
@@ -136,8 +259,12 @@ pub mod test {
         origin_domain_id: u32,
         destination_domain_id: u32,
         recipient_address: H256,
+        body: Vec<u8>,
         seconds_to_next_attempt: u64,
         destination_domain: HyperlaneDomain,
+        tx_cost_estimate: Option<U256>,
+        lane: OperationLane,
+        restored_queue: Option<SubmitterQueue>,
     }
 
     impl MockPendingOperation {
@@ -149,7 +276,11 @@ pub mod test {
                 destination_domain,
                 sender_address: H256::random(),
                 recipient_address: H256::random(),
+                body: vec![],
                 origin_domain_id: 0,
+                tx_cost_estimate: None,
+                lane: OperationLane::Standard,
+                restored_queue: None,
             }
         }
 
@@ -158,6 +289,7 @@ pub mod test {
                 id: message.id(),
                 sender_address: message.sender,
                 recipient_address: message.recipient,
+                body: message.body,
                 origin_domain_id: message.origin,
                 destination_domain_id: message.destination,
                 seconds_to_next_attempt: 0,
@@ -168,6 +300,9 @@ pub mod test {
                     domain_protocol: HyperlaneDomainProtocol::Ethereum,
                     domain_technical_stack: HyperlaneDomainTechnicalStack::Other,
                 },
+                tx_cost_estimate: None,
+                lane: OperationLane::Standard,
+                restored_queue: None,
             }
         }
 
@@ -191,6 +326,24 @@ pub mod test {
                 ..self
             }
         }
+
+        pub fn with_lane(self, lane: OperationLane) -> Self {
+            Self { lane, ..self }
+        }
+
+        pub fn with_tx_cost_estimate(self, gas_limit: u64) -> Self {
+            Self {
+                tx_cost_estimate: Some(gas_limit.into()),
+                ..self
+            }
+        }
+
+        pub fn with_restored_queue(self, queue: SubmitterQueue) -> Self {
+            Self {
+                restored_queue: Some(queue),
+                ..self
+            }
+        }
     }
 
     impl TryBatchAs<HyperlaneMessage> for MockPendingOperation {}
@@ -220,6 +373,10 @@ pub mod test {
             &self.recipient_address
         }
 
+        fn body(&self) -> &[u8] {
+            &self.body
+        }
+
         fn get_metric(&self) -> Option<Arc<IntGauge>> {
             None
         }
@@ -265,7 +422,15 @@ pub mod test {
         }
 
         fn get_tx_cost_estimate(&self) -> Option<U256> {
-            todo!()
+            self.tx_cost_estimate
+        }
+
+        fn lane(&self) -> OperationLane {
+            self.lane
+        }
+
+        fn restored_queue(&self) -> Option<SubmitterQueue> {
+            self.restored_queue
         }
 
         /// This will be called after the operation has been submitted and is
@@ -447,4 +612,96 @@ pub mod test {
         assert_eq!(popped[3], op_ids[0]);
         assert_eq!(popped[4], op_ids[1]);
     }
+
+    async fn push_ops_in_lanes(
+        op_queue: &OpQueue,
+        lanes: &[OperationLane],
+        seconds_to_next_attempt: u64,
+    ) {
+        let destination_domain: HyperlaneDomain = KnownHyperlaneDomain::Injective.into();
+        for lane in lanes {
+            let op = MockPendingOperation::new(seconds_to_next_attempt, destination_domain.clone())
+                .with_lane(*lane);
+            op_queue
+                .push(
+                    Box::new(op) as QueueOperation,
+                    Some(PendingOperationStatus::FirstPrepareAttempt),
+                )
+                .await;
+        }
+    }
+
+    fn dummy_op_queue_with_premium_weight(premium_weight: u32) -> OpQueue {
+        let (metrics, queue_metrics_label) = dummy_metrics_and_label();
+        let broadcaster = sync::broadcast::Sender::new(100);
+        OpQueue::new(
+            metrics,
+            queue_metrics_label,
+            Arc::new(Mutex::new(broadcaster.subscribe())),
+        )
+        .with_premium_weight(Some(premium_weight))
+    }
+
+    #[tokio::test]
+    async fn test_premium_lane_is_weighted() {
+        use OperationLane::*;
+        let mut op_queue = dummy_op_queue_with_premium_weight(2);
+        push_ops_in_lanes(&op_queue, &[Standard, Standard, Standard], 0).await;
+        push_ops_in_lanes(&op_queue, &[Premium, Premium, Premium], 0).await;
+
+        let popped = op_queue.pop_many(6).await;
+        assert_eq!(
+            popped.iter().map(|op| op.lane()).collect::<Vec<_>>(),
+            vec![Premium, Premium, Standard, Premium, Standard, Standard]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_premium_streak_carries_over_pops() {
+        use OperationLane::*;
+        let mut op_queue = dummy_op_queue_with_premium_weight(1);
+        push_ops_in_lanes(&op_queue, &[Premium, Premium, Standard, Standard], 0).await;
+
+        let mut lanes = vec![];
+        while let Some(op) = op_queue.pop().await {
+            lanes.push(op.lane());
+        }
+        assert_eq!(lanes, vec![Premium, Standard, Premium, Standard]);
+    }
+
+    #[tokio::test]
+    async fn test_unweighted_queue_pops_lanes_in_queue_order() {
+        use OperationLane::*;
+        let (metrics, queue_metrics_label) = dummy_metrics_and_label();
+        let broadcaster = sync::broadcast::Sender::new(100);
+        let mut op_queue = OpQueue::new(
+            metrics,
+            queue_metrics_label,
+            Arc::new(Mutex::new(broadcaster.subscribe())),
+        );
+        push_ops_in_lanes(&op_queue, &[Premium], 3).await;
+        push_ops_in_lanes(&op_queue, &[Standard], 1).await;
+        push_ops_in_lanes(&op_queue, &[Premium], 2).await;
+
+        let popped = op_queue.pop_many(3).await;
+        assert_eq!(
+            popped.iter().map(|op| op.lane()).collect::<Vec<_>>(),
+            vec![Standard, Premium, Premium]
+        );
+        assert_eq!(op_queue.queue.lock().await.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_ready_standard_ops_come_before_waiting_premium_ops() {
+        use OperationLane::*;
+        let mut op_queue = dummy_op_queue_with_premium_weight(4);
+        push_ops_in_lanes(&op_queue, &[Premium], 100).await;
+        push_ops_in_lanes(&op_queue, &[Standard], 0).await;
+
+        let popped = op_queue.pop_many(2).await;
+        assert_eq!(
+            popped.iter().map(|op| op.lane()).collect::<Vec<_>>(),
+            vec![Standard, Premium]
+        );
+    }
 }
@@ -1,6 +1,7 @@
 #![allow(clippy::doc_markdown)] // TODO: `rustc` 1.80.1 clippy issue
 #![allow(clippy::doc_lazy_continuation)] // TODO: `rustc` 1.80.1 clippy issue
 
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -13,9 +14,10 @@ use hyperlane_core::ConfirmReason::*;
 use hyperlane_core::PendingOperation;
 use hyperlane_core::PendingOperationStatus;
 use hyperlane_core::ReprepareReason;
+use hyperlane_core::SubmitterQueue;
 use itertools::Either;
 use itertools::Itertools;
-use prometheus::{IntCounter, IntGaugeVec};
+use prometheus::{IntCounter, IntGauge, IntGaugeVec};
 use tokio::sync::broadcast::Sender;
 use tokio::sync::mpsc;
 use tokio::sync::Mutex;
@@ -25,10 +27,11 @@ use tokio_metrics::TaskMonitor;
 use tracing::{debug, info_span, instrument, instrument::Instrumented, trace, Instrument};
 use tracing::{info, warn};
 
+use hyperlane_base::db::{DbResult, HyperlaneRocksDB};
 use hyperlane_base::CoreMetrics;
 use hyperlane_core::{
-    ChainCommunicationError, ChainResult, HyperlaneDomain, HyperlaneDomainProtocol,
-    PendingOperationResult, QueueOperation, TxOutcome,
+    ChainCommunicationError, ChainResult, HyperlaneDomain, HyperlaneDomainProtocol, Mailbox,
+    PendingOperationResult, QueueOperation, TxOutcome, U256,
 };
 
 use crate::msg::pending_message::CONFIRM_DELAY;
@@ -37,6 +40,13 @@ use crate::settings::matching_list::MatchingList;
 use super::op_queue::OpQueue;
 use super::op_queue::OperationPriorityQueue;
 
+/// How often paused tasks check whether they were resumed
+const PAUSED_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long shutting down waits for the operations being processed to be back
+/// in their queues before taking the queue snapshots anyway
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// SerialSubmitter accepts operations over a channel. It is responsible for
 /// executing the right strategy to deliver those messages to the destination
 /// chain. It is designed to be used in a scenario allowing only one
@@ -94,32 +104,46 @@ pub struct SerialSubmitter {
     metrics: SerialSubmitterMetrics,
     /// Max batch size for submitting messages
     max_batch_size: u32,
+    /// Max estimated gas of the messages in a batch
+    max_batch_gas: Option<U256>,
     /// tokio task monitor
     task_monitor: TaskMonitor,
+    /// Whether the prepare, submit and confirm tasks are paused
+    pause: SubmitterPause,
+    /// Mailboxes of the destination's signer pool, one per signer. Operations
+    /// are spread across them when there is more than one.
+    submission_mailboxes: Vec<Arc<dyn Mailbox>>,
     prepare_queue: OpQueue,
     submit_queue: OpQueue,
     confirm_queue: OpQueue,
 }
 
 impl SerialSubmitter {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         domain: HyperlaneDomain,
         rx: mpsc::UnboundedReceiver<QueueOperation>,
         retry_op_transmitter: Sender<MatchingList>,
         metrics: SerialSubmitterMetrics,
         max_batch_size: u32,
+        max_batch_gas: Option<U256>,
+        premium_weight: Option<u32>,
         task_monitor: TaskMonitor,
+        pause: SubmitterPause,
     ) -> Self {
+        // Only the queues that compete for the submission slot have priority lanes
         let prepare_queue = OpQueue::new(
             metrics.submitter_queue_length.clone(),
             "prepare_queue".to_string(),
             Arc::new(Mutex::new(retry_op_transmitter.subscribe())),
-        );
+        )
+        .with_premium_weight(premium_weight);
         let submit_queue = OpQueue::new(
             metrics.submitter_queue_length.clone(),
             "submit_queue".to_string(),
             Arc::new(Mutex::new(retry_op_transmitter.subscribe())),
-        );
+        )
+        .with_premium_weight(premium_weight);
         let confirm_queue = OpQueue::new(
             metrics.submitter_queue_length.clone(),
             "confirm_queue".to_string(),
@@ -131,17 +155,39 @@ impl SerialSubmitter {
             rx,
             metrics,
             max_batch_size,
+            max_batch_gas,
             task_monitor,
+            pause,
+            submission_mailboxes: vec![],
             prepare_queue,
             submit_queue,
             confirm_queue,
         }
     }
 
+    /// Submit operations through these mailboxes, each of which sends from a
+    /// different signer, instead of through the destination's default mailbox
+    pub fn with_submission_mailboxes(mut self, mailboxes: Vec<Arc<dyn Mailbox>>) -> Self {
+        self.submission_mailboxes = mailboxes;
+        self
+    }
+
+    pub fn pause(&self) -> SubmitterPause {
+        self.pause.clone()
+    }
+
     pub async fn prepare_queue(&self) -> OperationPriorityQueue {
         self.prepare_queue.queue.clone()
     }
 
+    pub async fn submit_queue(&self) -> OperationPriorityQueue {
+        self.submit_queue.queue.clone()
+    }
+
+    pub async fn confirm_queue(&self) -> OperationPriorityQueue {
+        self.confirm_queue.queue.clone()
+    }
+
     pub fn spawn(self) -> Instrumented<JoinHandle<()>> {
         let span = info_span!("SerialSubmitter", destination=%self.domain);
         let task_monitor = self.task_monitor.clone();
@@ -157,7 +203,10 @@ impl SerialSubmitter {
             metrics,
             rx: rx_prepare,
             max_batch_size,
+            max_batch_gas,
             task_monitor,
+            pause,
+            submission_mailboxes,
             prepare_queue,
             submit_queue,
             confirm_queue,
@@ -166,7 +215,13 @@ impl SerialSubmitter {
         let tasks = [
             tokio::spawn(TaskMonitor::instrument(
                 &task_monitor,
-                receive_task(domain.clone(), rx_prepare, prepare_queue.clone()),
+                receive_task(
+                    domain.clone(),
+                    rx_prepare,
+                    prepare_queue.clone(),
+                    submit_queue.clone(),
+                    confirm_queue.clone(),
+                ),
             )),
             tokio::spawn(TaskMonitor::instrument(
                 &task_monitor,
@@ -177,6 +232,7 @@ impl SerialSubmitter {
                     confirm_queue.clone(),
                     max_batch_size,
                     metrics.clone(),
+                    pause.clone(),
                 ),
             )),
             tokio::spawn(TaskMonitor::instrument(
@@ -187,7 +243,10 @@ impl SerialSubmitter {
                     submit_queue,
                     confirm_queue.clone(),
                     max_batch_size,
+                    max_batch_gas,
+                    submission_mailboxes,
                     metrics.clone(),
+                    pause.clone(),
                 ),
             )),
             tokio::spawn(TaskMonitor::instrument(
@@ -198,6 +257,7 @@ impl SerialSubmitter {
                     confirm_queue,
                     max_batch_size,
                     metrics,
+                    pause,
                 ),
             )),
         ];
@@ -217,6 +277,8 @@ async fn receive_task(
     domain: HyperlaneDomain,
     mut rx: mpsc::UnboundedReceiver<QueueOperation>,
     prepare_queue: OpQueue,
+    submit_queue: OpQueue,
+    confirm_queue: OpQueue,
 ) {
     // Pull any messages sent to this submitter
     while let Some(op) = rx.recv().await {
@@ -224,6 +286,23 @@ async fn receive_task(
         // make sure things are getting wired up correctly; if this works in testing it
         // should also be valid in production.
         debug_assert_eq!(*op.destination_domain(), domain);
+        // Operations restored from a queue snapshot go back to the queue they were
+        // in, with the status they had there
+        match op.restored_queue() {
+            Some(SubmitterQueue::Prepare) => {
+                prepare_queue.push(op, None).await;
+                continue;
+            }
+            Some(SubmitterQueue::Submit) => {
+                submit_queue.push(op, None).await;
+                continue;
+            }
+            Some(SubmitterQueue::Confirm) => {
+                confirm_queue.push(op, None).await;
+                continue;
+            }
+            None => {}
+        }
         let status = op.retrieve_status_from_db().unwrap_or_else(|| {
             trace!(
                 ?op,
@@ -235,6 +314,35 @@ async fn receive_task(
     }
 }
 
+/// Persist a snapshot of every operation in the given queues, so that they are
+/// restored in the same queues when the relayer starts again.
+/// The submitters are paused first, and the snapshot is only taken once the
+/// operations they were processing are back in their queues or confirmed.
+/// Operations still being processed after `SHUTDOWN_DRAIN_TIMEOUT` aren't in
+/// any snapshot, and are picked up from the db on startup like any other.
+pub async fn persist_queue_snapshots(
+    pauses: &[SubmitterPause],
+    queues: &[(SubmitterQueue, OperationPriorityQueue)],
+) {
+    let drained = join_all(pauses.iter().map(SubmitterPause::pause_until_restart));
+    if tokio::time::timeout(SHUTDOWN_DRAIN_TIMEOUT, drained)
+        .await
+        .is_err()
+    {
+        warn!(
+            timeout = ?SHUTDOWN_DRAIN_TIMEOUT,
+            "Timed out waiting for the operations being processed, they are left out of the queue snapshots"
+        );
+    }
+    for (queue_kind, queue) in queues {
+        let queue = queue.lock().await;
+        for op in queue.iter() {
+            op.0.persist_queue_snapshot(*queue_kind);
+        }
+        info!(queue = %queue_kind, ops = queue.len(), "Persisted queue snapshot");
+    }
+}
+
 #[instrument(skip_all, fields(%domain))]
 async fn prepare_task(
     domain: HyperlaneDomain,
@@ -243,10 +351,16 @@ async fn prepare_task(
     confirm_queue: OpQueue,
     max_batch_size: u32,
     metrics: SerialSubmitterMetrics,
+    pause: SubmitterPause,
 ) {
     // Prepare at most `max_batch_size` ops at a time to avoid getting rate-limited
     let ops_to_prepare = max_batch_size as usize;
     loop {
+        let Some(_in_flight) = pause.start_work() else {
+            // operations stay in their queue until the submitter is resumed
+            sleep(PAUSED_POLL_INTERVAL).await;
+            continue;
+        };
         // Pop messages here according to the configured batch.
         let mut batch = prepare_queue.pop_many(ops_to_prepare).await;
         if batch.is_empty() {
@@ -310,36 +424,129 @@ async fn prepare_task(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 #[instrument(skip_all, fields(%domain))]
 async fn submit_task(
     domain: HyperlaneDomain,
-    mut prepare_queue: OpQueue,
+    prepare_queue: OpQueue,
     mut submit_queue: OpQueue,
-    mut confirm_queue: OpQueue,
+    confirm_queue: OpQueue,
     max_batch_size: u32,
+    max_batch_gas: Option<U256>,
+    submission_mailboxes: Vec<Arc<dyn Mailbox>>,
     metrics: SerialSubmitterMetrics,
+    pause: SubmitterPause,
 ) {
     let recv_limit = max_batch_size as usize;
     loop {
-        let mut batch = submit_queue.pop_many(recv_limit).await;
+        let Some(_in_flight) = pause.start_work() else {
+            sleep(PAUSED_POLL_INTERVAL).await;
+            continue;
+        };
+        let ops = submit_queue.pop_many(recv_limit).await;
+        if ops.is_empty() {
+            // The queue is empty, so give some time before checking again to prevent burning CPU
+            sleep(Duration::from_millis(100)).await;
+            continue;
+        }
 
-        match batch.len().cmp(&1) {
-            std::cmp::Ordering::Less => {
-                // The queue is empty, so give some time before checking again to prevent burning CPU
-                sleep(Duration::from_millis(100)).await;
-                continue;
-            }
-            std::cmp::Ordering::Equal => {
-                let op = batch.pop().unwrap();
-                submit_single_operation(op, &mut prepare_queue, &mut confirm_queue, &metrics).await;
+        if submission_mailboxes.len() < 2 {
+            submit_ops(
+                ops,
+                &domain,
+                max_batch_gas,
+                prepare_queue.clone(),
+                confirm_queue.clone(),
+                &metrics,
+            )
+            .await;
+            continue;
+        }
+
+        // Each signer submits its share of the operations in order, while the
+        // signers submit concurrently
+        let submissions = group_by_signer(ops, submission_mailboxes.len())
+            .into_iter()
+            .enumerate()
+            .filter(|(_, ops)| !ops.is_empty())
+            .map(|(signer, mut ops)| {
+                for op in ops.iter_mut() {
+                    op.set_submission_mailbox(submission_mailboxes[signer].clone());
+                }
+                submit_ops(
+                    ops,
+                    &domain,
+                    max_batch_gas,
+                    prepare_queue.clone(),
+                    confirm_queue.clone(),
+                    &metrics,
+                )
+            });
+        join_all(submissions).await;
+    }
+}
+
+async fn submit_ops(
+    ops: Vec<QueueOperation>,
+    domain: &HyperlaneDomain,
+    max_batch_gas: Option<U256>,
+    mut prepare_queue: OpQueue,
+    mut confirm_queue: OpQueue,
+    metrics: &SerialSubmitterMetrics,
+) {
+    for mut batch in split_by_gas_budget(ops, max_batch_gas) {
+        if batch.len() == 1 {
+            let op = batch.pop().unwrap();
+            submit_single_operation(op, &mut prepare_queue, &mut confirm_queue, metrics).await;
+        } else {
+            OperationBatch::new(batch, domain.clone())
+                .submit(&mut prepare_queue, &mut confirm_queue, metrics)
+                .await;
+        }
+    }
+}
+
+/// Groups the operations, in order, by the signer that submits them. All the
+/// operations of a message sender go to the same signer, so they are
+/// delivered in the order they were dispatched.
+fn group_by_signer(
+    operations: Vec<QueueOperation>,
+    signer_count: usize,
+) -> Vec<Vec<QueueOperation>> {
+    let mut groups: Vec<Vec<QueueOperation>> = (0..signer_count).map(|_| vec![]).collect();
+    for op in operations {
+        let key = op.sender_address().to_low_u64_be() ^ u64::from(op.origin_domain_id());
+        groups[(key % signer_count as u64) as usize].push(op);
+    }
+    groups
+}
+
+/// Splits the operations, in order, into batches whose total estimated gas
+/// fits in `max_batch_gas`. An operation that is over the budget on its own
+/// is submitted in a batch by itself.
+fn split_by_gas_budget(
+    operations: Vec<QueueOperation>,
+    max_batch_gas: Option<U256>,
+) -> Vec<Vec<QueueOperation>> {
+    let Some(max_batch_gas) = max_batch_gas else {
+        return vec![operations];
+    };
+    let mut batches: Vec<Vec<QueueOperation>> = vec![];
+    let mut batch_gas = U256::zero();
+    for op in operations {
+        let op_gas = op.get_tx_cost_estimate().unwrap_or_default();
+        match batches.last_mut() {
+            Some(batch) if batch_gas.saturating_add(op_gas) <= max_batch_gas => {
+                batch_gas = batch_gas.saturating_add(op_gas);
+                batch.push(op);
             }
-            std::cmp::Ordering::Greater => {
-                OperationBatch::new(batch, domain.clone())
-                    .submit(&mut prepare_queue, &mut confirm_queue, &metrics)
-                    .await;
+            _ => {
+                batch_gas = op_gas;
+                batches.push(vec![op]);
             }
         }
     }
+    batches
 }
 
 #[instrument(skip(prepare_queue, confirm_queue, metrics), ret, level = "debug")]
@@ -409,9 +616,14 @@ async fn confirm_task(
     mut confirm_queue: OpQueue,
     max_batch_size: u32,
     metrics: SerialSubmitterMetrics,
+    pause: SubmitterPause,
 ) {
     let recv_limit = max_batch_size as usize;
     loop {
+        let Some(_in_flight) = pause.start_work() else {
+            sleep(PAUSED_POLL_INTERVAL).await;
+            continue;
+        };
         // Pick the next message to try confirming.
         let batch = confirm_queue.pop_many(recv_limit).await;
 
@@ -484,6 +696,86 @@ async fn confirm_operation(
     operation_result
 }
 
+/// Pauses the prepare, submit and confirm tasks of a `SerialSubmitter`, e.g. while
+/// its destination chain is halted. Operations keep being received and stay in their
+/// queues while paused. The state is persisted in the destination's db, so it
+/// survives restarts.
+#[derive(Debug, Clone)]
+pub struct SubmitterPause {
+    paused: Arc<AtomicBool>,
+    /// How many tasks are processing operations they popped from their queue
+    in_flight: Arc<AtomicUsize>,
+    db: HyperlaneRocksDB,
+    metric: IntGauge,
+}
+
+/// Marks a task of a `SerialSubmitter` as processing operations it popped from
+/// its queue, until it is dropped.
+#[derive(Debug)]
+struct InFlight(Arc<AtomicUsize>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl SubmitterPause {
+    pub fn new(db: HyperlaneRocksDB, metrics: &CoreMetrics) -> Self {
+        let paused = db
+            .retrieve_submitter_paused()
+            .unwrap_or_else(|err| {
+                warn!(?err, domain = %db.domain(), "Failed to read submitter pause state, assuming not paused");
+                None
+            })
+            .unwrap_or(false);
+        let metric = metrics
+            .submitter_paused()
+            .with_label_values(&[db.domain().name()]);
+        metric.set(paused as i64);
+        if paused {
+            warn!(domain = %db.domain(), "Submitter is paused");
+        }
+        Self {
+            paused: Arc::new(AtomicBool::new(paused)),
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            db,
+            metric,
+        }
+    }
+
+    /// Marks a task as processing operations, unless the submitter is paused
+    fn start_work(&self) -> Option<InFlight> {
+        // Counting the task in before checking the pause ensures that
+        // `pause_until_restart` either sees it or is seen by it
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let in_flight = InFlight(self.in_flight.clone());
+        (!self.paused.load(Ordering::SeqCst)).then_some(in_flight)
+    }
+
+    /// Pause the submitter without persisting it, so it runs again after a
+    /// restart, and wait until none of its tasks is processing operations
+    pub async fn pause_until_restart(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+        while self.in_flight.load(Ordering::SeqCst) > 0 {
+            sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Pause or resume the submitter, persisting the new state first
+    pub fn set_paused(&self, paused: bool) -> DbResult<()> {
+        self.db.store_submitter_paused(&paused)?;
+        self.paused.store(paused, Ordering::Relaxed);
+        self.metric.set(paused as i64);
+        info!(domain = %self.db.domain(), paused, "Updated submitter pause state");
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SerialSubmitterMetrics {
     submitter_queue_length: IntGaugeVec,
@@ -526,27 +818,60 @@ struct OperationBatch {
 }
 
 impl OperationBatch {
+    /// Submits the operations as a batch. If submitting the batch fails, it
+    /// is bisected until the operations that are to blame are submitted on
+    /// their own. Operations that would revert within the batch, or that are
+    /// sent to a destination that doesn't support batching, are submitted
+    /// serially right away.
     async fn submit(
         self,
         prepare_queue: &mut OpQueue,
         confirm_queue: &mut OpQueue,
         metrics: &SerialSubmitterMetrics,
     ) {
-        let excluded_ops = match self.try_submit_as_batch(metrics).await {
-            Ok(batch_result) => {
-                Self::handle_batch_result(self.operations, batch_result, confirm_queue).await
-            }
-            Err(e) => {
-                warn!(error=?e, batch=?self.operations, "Error when submitting batch");
-                self.operations
+        // Batches that are yet to be submitted, the next one last
+        let mut pending = vec![self.operations];
+        while let Some(mut operations) = pending.pop() {
+            if operations.len() == 1 {
+                let op = operations.pop().unwrap();
+                submit_single_operation(op, prepare_queue, confirm_queue, metrics).await;
+                continue;
             }
-        };
 
-        if !excluded_ops.is_empty() {
-            warn!(excluded_ops=?excluded_ops, "Either the batch tx would revert, or the operations would revert in the batch. Falling back to serial submission.");
-            OperationBatch::new(excluded_ops, self.domain)
-                .submit_serially(prepare_queue, confirm_queue, metrics)
-                .await;
+            let batch = OperationBatch::new(operations, self.domain.clone());
+            let batch_result = match batch.try_submit_as_batch(metrics).await {
+                Ok(batch_result) => batch_result,
+                Err(ChainCommunicationError::BatchingFailed) => {
+                    // Smaller batches would fail the same way
+                    warn!(batch=?batch.operations, "Batching isn't supported. Falling back to serial submission.");
+                    batch
+                        .submit_serially(prepare_queue, confirm_queue, metrics)
+                        .await;
+                    continue;
+                }
+                Err(e) => {
+                    warn!(error=?e, batch=?batch.operations, "Error when submitting batch");
+                    let mut first_half = batch.operations;
+                    let second_half = first_half.split_off(first_half.len() / 2);
+                    debug!(
+                        first_half = first_half.len(),
+                        second_half = second_half.len(),
+                        "Batch could not be sent, bisecting it"
+                    );
+                    pending.push(second_half);
+                    pending.push(first_half);
+                    continue;
+                }
+            };
+
+            let excluded_ops =
+                Self::handle_batch_result(batch.operations, batch_result, confirm_queue).await;
+            if !excluded_ops.is_empty() {
+                warn!(excluded_ops=?excluded_ops, "Either the batch tx would revert, or the operations would revert in the batch. Falling back to serial submission.");
+                OperationBatch::new(excluded_ops, self.domain.clone())
+                    .submit_serially(prepare_queue, confirm_queue, metrics)
+                    .await;
+            }
         }
     }
 
@@ -555,8 +880,8 @@ impl OperationBatch {
         &self,
         metrics: &SerialSubmitterMetrics,
     ) -> ChainResult<BatchResult> {
-        // We already assume that the relayer submits to a single mailbox per destination.
-        // So it's fine to use the first item in the batch to get the mailbox.
+        // All the operations of a batch are submitted by the same signer, so it's
+        // fine to use the first item in the batch to get the mailbox.
         let Some(first_item) = self.operations.first() else {
             return Err(ChainCommunicationError::BatchIsEmpty);
         };
@@ -621,3 +946,184 @@ impl OperationBatch {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use hyperlane_core::{KnownHyperlaneDomain, H256};
+
+    use super::*;
+    use crate::msg::op_queue::test::MockPendingOperation;
+
+    fn ops_with_gas(gas_limits: &[u64]) -> Vec<QueueOperation> {
+        let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum);
+        gas_limits
+            .iter()
+            .map(|gas_limit| {
+                Box::new(
+                    MockPendingOperation::new(0, domain.clone()).with_tx_cost_estimate(*gas_limit),
+                ) as QueueOperation
+            })
+            .collect()
+    }
+
+    fn batch_gas_limits(batches: &[Vec<QueueOperation>]) -> Vec<Vec<u64>> {
+        batches
+            .iter()
+            .map(|batch| {
+                batch
+                    .iter()
+                    .map(|op| op.get_tx_cost_estimate().unwrap().as_u64())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_receive_task_restores_ops_in_their_queue() {
+        let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum);
+        let (metrics, label) = crate::msg::op_queue::test::dummy_metrics_and_label();
+        let broadcaster = tokio::sync::broadcast::Sender::new(100);
+        let [prepare_queue, submit_queue, confirm_queue] = [0; 3].map(|_| {
+            OpQueue::new(
+                metrics.clone(),
+                label.clone(),
+                Arc::new(Mutex::new(broadcaster.subscribe())),
+            )
+        });
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        for queue in [
+            SubmitterQueue::Confirm,
+            SubmitterQueue::Submit,
+            SubmitterQueue::Confirm,
+        ] {
+            let op = MockPendingOperation::new(0, domain.clone()).with_restored_queue(queue);
+            tx.send(Box::new(op) as QueueOperation).unwrap();
+        }
+        drop(tx);
+        receive_task(
+            domain,
+            rx,
+            prepare_queue.clone(),
+            submit_queue.clone(),
+            confirm_queue.clone(),
+        )
+        .await;
+
+        assert_eq!(prepare_queue.queue.lock().await.len(), 0);
+        assert_eq!(submit_queue.queue.lock().await.len(), 1);
+        assert_eq!(confirm_queue.queue.lock().await.len(), 2);
+    }
+
+    #[test]
+    fn test_split_by_gas_budget() {
+        let ops = ops_with_gas(&[400, 300, 300, 200, 900, 100]);
+        let ids = ops.iter().map(|op| op.id()).collect_vec();
+        let batches = split_by_gas_budget(ops, Some(1_000.into()));
+        assert_eq!(
+            batch_gas_limits(&batches),
+            vec![vec![400, 300, 300], vec![200], vec![900, 100]]
+        );
+        // The order of the operations is kept
+        assert_eq!(
+            batches.iter().flatten().map(|op| op.id()).collect_vec(),
+            ids
+        );
+    }
+
+    #[test]
+    fn test_split_by_gas_budget_isolates_ops_over_budget() {
+        let batches = split_by_gas_budget(ops_with_gas(&[100, 1_500, 200]), Some(1_000.into()));
+        assert_eq!(
+            batch_gas_limits(&batches),
+            vec![vec![100], vec![1_500], vec![200]]
+        );
+    }
+
+    #[test]
+    fn test_split_without_gas_budget() {
+        let batches = split_by_gas_budget(ops_with_gas(&[400, 800, 900]), None);
+        assert_eq!(batch_gas_limits(&batches), vec![vec![400, 800, 900]]);
+    }
+
+    #[test]
+    fn test_group_by_signer_keeps_sender_order() {
+        let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum);
+        let senders = [
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+            "0x0000000000000000000000000000000000000000000000000000000000000002",
+            "0x0000000000000000000000000000000000000000000000000000000000000003",
+        ];
+        let ops = (0..12)
+            .map(|i| {
+                Box::new(
+                    MockPendingOperation::new(0, domain.clone())
+                        .with_sender_address(senders[i % senders.len()]),
+                ) as QueueOperation
+            })
+            .collect_vec();
+        let ids = ops.iter().map(|op| op.id()).collect_vec();
+
+        let groups = group_by_signer(ops, 2);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups.iter().map(Vec::len).sum::<usize>(), ids.len());
+        for (i, sender) in senders.iter().enumerate() {
+            let sender = H256::from_str(sender).unwrap();
+            // All the operations of a sender are submitted by a single signer
+            let signers = groups
+                .iter()
+                .positions(|group| group.iter().any(|op| op.sender_address() == &sender))
+                .collect_vec();
+            assert_eq!(signers.len(), 1);
+            // ...in the order they were popped
+            let sender_ids = groups[signers[0]]
+                .iter()
+                .filter(|op| op.sender_address() == &sender)
+                .map(|op| op.id())
+                .collect_vec();
+            let expected_ids = ids
+                .iter()
+                .skip(i)
+                .step_by(senders.len())
+                .copied()
+                .collect_vec();
+            assert_eq!(sender_ids, expected_ids);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pause_until_restart_waits_for_in_flight_operations() {
+        use hyperlane_base::db::test_utils;
+        use prometheus::Registry;
+
+        test_utils::run_test_db(|db| async move {
+            let domain = HyperlaneDomain::new_test_domain("submitter_pause");
+            let db = HyperlaneRocksDB::new(&domain, db);
+            let metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+            let pause = SubmitterPause::new(db.clone(), &metrics);
+
+            let in_flight = pause.start_work().unwrap();
+            let drained = pause.pause_until_restart();
+            tokio::pin!(drained);
+            assert!(
+                tokio::time::timeout(Duration::from_millis(300), &mut drained)
+                    .await
+                    .is_err(),
+                "Pausing should wait for the task processing operations"
+            );
+            // Tasks that didn't start processing operations yet stay idle
+            assert!(pause.start_work().is_none());
+
+            drop(in_flight);
+            tokio::time::timeout(Duration::from_secs(1), drained)
+                .await
+                .unwrap();
+            assert!(pause.is_paused());
+            // The pause only lasts until the relayer restarts
+            assert_eq!(db.retrieve_submitter_paused().unwrap(), None);
+        })
+        .await;
+    }
+}
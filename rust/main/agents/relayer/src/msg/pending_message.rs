@@ -3,30 +3,35 @@
 use std::{
     fmt::{Debug, Formatter},
     sync::Arc,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use async_trait::async_trait;
 use derive_new::new;
+use ethers::utils::hex;
 use eyre::Result;
 use hyperlane_base::{
-    db::{HyperlaneDb, HyperlaneRocksDB},
+    db::{DeadLetteredMessage, HyperlaneDb, HyperlaneRocksDB, QueuedMessageSnapshot, SubmittedTx},
     CoreMetrics,
 };
 use hyperlane_core::{
     gas_used_by_operation, BatchItem, ChainCommunicationError, ChainResult, ConfirmReason,
-    HyperlaneChain, HyperlaneDomain, HyperlaneMessage, Mailbox, MessageSubmissionData,
-    PendingOperation, PendingOperationResult, PendingOperationStatus, ReprepareReason, TryBatchAs,
-    TxOutcome, H256, U256,
+    FixedPointNumber, GasPaymentKey, HyperlaneChain, HyperlaneDomain, HyperlaneMessage, Mailbox,
+    MessageSubmissionData, OperationLane, PendingOperation, PendingOperationResult,
+    PendingOperationStatus, ReprepareReason, SubmitterQueue, TryBatchAs, TxOutcome, H256, U256,
 };
 use prometheus::{IntCounter, IntGauge};
 use serde::Serialize;
 use tracing::{debug, error, info, info_span, instrument, trace, warn, Instrument};
 
 use super::{
+    events::{OperationEventKind, OperationEvents},
     gas_payment::{GasPaymentEnforcer, GasPolicyStatus},
     metadata::{BaseMetadataBuilder, MessageMetadataBuilder, MetadataBuilder},
+    profitability::ProfitabilityLedger,
+    rate_limiter::RateLimiter,
 };
+use crate::settings::{DeadLetterConf, PriorityLaneConf};
 
 pub const CONFIRM_DELAY: Duration = if cfg!(any(test, feature = "test-utils")) {
     // Wait 5 seconds after submitting the message before confirming in test mode
@@ -52,6 +57,22 @@ pub struct MessageContext {
     /// Hard limit on transaction gas when submitting a transaction to the
     /// destination.
     pub transaction_gas_limit: Option<U256>,
+    /// If true, messages are prepared but only logged instead of being
+    /// submitted.
+    pub dry_run: bool,
+    /// When messages that keep failing should be moved to the dead-letter
+    /// queue instead of being retried.
+    pub dead_letter: DeadLetterConf,
+    /// Limits how quickly the messages of a sender or app from the origin are
+    /// relayed.
+    pub rate_limiter: Arc<RateLimiter>,
+    /// Records the gas payments received and the gas spent per route.
+    pub profitability_ledger: Arc<ProfitabilityLedger>,
+    /// Which messages are served from the premium lane of the destination's
+    /// submission queues, if it has priority lanes.
+    pub priority_lane: Option<PriorityLaneConf>,
+    /// Where the lifecycle events of the messages are published.
+    pub events: OperationEvents,
     pub metrics: MessageSubmissionMetrics,
 }
 
@@ -64,7 +85,16 @@ pub struct PendingMessage {
     status: PendingOperationStatus,
     app_context: Option<String>,
     #[new(default)]
+    lane: OperationLane,
+    /// Whether the message was requested to be relayed ahead of the others
+    #[new(default)]
+    expedited: bool,
+    #[new(default)]
     submitted: bool,
+    /// Whether the message already took a token from its rate limit buckets
+    #[new(default)]
+    #[serde(skip_serializing)]
+    rate_limit_passed: bool,
     #[new(default)]
     #[serde(skip_serializing)]
     submission_data: Option<Box<MessageSubmissionData>>,
@@ -85,6 +115,15 @@ pub struct PendingMessage {
     #[new(default)]
     #[serde(skip_serializing)]
     metric: Option<Arc<IntGauge>>,
+    /// Overrides the destination mailbox of the context when submitting
+    #[new(default)]
+    #[serde(skip_serializing)]
+    submission_mailbox: Option<Arc<dyn Mailbox>>,
+    /// The queue the message was in before the relayer restarted, if it was
+    /// restored from a snapshot
+    #[new(default)]
+    #[serde(skip_serializing)]
+    restored_queue: Option<SubmitterQueue>,
 }
 
 impl Debug for PendingMessage {
@@ -152,6 +191,13 @@ impl PendingOperation for PendingMessage {
         {
             warn!(message_id = ?self.message.id(), err = %e, status = %self.status, "Persisting `status` failed for message");
         }
+        self.ctx.events.publish(
+            &self.message,
+            self.app_context.as_deref(),
+            OperationEventKind::StatusChanged {
+                status: status.clone(),
+            },
+        );
         self.status = status;
     }
 
@@ -159,6 +205,14 @@ impl PendingOperation for PendingMessage {
         self.message.nonce
     }
 
+    fn lane(&self) -> OperationLane {
+        self.lane
+    }
+
+    fn is_expedited(&self) -> bool {
+        self.expedited
+    }
+
     fn origin_domain_id(&self) -> u32 {
         self.message.origin
     }
@@ -175,6 +229,10 @@ impl PendingOperation for PendingMessage {
         &self.message.recipient
     }
 
+    fn body(&self) -> &[u8] {
+        &self.message.body
+    }
+
     fn retrieve_status_from_db(&self) -> Option<PendingOperationStatus> {
         match self.ctx.origin_db.retrieve_status_by_message_id(&self.id()) {
             Ok(status) => status,
@@ -217,6 +275,27 @@ impl PendingOperation for PendingMessage {
             return PendingOperationResult::Confirm(ConfirmReason::AlreadySubmitted);
         }
 
+        // Checked after the delivery status, so messages that were already
+        // delivered don't use up the tokens of their sender
+        if !self.rate_limit_passed {
+            if let Err(wait) = self
+                .ctx
+                .rate_limiter
+                .try_acquire(&self.message, self.app_context.as_deref())
+            {
+                // Being rate limited isn't a failure of the message, so it
+                // neither counts as a retry nor as a failed operation
+                debug!(?wait, "Message is rate limited");
+                let rate_limited = PendingOperationStatus::Retry(ReprepareReason::RateLimited);
+                if self.status != rate_limited {
+                    self.set_status(rate_limited);
+                }
+                self.set_next_attempt_after(wait);
+                return PendingOperationResult::NotReady;
+            }
+            self.rate_limit_passed = true;
+        }
+
         let provider = self.ctx.destination_mailbox.provider();
 
         // We cannot deliver to an address that is not a contract so check and drop if it isn't.
@@ -331,6 +410,7 @@ impl PendingOperation for PendingMessage {
             }
         }
 
+        self.lane = self.classify_lane(Some(gas_limit));
         self.submission_data = Some(Box::new(MessageSubmissionData {
             metadata,
             gas_limit,
@@ -351,23 +431,36 @@ impl PendingOperation for PendingMessage {
             .expect("Pending message must be prepared before it can be submitted");
 
         // To avoid spending gas on a tx that will revert, dry-run just before submitting.
+        let mut tx_cost_estimate = None;
         if let Some(metadata) = self.metadata.as_ref() {
-            if self
-                .ctx
-                .destination_mailbox
+            match self
+                .submission_mailbox()
                 .process_estimate_costs(&self.message, metadata)
                 .await
-                .is_err()
             {
-                return self.on_reprepare::<String>(None, ReprepareReason::ErrorEstimatingGas);
+                Ok(estimate) => tx_cost_estimate = Some(estimate),
+                Err(_) => {
+                    return self.on_reprepare::<String>(None, ReprepareReason::ErrorEstimatingGas);
+                }
             }
         }
 
+        if self.ctx.dry_run {
+            info!(
+                gas_limit = ?state.gas_limit,
+                ?tx_cost_estimate,
+                metadata = %hex::encode(&state.metadata),
+                hyp_message = %self.message,
+                "Dry run, not submitting message"
+            );
+            self.ctx.metrics.messages_dry_run.inc();
+            return PendingOperationResult::Drop;
+        }
+
         // We use the estimated gas limit from the prior call to
         // `process_estimate_costs` to avoid a second gas estimation.
         let tx_outcome = self
-            .ctx
-            .destination_mailbox
+            .submission_mailbox()
             .process(&self.message, &state.metadata, Some(state.gas_limit))
             .await;
         match tx_outcome {
@@ -416,6 +509,14 @@ impl PendingOperation for PendingMessage {
                 submission=?self.submission_outcome,
                 "Message successfully processed"
             );
+            self.ctx.events.publish(
+                &self.message,
+                self.app_context.as_deref(),
+                OperationEventKind::Confirmed {
+                    tx_hash: self.submission_outcome.as_ref().map(|o| o.transaction_id),
+                    gas_used: self.submission_outcome.as_ref().map(|o| o.gas_used),
+                },
+            );
             PendingOperationResult::Success
         } else {
             let span = info_span!(
@@ -462,6 +563,16 @@ impl PendingOperation for PendingMessage {
         {
             error!(error=?e, "Error when recording tx outcome");
         }
+        self.ctx.profitability_ledger.record_gas_spent(
+            &self.message,
+            self.app_context.as_deref(),
+            &operation_outcome,
+        );
+        self.ctx.events.publish_submitted(
+            &self.message,
+            self.app_context.as_deref(),
+            &operation_outcome,
+        );
         // set the outcome in `Self` as well, for later logging
         self.set_submission_outcome(operation_outcome);
         debug!(
@@ -490,7 +601,11 @@ impl PendingOperation for PendingMessage {
     }
 
     fn try_get_mailbox(&self) -> Option<Arc<dyn Mailbox>> {
-        Some(self.ctx.destination_mailbox.clone())
+        Some(self.submission_mailbox().clone())
+    }
+
+    fn set_submission_mailbox(&mut self, mailbox: Arc<dyn Mailbox>) {
+        self.submission_mailbox = Some(mailbox);
     }
 
     fn get_metric(&self) -> Option<Arc<IntGauge>> {
@@ -500,6 +615,50 @@ impl PendingOperation for PendingMessage {
     fn set_metric(&mut self, metric: Arc<IntGauge>) {
         self.metric = Some(metric);
     }
+
+    fn persist_queue_snapshot(&self, queue: SubmitterQueue) {
+        let now = Instant::now();
+        let snapshot = QueuedMessageSnapshot {
+            message_id: self.message.id(),
+            queue,
+            status: self.status.clone(),
+            app_context: self.app_context.clone(),
+            lane: self.lane,
+            expedited: self.expedited,
+            num_retries: self.num_retries,
+            next_attempt_in_secs: self
+                .next_attempt_after
+                .map(|a| a.saturating_duration_since(now).as_secs()),
+            submission_data: self
+                .submission_data
+                .as_ref()
+                .map(|d| (d.metadata.clone(), d.gas_limit)),
+            submitted_tx: self
+                .submission_outcome
+                .as_ref()
+                .filter(|_| self.submitted)
+                .map(|o| SubmittedTx {
+                    tx_hash: o.transaction_id,
+                    executed: o.executed,
+                    gas_used: o.gas_used,
+                }),
+            snapshot_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        };
+        if let Err(e) = self
+            .ctx
+            .origin_db
+            .store_queued_message_snapshot_by_id(&snapshot.message_id, &snapshot)
+        {
+            warn!(message_id = ?snapshot.message_id, err = %e, %queue, "Persisting queue snapshot failed for message");
+        }
+    }
+
+    fn restored_queue(&self) -> Option<SubmitterQueue> {
+        self.restored_queue
+    }
 }
 
 impl PendingMessage {
@@ -517,6 +676,7 @@ impl PendingMessage {
             PendingOperationStatus::FirstPrepareAttempt,
             app_context,
         );
+        pm.lane = pm.classify_lane(None);
         match pm
             .ctx
             .origin_db
@@ -535,6 +695,52 @@ impl PendingMessage {
         pm
     }
 
+    /// Constructor that restores the message in the state it was in the
+    /// submission queues before the relayer shut down, so that it doesn't have
+    /// to be prepared or submitted again.
+    pub fn from_queue_snapshot(
+        message: HyperlaneMessage,
+        ctx: Arc<MessageContext>,
+        snapshot: QueuedMessageSnapshot,
+    ) -> Self {
+        let mut pm = Self::new(message, ctx, snapshot.status, snapshot.app_context);
+        pm.lane = snapshot.lane;
+        pm.expedited = snapshot.expedited;
+        pm.num_retries = snapshot.num_retries;
+        pm.next_attempt_after = snapshot
+            .next_attempt_in_secs
+            .map(|secs| Instant::now() + Duration::from_secs(secs));
+        if let Some((metadata, gas_limit)) = snapshot.submission_data {
+            // The message was prepared, so it already passed the rate limiter
+            pm.rate_limit_passed = true;
+            pm.metadata = Some(metadata.clone());
+            pm.submission_data = Some(Box::new(MessageSubmissionData {
+                metadata,
+                gas_limit,
+            }));
+        }
+        if let Some(tx) = snapshot.submitted_tx {
+            pm.submitted = true;
+            pm.submission_outcome = Some(TxOutcome {
+                transaction_id: tx.tx_hash,
+                executed: tx.executed,
+                gas_used: tx.gas_used,
+                // Only used for logging, and not worth persisting
+                gas_price: FixedPointNumber::zero(),
+            });
+        }
+        pm.restored_queue = Some(snapshot.queue);
+        pm
+    }
+
+    /// Relay the message ahead of the others, skipping the backoff of its
+    /// previous attempts
+    pub fn expedite(mut self) -> Self {
+        self.expedited = true;
+        self.next_attempt_after = None;
+        self
+    }
+
     fn on_reprepare<E: Debug>(
         &mut self,
         err: Option<E>,
@@ -547,9 +753,51 @@ impl PendingMessage {
         } else {
             warn!("Repreparing message: {}", reason.clone());
         }
+        if self
+            .ctx
+            .dead_letter
+            .should_dead_letter(self.num_retries, &reason)
+            && self.dead_letter(reason.clone())
+        {
+            return PendingOperationResult::Drop;
+        }
         PendingOperationResult::Reprepare(reason)
     }
 
+    /// Move the message to the dead-letter queue of the origin db, where it stays
+    /// until it is requeued or dropped through the relayer API. Returns whether the
+    /// message was dead-lettered.
+    fn dead_letter(&self, reason: ReprepareReason) -> bool {
+        let dead_lettered_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let entry = DeadLetteredMessage {
+            message_id: self.message.id(),
+            origin: self.message.origin,
+            destination: self.message.destination,
+            nonce: self.message.nonce,
+            num_retries: self.num_retries,
+            reason,
+            dead_lettered_at,
+            dropped_at: None,
+        };
+        match self
+            .ctx
+            .origin_db
+            .store_dead_lettered_message_by_id(&entry.message_id, &entry)
+        {
+            Ok(()) => {
+                warn!(id = ?entry.message_id, num_retries = entry.num_retries, reason = %entry.reason, "Moving message to dead-letter queue");
+                true
+            }
+            Err(e) => {
+                warn!(id = ?entry.message_id, err = %e, "Failed to dead-letter message, will keep retrying it");
+                false
+            }
+        }
+    }
+
     fn on_reconfirm<E: Debug>(&mut self, err: Option<E>, reason: &str) -> PendingOperationResult {
         self.inc_attempts();
         if let Some(e) = err {
@@ -560,6 +808,54 @@ impl PendingMessage {
         PendingOperationResult::NotReady
     }
 
+    /// Messages are premium if their app context is, or if they paid for
+    /// enough gas beyond the `gas_limit` they are submitted with. Until the
+    /// gas limit is estimated, only the app context is considered.
+    fn classify_lane(&self, gas_limit: Option<U256>) -> OperationLane {
+        let Some(conf) = &self.ctx.priority_lane else {
+            return OperationLane::Standard;
+        };
+        if self.app_context.as_ref().map_or(false, |app_context| {
+            conf.premium_app_contexts.contains(app_context)
+        }) {
+            return OperationLane::Premium;
+        }
+        let (Some(min_gas_overpayment), Some(gas_limit)) = (conf.min_gas_overpayment, gas_limit)
+        else {
+            return OperationLane::Standard;
+        };
+        let payment = self
+            .ctx
+            .origin_db
+            .retrieve_gas_payment_by_gas_payment_key(GasPaymentKey {
+                message_id: self.message.id(),
+                destination: self.message.destination,
+            });
+        match payment {
+            Ok(Some(payment))
+                if !gas_limit.is_zero()
+                    && payment.gas_amount.to_f64_lossy()
+                        >= gas_limit.to_f64_lossy() * min_gas_overpayment =>
+            {
+                OperationLane::Premium
+            }
+            Ok(_) => OperationLane::Standard,
+            Err(err) => {
+                warn!(
+                    ?err,
+                    "Failed to retrieve gas payment to classify message lane"
+                );
+                OperationLane::Standard
+            }
+        }
+    }
+
+    fn submission_mailbox(&self) -> &Arc<dyn Mailbox> {
+        self.submission_mailbox
+            .as_ref()
+            .unwrap_or(&self.ctx.destination_mailbox)
+    }
+
     fn is_ready(&self) -> bool {
         self.next_attempt_after
             .map(|a| Instant::now() >= a)
@@ -579,6 +875,13 @@ impl PendingMessage {
             .store_processed_by_nonce(&self.message.nonce, &true)?;
         self.ctx.metrics.update_nonce(&self.message);
         self.ctx.metrics.messages_processed.inc();
+        // Messages delivered by someone else didn't cost us anything, so
+        // their payments aren't counted either
+        if self.submission_outcome.is_some() {
+            self.ctx
+                .profitability_ledger
+                .record_delivery(&self.message, self.app_context.as_deref());
+        }
         Ok(())
     }
 
@@ -643,6 +946,7 @@ pub struct MessageSubmissionMetrics {
     // Fields are public for testing purposes
     pub last_known_nonce: IntGauge,
     pub messages_processed: IntCounter,
+    pub messages_dry_run: IntCounter,
 }
 
 impl MessageSubmissionMetrics {
@@ -662,6 +966,9 @@ impl MessageSubmissionMetrics {
             messages_processed: metrics
                 .messages_processed_count()
                 .with_label_values(&[origin, destination]),
+            messages_dry_run: metrics
+                .messages_dry_run_count()
+                .with_label_values(&[origin, destination]),
         }
     }
 
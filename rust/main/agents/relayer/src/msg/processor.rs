@@ -8,30 +8,25 @@ use std::{
 
 use async_trait::async_trait;
 use derive_new::new;
-use ethers::utils::hex;
 use eyre::Result;
 use hyperlane_base::{
-    db::{HyperlaneDb, HyperlaneRocksDB},
+    db::{DbResult, HyperlaneDb, HyperlaneRocksDB},
     CoreMetrics,
 };
-use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, QueueOperation};
+use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, QueueOperation, H256};
 use prometheus::IntGauge;
-use tokio::sync::mpsc::UnboundedSender;
-use tracing::{debug, instrument, trace};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tracing::{debug, info, instrument, trace, warn};
 
-use super::{blacklist::AddressBlacklist, metadata::AppContextClassifier, pending_message::*};
+use super::{filters::SharedMessageFilters, metadata::AppContextClassifier, pending_message::*};
 use crate::{processor::ProcessorExt, settings::matching_list::MatchingList};
 
 /// Finds unprocessed messages from an origin and submits then through a channel
 /// for to the appropriate destination.
 #[allow(clippy::too_many_arguments)]
 pub struct MessageProcessor {
-    /// A matching list of messages that should be whitelisted.
-    message_whitelist: Arc<MatchingList>,
-    /// A matching list of messages that should be blacklisted.
-    message_blacklist: Arc<MatchingList>,
-    /// Addresses that messages may not interact with.
-    address_blacklist: Arc<AddressBlacklist>,
+    /// Which messages should be relayed. Can be updated at runtime.
+    message_filters: SharedMessageFilters,
     metrics: MessageProcessorMetrics,
     /// channel for each destination chain to send operations (i.e. message
     /// submissions) to
@@ -39,6 +34,15 @@ pub struct MessageProcessor {
     /// Needed context to send a message for each destination chain
     destination_ctxs: HashMap<u32, Arc<MessageContext>>,
     metric_app_contexts: Vec<(MatchingList, String)>,
+    /// Messages requeued from the dead-letter queue, which are sent to the
+    /// submitter again regardless of the nonce iterator
+    requeue_receiver: UnboundedReceiver<HyperlaneMessage>,
+    /// Messages of the origin transactions that were requested to be relayed,
+    /// which are sent to the submitter ahead of the nonce iterator
+    relay_tx_receiver: UnboundedReceiver<HyperlaneMessage>,
+    /// Messages that were already sent to the submitter ahead of the nonce
+    /// iterator, so that it skips them, by id with their nonce
+    expedited_ids: HashMap<H256, u32>,
     nonce_iterator: ForwardBackwardIterator,
 }
 
@@ -79,6 +83,17 @@ impl ForwardBackwardIterator {
         }
     }
 
+    /// Whether the iterator has already gone past the nonce, in which case
+    /// the message was either sent to the submitter or found processed.
+    fn has_passed(&self, nonce: u32) -> bool {
+        let below_high = self
+            .high_nonce_iter
+            .nonce
+            .map_or(false, |high| nonce < high);
+        let above_low = self.low_nonce_iter.nonce.map_or(true, |low| nonce > low);
+        below_high && above_low
+    }
+
     async fn try_get_next_message(
         &mut self,
         metrics: &MessageProcessorMetrics,
@@ -230,8 +245,8 @@ impl Debug for MessageProcessor {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "MessageProcessor {{ message_whitelist: {:?}, message_blacklist: {:?}, address_blacklist: {:?}, nonce_iterator: {:?}}}",
-            self.message_whitelist, self.message_blacklist, self.address_blacklist, self.nonce_iterator
+            "MessageProcessor {{ message_filters: {:?}, nonce_iterator: {:?}}}",
+            self.message_filters, self.nonce_iterator
         )
     }
 }
@@ -250,6 +265,49 @@ impl ProcessorExt for MessageProcessor {
         // satisfied or the message is disqualified, push the message onto
         // self.tx_msg and then continue the scan at the next highest
         // nonce.
+        // Messages requeued from the dead-letter queue were already filtered
+        // when first processed, so they go straight to the submitter.
+        while let Ok(msg) = self.requeue_receiver.try_recv() {
+            info!(id = ?msg.id(), "Requeueing message from the dead-letter queue");
+            self.send_to_submitter(msg, false).await?;
+        }
+
+        // Release the expedited messages the iterator went past without
+        // picking them up, because they were processed in the meantime
+        let nonce_iterator = &self.nonce_iterator;
+        self.expedited_ids
+            .retain(|_, nonce| !nonce_iterator.has_passed(*nonce));
+
+        while let Ok(msg) = self.relay_tx_receiver.try_recv() {
+            if !self.message_filters.read().await.allows(&msg) {
+                info!(id = ?msg.id(), "Not expediting message that is filtered out");
+                continue;
+            }
+            if msg.destination == self.domain().id() {
+                continue;
+            }
+            if self.expedited_ids.contains_key(&msg.id())
+                || self.nonce_iterator.has_passed(msg.nonce)
+            {
+                info!(id = ?msg.id(), "Not expediting message that was already sent");
+                continue;
+            }
+            match self.is_processed_or_dead_lettered(&msg) {
+                Ok(false) => {}
+                Ok(true) => {
+                    info!(id = ?msg.id(), "Not expediting message that was processed or dead-lettered");
+                    continue;
+                }
+                Err(err) => {
+                    warn!(id = ?msg.id(), ?err, "Failed to look up message, not expediting it");
+                    continue;
+                }
+            }
+            info!(id = ?msg.id(), "Expediting message of a relayed transaction");
+            self.expedited_ids.insert(msg.id(), msg.nonce);
+            self.send_to_submitter(msg, true).await?;
+        }
+
         // Scan until we find next nonce without delivery confirmation.
         if let Some(msg) = self.try_get_unprocessed_message().await? {
             debug!(
@@ -259,26 +317,9 @@ impl ProcessorExt for MessageProcessor {
             );
             let destination = msg.destination;
 
-            // Skip if not whitelisted.
-            if !self.message_whitelist.msg_matches(&msg, true) {
-                debug!(?msg, whitelist=?self.message_whitelist, "Message not whitelisted, skipping");
-                return Ok(());
-            }
-
-            // Skip if the message is blacklisted
-            if self.message_blacklist.msg_matches(&msg, false) {
-                debug!(?msg, blacklist=?self.message_blacklist, "Message blacklisted, skipping");
-                return Ok(());
-            }
-
-            // Skip if the message involves a blacklisted address
-            if let Some(blacklisted_address) = self.address_blacklist.find_blacklisted_address(&msg)
-            {
-                debug!(
-                    ?msg,
-                    blacklisted_address = hex::encode(blacklisted_address),
-                    "Message involves blacklisted address, skipping"
-                );
+            // Skip if the message is filtered out by the whitelist, blacklist or
+            // address blacklist
+            if !self.message_filters.read().await.allows(&msg) {
                 return Ok(());
             }
 
@@ -288,25 +329,26 @@ impl ProcessorExt for MessageProcessor {
                 return Ok(());
             }
 
-            // Skip if the message is intended for a destination we do not service
-            if !self.send_channels.contains_key(&destination) {
-                debug!(?msg, "Message destined for unknown domain, skipping");
+            // Skip if the message was moved to the dead-letter queue. It will only be
+            // picked up again if it is requeued, and never if it was dropped.
+            if self
+                .nonce_iterator
+                .high_nonce_iter
+                .db
+                .retrieve_dead_lettered_message_by_id(&msg.id())?
+                .is_some()
+            {
+                debug!(?msg, "Message is dead-lettered or dropped, skipping");
                 return Ok(());
             }
 
-            debug!(%msg, "Sending message to submitter");
-
-            let app_context_classifier =
-                AppContextClassifier::new(self.metric_app_contexts.clone());
+            // Skip if the message was already expedited
+            if self.expedited_ids.remove(&msg.id()).is_some() {
+                debug!(?msg, "Message was already expedited, skipping");
+                return Ok(());
+            }
 
-            let app_context = app_context_classifier.get_app_context(&msg).await?;
-            // Finally, build the submit arg and dispatch it to the submitter.
-            let pending_msg = PendingMessage::from_persisted_retries(
-                msg,
-                self.destination_ctxs[&destination].clone(),
-                app_context,
-            );
-            self.send_channels[&destination].send(Box::new(pending_msg) as QueueOperation)?;
+            self.send_to_submitter(msg, false).await?;
         } else {
             tokio::time::sleep(Duration::from_secs(1)).await;
         }
@@ -318,26 +360,116 @@ impl MessageProcessor {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         db: HyperlaneRocksDB,
-        message_whitelist: Arc<MatchingList>,
-        message_blacklist: Arc<MatchingList>,
-        address_blacklist: Arc<AddressBlacklist>,
+        message_filters: SharedMessageFilters,
         metrics: MessageProcessorMetrics,
         send_channels: HashMap<u32, UnboundedSender<QueueOperation>>,
         destination_ctxs: HashMap<u32, Arc<MessageContext>>,
         metric_app_contexts: Vec<(MatchingList, String)>,
+        requeue_receiver: UnboundedReceiver<HyperlaneMessage>,
+        relay_tx_receiver: UnboundedReceiver<HyperlaneMessage>,
     ) -> Self {
         Self {
-            message_whitelist,
-            message_blacklist,
-            address_blacklist,
+            message_filters,
             metrics,
             send_channels,
             destination_ctxs,
             metric_app_contexts,
+            requeue_receiver,
+            relay_tx_receiver,
+            expedited_ids: HashMap::new(),
             nonce_iterator: ForwardBackwardIterator::new(Arc::new(db) as Arc<dyn HyperlaneDb>),
         }
     }
 
+    /// Sends the messages that were in the submission queues when the relayer
+    /// shut down back to the submitter, in the state they were in. The nonce
+    /// iterator skips them like expedited messages, so they aren't prepared or
+    /// submitted twice.
+    pub fn restore_queue_snapshots(&mut self, db: &HyperlaneRocksDB) {
+        let snapshots = match db.retrieve_queued_message_snapshots() {
+            Ok(snapshots) => snapshots,
+            Err(err) => {
+                warn!(
+                    ?err,
+                    "Failed to retrieve queue snapshots, messages will be prepared again"
+                );
+                return;
+            }
+        };
+        for snapshot in snapshots {
+            let id = snapshot.message_id;
+            // The snapshot is only restored once, later restarts take a new one
+            if let Err(err) = db.remove_queued_message_snapshot_by_id(&id) {
+                warn!(?id, ?err, "Failed to remove queue snapshot of message");
+            }
+            let msg = match db.retrieve_message_by_id(&id) {
+                Ok(Some(msg)) => msg,
+                r => {
+                    warn!(?id, result = ?r, "Failed to retrieve message of queue snapshot");
+                    continue;
+                }
+            };
+            let (destination, nonce) = (msg.destination, msg.nonce);
+            let (Some(channel), Some(ctx)) = (
+                self.send_channels.get(&destination),
+                self.destination_ctxs.get(&destination),
+            ) else {
+                debug!(
+                    ?msg,
+                    "Queue snapshot of message destined for unknown domain, skipping"
+                );
+                continue;
+            };
+            let queue = snapshot.queue;
+            let pending_msg = PendingMessage::from_queue_snapshot(msg, ctx.clone(), snapshot);
+            if channel
+                .send(Box::new(pending_msg) as QueueOperation)
+                .is_err()
+            {
+                warn!(?id, "Failed to restore message from queue snapshot");
+                continue;
+            }
+            debug!(?id, %queue, "Restored message from queue snapshot");
+            self.expedited_ids.insert(id, nonce);
+        }
+    }
+
+    /// Whether the message was processed or dead-lettered. Dead-lettered
+    /// messages are only sent again once they are requeued.
+    fn is_processed_or_dead_lettered(&self, msg: &HyperlaneMessage) -> DbResult<bool> {
+        let db = &self.nonce_iterator.high_nonce_iter.db;
+        Ok(db.retrieve_processed_by_nonce(&msg.nonce)?.unwrap_or(false)
+            || db
+                .retrieve_dead_lettered_message_by_id(&msg.id())?
+                .is_some())
+    }
+
+    async fn send_to_submitter(&self, msg: HyperlaneMessage, expedite: bool) -> Result<()> {
+        let destination = msg.destination;
+        // Skip if the message is intended for a destination we do not service
+        if !self.send_channels.contains_key(&destination) {
+            debug!(?msg, "Message destined for unknown domain, skipping");
+            return Ok(());
+        }
+
+        debug!(%msg, "Sending message to submitter");
+
+        let app_context_classifier = AppContextClassifier::new(self.metric_app_contexts.clone());
+
+        let app_context = app_context_classifier.get_app_context(&msg).await?;
+        // Finally, build the submit arg and dispatch it to the submitter.
+        let mut pending_msg = PendingMessage::from_persisted_retries(
+            msg,
+            self.destination_ctxs[&destination].clone(),
+            app_context,
+        );
+        if expedite {
+            pending_msg = pending_msg.expedite();
+        }
+        self.send_channels[&destination].send(Box::new(pending_msg) as QueueOperation)?;
+        Ok(())
+    }
+
     async fn try_get_unprocessed_message(&mut self) -> Result<Option<HyperlaneMessage>> {
         trace!(nonce_iterator=?self.nonce_iterator, "Trying to get the next processor message");
         let next_message = self
@@ -396,6 +528,9 @@ mod test {
         msg::{
             gas_payment::GasPaymentEnforcer,
             metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier},
+            profitability::ProfitabilityLedger,
+            quorum_health::QuorumHealthTracker,
+            rate_limiter::RateLimiter,
         },
         processor::Processor,
     };
@@ -403,14 +538,15 @@ mod test {
     use super::*;
     use hyperlane_base::{
         db::{
-            test_utils, DbResult, HyperlaneRocksDB, InterchainGasExpenditureData,
-            InterchainGasPaymentData,
+            test_utils, DbResult, DeadLetteredMessage, HyperlaneRocksDB,
+            InterchainGasExpenditureData, InterchainGasPaymentData,
         },
         settings::{ChainConf, ChainConnectionConf, Settings},
+        CheckpointCache,
     };
     use hyperlane_core::{
         test_utils::dummy_domain, GasPaymentKey, InterchainGasPayment, InterchainGasPaymentMeta,
-        MerkleTreeInsertion, PendingOperationStatus, H256,
+        MerkleTreeInsertion, PendingOperationStatus, ReprepareReason, H256,
     };
     use hyperlane_test::mocks::{MockMailboxContract, MockValidatorAnnounceContract};
     use prometheus::{IntCounter, Registry};
@@ -441,6 +577,7 @@ mod test {
         MessageSubmissionMetrics {
             last_known_nonce: IntGauge::new("last_known_nonce_gauge", "help string").unwrap(),
             messages_processed: IntCounter::new("message_processed_gauge", "help string").unwrap(),
+            messages_dry_run: IntCounter::new("messages_dry_run_gauge", "help string").unwrap(),
         }
     }
 
@@ -448,6 +585,7 @@ mod test {
         ChainConf {
             domain: domain.clone(),
             signer: Default::default(),
+            signer_pool: vec![],
             reorg_period: Default::default(),
             addresses: Default::default(),
             connection: ChainConnectionConf::Ethereum(hyperlane_ethereum::ConnectionConf {
@@ -478,13 +616,17 @@ mod test {
         );
         let destination_chain_conf = settings.chain_setup(destination_domain).unwrap();
         let core_metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+        let checkpoint_cache = Arc::new(CheckpointCache::with_defaults(&core_metrics));
         BaseMetadataBuilder::new(
             origin_domain.clone(),
             destination_chain_conf.clone(),
             Arc::new(RwLock::new(MerkleTreeBuilder::new())),
             Arc::new(MockValidatorAnnounceContract::default()),
             false,
+            Duration::from_secs(30),
             Arc::new(core_metrics),
+            checkpoint_cache,
+            Arc::new(QuorumHealthTracker::default()),
             db.clone(),
             IsmAwareAppContextClassifier::new(Arc::new(MockMailboxContract::default()), vec![]),
         )
@@ -502,20 +644,32 @@ mod test {
             metadata_builder: Arc::new(base_metadata_builder),
             origin_gas_payment_enforcer: Arc::new(GasPaymentEnforcer::new([], db.clone())),
             transaction_gas_limit: Default::default(),
+            dry_run: false,
+            dead_letter: Default::default(),
+            rate_limiter: Arc::new(RateLimiter::new([], db.clone())),
+            profitability_ledger: Arc::new(ProfitabilityLedger::new(
+                HashMap::new(),
+                HashMap::new(),
+                &CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap(),
+            )),
+            priority_lane: None,
+            events: Default::default(),
             metrics: dummy_submission_metrics(),
         });
 
         let (send_channel, receive_channel) = mpsc::unbounded_channel::<QueueOperation>();
+        let (_, requeue_receiver) = mpsc::unbounded_channel::<HyperlaneMessage>();
+        let (_, relay_tx_receiver) = mpsc::unbounded_channel::<HyperlaneMessage>();
         (
             MessageProcessor::new(
                 db.clone(),
                 Default::default(),
-                Default::default(),
-                Default::default(),
                 dummy_processor_metrics(origin_domain.id()),
                 HashMap::from([(destination_domain.id(), send_channel)]),
                 HashMap::from([(destination_domain.id(), message_context)]),
                 vec![],
+                requeue_receiver,
+                relay_tx_receiver,
             ),
             receive_channel,
         )
@@ -742,6 +896,17 @@ mod test {
             /// Retrieve the nonce of the highest processed message we're aware of
             fn retrieve_highest_seen_message_nonce_number(&self) -> DbResult<Option<u32>>;
 
+            fn store_dead_lettered_message_by_id(
+                &self,
+                message_id: &H256,
+                entry: &DeadLetteredMessage,
+            ) -> DbResult<()>;
+
+            fn retrieve_dead_lettered_message_by_id(
+                &self,
+                message_id: &H256,
+            ) -> DbResult<Option<DeadLetteredMessage>>;
+
         }
     }
 
@@ -800,6 +965,178 @@ mod test {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_dead_lettered_messages_are_skipped() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+
+            persist_retried_messages(&[0, 0], &db, &destination_domain);
+            let dead_lettered = dummy_hyperlane_message(&destination_domain, 0);
+            db.store_dead_lettered_message_by_id(
+                &dead_lettered.id(),
+                &DeadLetteredMessage {
+                    message_id: dead_lettered.id(),
+                    origin: dead_lettered.origin,
+                    destination: dead_lettered.destination,
+                    nonce: dead_lettered.nonce,
+                    num_retries: 10,
+                    reason: ReprepareReason::ErrorEstimatingGas,
+                    dead_lettered_at: 0,
+                    dropped_at: None,
+                },
+            )
+            .unwrap();
+
+            let pending_messages =
+                get_first_n_operations_from_processor(&origin_domain, &destination_domain, &db, 1)
+                    .await;
+
+            assert_eq!(pending_messages.len(), 1);
+            assert_ne!(pending_messages[0].id(), dead_lettered.id());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_relayed_tx_messages_are_expedited_once() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+
+            persist_retried_messages(&[0, 0, 5], &db, &destination_domain);
+            let relayed = dummy_hyperlane_message(&destination_domain, 2);
+
+            let (mut message_processor, mut receive_channel) =
+                dummy_message_processor(&origin_domain, &destination_domain, &db);
+            let (relay_tx_sender, relay_tx_receiver) =
+                mpsc::unbounded_channel::<HyperlaneMessage>();
+            message_processor.relay_tx_receiver = relay_tx_receiver;
+            relay_tx_sender.send(relayed.clone()).unwrap();
+
+            let processor = Processor::new(Box::new(message_processor), TaskMonitor::new());
+            let process_fut = processor.spawn();
+            let mut pending_messages = vec![];
+            let pending_message_accumulator = async {
+                while let Some(pm) = receive_channel.recv().await {
+                    pending_messages.push(pm);
+                }
+            };
+            tokio::select! {
+                _ = process_fut => {},
+                _ = pending_message_accumulator => {},
+                _ = sleep(Duration::from_millis(200)) => {},
+            };
+
+            // The relayed message comes first, without the backoff of its
+            // previous attempts, and isn't sent again by the nonce iterator
+            assert_eq!(pending_messages.len(), 3);
+            assert_eq!(pending_messages[0].id(), relayed.id());
+            assert!(pending_messages[0].is_expedited());
+            assert!(pending_messages[0].next_attempt_after().is_none());
+            assert!(pending_messages[1..].iter().all(|pm| !pm.is_expedited()));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_relayed_tx_messages_already_sent_or_processed_are_not_expedited() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+
+            persist_retried_messages(&[0, 0, 0, 0], &db, &destination_domain);
+            let messages: Vec<_> = (0..4)
+                .map(|nonce| dummy_hyperlane_message(&destination_domain, nonce))
+                .collect();
+            db.store_processed_by_nonce(&3, &true).unwrap();
+
+            let (mut message_processor, mut receive_channel) =
+                dummy_message_processor(&origin_domain, &destination_domain, &db);
+            let (relay_tx_sender, relay_tx_receiver) =
+                mpsc::unbounded_channel::<HyperlaneMessage>();
+            message_processor.relay_tx_receiver = relay_tx_receiver;
+
+            // Nonce 3 is already processed, nonce 1 is expedited and the
+            // iterator sends nonce 2
+            relay_tx_sender.send(messages[3].clone()).unwrap();
+            relay_tx_sender.send(messages[1].clone()).unwrap();
+            message_processor.tick().await.unwrap();
+
+            // Nonce 1 is delivered before the iterator reaches it, so the
+            // iterator skips it as processed and sends nonce 0
+            db.store_processed_by_nonce(&1, &true).unwrap();
+            message_processor.tick().await.unwrap();
+
+            // The iterator already went past both nonces
+            relay_tx_sender.send(messages[2].clone()).unwrap();
+            relay_tx_sender.send(messages[0].clone()).unwrap();
+            message_processor.tick().await.unwrap();
+
+            let mut pending_messages = vec![];
+            while let Ok(pm) = receive_channel.try_recv() {
+                pending_messages.push(pm);
+            }
+            let ids: Vec<_> = pending_messages.iter().map(|pm| pm.id()).collect();
+            assert_eq!(
+                ids,
+                vec![messages[1].id(), messages[2].id(), messages[0].id()]
+            );
+            assert!(pending_messages[0].is_expedited());
+            assert!(pending_messages[1..].iter().all(|pm| !pm.is_expedited()));
+            assert!(message_processor.expedited_ids.is_empty());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_dead_lettered_relayed_tx_messages_are_not_expedited() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+
+            persist_retried_messages(&[0, 0], &db, &destination_domain);
+            let dead_lettered = dummy_hyperlane_message(&destination_domain, 1);
+            db.store_dead_lettered_message_by_id(
+                &dead_lettered.id(),
+                &DeadLetteredMessage {
+                    message_id: dead_lettered.id(),
+                    origin: dead_lettered.origin,
+                    destination: dead_lettered.destination,
+                    nonce: dead_lettered.nonce,
+                    num_retries: 10,
+                    reason: ReprepareReason::ErrorEstimatingGas,
+                    dead_lettered_at: 0,
+                    dropped_at: None,
+                },
+            )
+            .unwrap();
+
+            let (mut message_processor, mut receive_channel) =
+                dummy_message_processor(&origin_domain, &destination_domain, &db);
+            let (relay_tx_sender, relay_tx_receiver) =
+                mpsc::unbounded_channel::<HyperlaneMessage>();
+            message_processor.relay_tx_receiver = relay_tx_receiver;
+            relay_tx_sender.send(dead_lettered.clone()).unwrap();
+            message_processor.tick().await.unwrap();
+            message_processor.tick().await.unwrap();
+
+            let mut pending_messages = vec![];
+            while let Ok(pm) = receive_channel.try_recv() {
+                pending_messages.push(pm);
+            }
+            assert!(pending_messages
+                .iter()
+                .all(|pm| pm.id() != dead_lettered.id() && !pm.is_expedited()));
+            assert!(message_processor.expedited_ids.is_empty());
+        })
+        .await;
+    }
+
     #[tokio::test]
     async fn test_forward_backward_iterator() {
         let mut mock_db = MockDb::new();
@@ -0,0 +1,535 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use ethers::utils::keccak256;
+use eyre::{eyre, Result};
+use hyperlane_base::{
+    db::{HyperlaneRocksDB, ProfitabilityLedgerEntry},
+    CoreMetrics,
+};
+use hyperlane_core::{FixedPointNumber, GasPaymentKey, HyperlaneMessage, TxOutcome, H256, U256};
+use prometheus::GaugeVec;
+use serde::{Serialize, Serializer};
+use tokio::task::JoinHandle;
+use tracing::{info_span, instrument::Instrumented, warn, Instrument};
+
+/// Ledger entries cover one hour each
+const LEDGER_PERIOD_SECS: u64 = 60 * 60;
+
+/// The time windows the ledger is summarized over
+const LEDGER_WINDOWS: [(&str, Duration); 4] = [
+    ("1h", Duration::from_secs(60 * 60)),
+    ("24h", Duration::from_secs(24 * 60 * 60)),
+    ("7d", Duration::from_secs(7 * 24 * 60 * 60)),
+    ("30d", Duration::from_secs(30 * 24 * 60 * 60)),
+];
+
+/// How long entries are kept, i.e. until no part of their period is within
+/// the longest window
+const LEDGER_RETENTION_SECS: u64 =
+    LEDGER_WINDOWS[LEDGER_WINDOWS.len() - 1].1.as_secs() + LEDGER_PERIOD_SECS;
+
+const METRICS_UPDATE_INTERVAL: Duration = Duration::from_secs(60);
+
+const UNKNOWN_APP_CONTEXT: &str = "Unknown";
+
+/// Keeps track of the gas payments received and the gas spent for every app
+/// context, origin and destination, in hourly entries persisted in the origin
+/// dbs. Entries are pruned once they are outside of every time window.
+/// Payments are in the origin's native token and gas spent is in the
+/// destination's, so the two aren't comparable as they are.
+#[derive(Debug)]
+pub struct ProfitabilityLedger {
+    /// Databases of the origin chains, keyed by domain id
+    dbs: HashMap<u32, HyperlaneRocksDB>,
+    /// Chain names used as metric labels, keyed by domain id
+    domain_names: HashMap<u32, String>,
+    payments_metric: GaugeVec,
+    gas_spent_metric: GaugeVec,
+    /// The entries that weren't pruned yet, loaded from the dbs on startup so
+    /// summarizing the ledger doesn't need to scan them. Held while an entry
+    /// is updated and written back, since messages are confirmed concurrently
+    entries: Mutex<HashMap<H256, ProfitabilityLedgerEntry>>,
+}
+
+/// What was paid and spent on a route within each time window
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteSummary {
+    pub app_context: String,
+    pub origin: u32,
+    pub destination: u32,
+    pub windows: BTreeMap<&'static str, WindowTotals>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowTotals {
+    /// In the smallest unit of the origin's native token
+    #[serde(serialize_with = "serialize_decimal")]
+    pub payments_in_origin_token: U256,
+    /// In the smallest unit of the destination's native token
+    #[serde(serialize_with = "serialize_decimal")]
+    pub gas_spent_in_destination_token: U256,
+    #[serde(serialize_with = "serialize_decimal")]
+    pub gas_used: U256,
+    pub messages_delivered: u64,
+}
+
+fn serialize_decimal<S: Serializer>(value: &U256, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&value.to_string())
+}
+
+impl WindowTotals {
+    fn add(&mut self, entry: &ProfitabilityLedgerEntry) {
+        self.payments_in_origin_token =
+            self.payments_in_origin_token.saturating_add(entry.payments);
+        self.gas_spent_in_destination_token = self
+            .gas_spent_in_destination_token
+            .saturating_add(entry.gas_spent);
+        self.gas_used = self.gas_used.saturating_add(entry.gas_used);
+        self.messages_delivered += entry.messages_delivered;
+    }
+}
+
+impl ProfitabilityLedger {
+    pub fn new(
+        dbs: HashMap<u32, HyperlaneRocksDB>,
+        domain_names: HashMap<u32, String>,
+        metrics: &CoreMetrics,
+    ) -> Self {
+        let entries = dbs
+            .values()
+            .flat_map(|db| {
+                db.retrieve_profitability_ledger_entries()
+                    .unwrap_or_else(|err| {
+                        let domain = db.domain();
+                        warn!(?err, ?domain, "Failed to load the profitability ledger");
+                        vec![]
+                    })
+            })
+            .map(|entry| (key_of(&entry), entry))
+            .collect();
+        Self {
+            dbs,
+            domain_names,
+            payments_metric: metrics.route_gas_payments(),
+            gas_spent_metric: metrics.route_gas_spent(),
+            entries: Mutex::new(entries),
+        }
+    }
+
+    /// Record the gas spent by a transaction that submitted the message
+    pub fn record_gas_spent(
+        &self,
+        message: &HyperlaneMessage,
+        app_context: Option<&str>,
+        outcome: &TxOutcome,
+    ) {
+        if let Err(err) = self.record_gas_spent_at(message, app_context, outcome, now()) {
+            warn!(
+                ?err,
+                "Failed to record gas spent in the profitability ledger"
+            );
+        }
+    }
+
+    fn record_gas_spent_at(
+        &self,
+        message: &HyperlaneMessage,
+        app_context: Option<&str>,
+        outcome: &TxOutcome,
+        now: u64,
+    ) -> Result<()> {
+        let gas_spent: U256 = (FixedPointNumber::try_from(outcome.gas_used)?
+            * outcome.gas_price.clone())
+        .try_into()?;
+        self.update_entry(message, app_context, now, |entry| {
+            entry.gas_spent = entry.gas_spent.saturating_add(gas_spent);
+            entry.gas_used = entry.gas_used.saturating_add(outcome.gas_used);
+        })
+    }
+
+    /// Record the delivery of a message, along with the gas payments made for
+    /// it
+    pub fn record_delivery(&self, message: &HyperlaneMessage, app_context: Option<&str>) {
+        if let Err(err) = self.record_delivery_at(message, app_context, now()) {
+            warn!(
+                ?err,
+                "Failed to record delivery in the profitability ledger"
+            );
+        }
+    }
+
+    fn record_delivery_at(
+        &self,
+        message: &HyperlaneMessage,
+        app_context: Option<&str>,
+        now: u64,
+    ) -> Result<()> {
+        let payment = self
+            .db(message.origin)?
+            .retrieve_gas_payment_by_gas_payment_key(GasPaymentKey {
+                message_id: message.id(),
+                destination: message.destination,
+            })?
+            .map(|payment| payment.payment)
+            .unwrap_or_default();
+        self.update_entry(message, app_context, now, |entry| {
+            entry.payments = entry.payments.saturating_add(payment);
+            entry.messages_delivered += 1;
+        })
+    }
+
+    fn update_entry(
+        &self,
+        message: &HyperlaneMessage,
+        app_context: Option<&str>,
+        now: u64,
+        update: impl FnOnce(&mut ProfitabilityLedgerEntry),
+    ) -> Result<()> {
+        let db = self.db(message.origin)?;
+        let app_context = app_context.unwrap_or(UNKNOWN_APP_CONTEXT);
+        let period_start = now - now % LEDGER_PERIOD_SECS;
+        let key = entry_key(
+            app_context,
+            message.origin,
+            message.destination,
+            period_start,
+        );
+
+        let mut entries = self.lock_entries();
+        let entry = entries
+            .entry(key)
+            .or_insert_with(|| ProfitabilityLedgerEntry {
+                app_context: app_context.to_owned(),
+                origin: message.origin,
+                destination: message.destination,
+                period_start,
+                payments: U256::zero(),
+                gas_spent: U256::zero(),
+                gas_used: U256::zero(),
+                messages_delivered: 0,
+            });
+        update(entry);
+        db.store_profitability_ledger_entry(&key, entry)?;
+        Ok(())
+    }
+
+    fn db(&self, origin: u32) -> Result<&HyperlaneRocksDB> {
+        self.dbs
+            .get(&origin)
+            .ok_or_else(|| eyre!("No db for origin domain {}", origin))
+    }
+
+    fn lock_entries(&self) -> MutexGuard<'_, HashMap<H256, ProfitabilityLedgerEntry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Delete the entries that are outside of every time window
+    fn prune_at(&self, now: u64) -> Result<usize> {
+        let mut entries = self.lock_entries();
+        let expired = entries
+            .values()
+            .filter(|entry| now.saturating_sub(entry.period_start) >= LEDGER_RETENTION_SECS)
+            .map(|entry| (key_of(entry), entry.origin))
+            .collect::<Vec<_>>();
+        for (key, origin) in &expired {
+            self.db(*origin)?.delete_profitability_ledger_entry(key)?;
+            entries.remove(key);
+        }
+        Ok(expired.len())
+    }
+
+    /// Totals of every route within each time window
+    pub fn summarize(&self) -> Vec<RouteSummary> {
+        self.summarize_at(now())
+    }
+
+    fn summarize_at(&self, now: u64) -> Vec<RouteSummary> {
+        let mut routes: BTreeMap<(String, u32, u32), BTreeMap<&'static str, WindowTotals>> =
+            BTreeMap::new();
+        for entry in self.lock_entries().values() {
+            let windows = routes
+                .entry((entry.app_context.clone(), entry.origin, entry.destination))
+                .or_insert_with(|| {
+                    LEDGER_WINDOWS
+                        .iter()
+                        .map(|(name, _)| (*name, WindowTotals::default()))
+                        .collect()
+                });
+            let age = now.saturating_sub(entry.period_start);
+            for (name, window) in LEDGER_WINDOWS {
+                // Entries are counted if any part of their hour is within the
+                // window
+                if age < window.as_secs() + LEDGER_PERIOD_SECS {
+                    if let Some(totals) = windows.get_mut(name) {
+                        totals.add(entry);
+                    }
+                }
+            }
+        }
+        routes
+            .into_iter()
+            .map(
+                |((app_context, origin, destination), windows)| RouteSummary {
+                    app_context,
+                    origin,
+                    destination,
+                    windows,
+                },
+            )
+            .collect()
+    }
+
+    fn update_metrics(&self) {
+        for summary in self.summarize() {
+            let origin = self.domain_name(summary.origin);
+            let destination = self.domain_name(summary.destination);
+            for (window, totals) in summary.windows {
+                let labels = [
+                    summary.app_context.as_str(),
+                    origin.as_str(),
+                    destination.as_str(),
+                    window,
+                ];
+                self.payments_metric
+                    .with_label_values(&labels)
+                    .set(totals.payments_in_origin_token.to_f64_lossy());
+                self.gas_spent_metric
+                    .with_label_values(&labels)
+                    .set(totals.gas_spent_in_destination_token.to_f64_lossy());
+            }
+        }
+    }
+
+    fn domain_name(&self, domain: u32) -> String {
+        self.domain_names
+            .get(&domain)
+            .cloned()
+            .unwrap_or_else(|| domain.to_string())
+    }
+
+    /// Periodically update the metrics, since the windows move over time
+    pub fn spawn(self: Arc<Self>) -> Instrumented<JoinHandle<()>> {
+        tokio::spawn(async move {
+            let mut pruned_period = None;
+            loop {
+                // Entries only leave the longest window once per period
+                let now = now();
+                if pruned_period != Some(now / LEDGER_PERIOD_SECS) {
+                    if let Err(err) = self.prune_at(now) {
+                        warn!(?err, "Failed to prune the profitability ledger");
+                    }
+                    pruned_period = Some(now / LEDGER_PERIOD_SECS);
+                }
+                self.update_metrics();
+                tokio::time::sleep(METRICS_UPDATE_INTERVAL).await;
+            }
+        })
+        .instrument(info_span!("ProfitabilityLedger"))
+    }
+}
+
+fn entry_key(app_context: &str, origin: u32, destination: u32, period_start: u64) -> H256 {
+    keccak256(format!(
+        "{app_context}/{origin}/{destination}/{period_start}"
+    ))
+    .into()
+}
+
+fn key_of(entry: &ProfitabilityLedgerEntry) -> H256 {
+    entry_key(
+        &entry.app_context,
+        entry.origin,
+        entry.destination,
+        entry.period_start,
+    )
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_base::db::test_utils;
+    use hyperlane_core::{HyperlaneDomain, InterchainGasPayment, LogMeta, H512};
+    use prometheus::Registry;
+
+    use super::*;
+
+    const HOUR: u64 = LEDGER_PERIOD_SECS;
+
+    fn message(db: &HyperlaneRocksDB, nonce: u32) -> HyperlaneMessage {
+        HyperlaneMessage {
+            nonce,
+            origin: db.domain().id(),
+            destination: db.domain().id() + 1,
+            ..Default::default()
+        }
+    }
+
+    fn pay(db: &HyperlaneRocksDB, message: &HyperlaneMessage, payment: u64) {
+        db.process_gas_payment(
+            InterchainGasPayment {
+                message_id: message.id(),
+                destination: message.destination,
+                payment: payment.into(),
+                gas_amount: U256::zero(),
+            },
+            &LogMeta::random(),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_summarizes_routes_over_windows() {
+        test_utils::run_test_db(|db| async move {
+            let domain = HyperlaneDomain::new_test_domain("profitability_ledger");
+            let db = HyperlaneRocksDB::new(&domain, db);
+            let metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+            let ledger = ProfitabilityLedger::new(
+                HashMap::from([(domain.id(), db.clone())]),
+                HashMap::new(),
+                &metrics,
+            );
+
+            let now = 1_000 * HOUR;
+            // A message delivered two days ago, and one delivered just now
+            let old = message(&db, 0);
+            pay(&db, &old, 100);
+            ledger
+                .record_delivery_at(&old, Some("app"), now - 48 * HOUR)
+                .unwrap();
+            let new = message(&db, 1);
+            pay(&db, &new, 50);
+            let outcome = TxOutcome {
+                transaction_id: H512::zero(),
+                executed: true,
+                gas_used: 3.into(),
+                gas_price: 10u32.into(),
+            };
+            ledger
+                .record_gas_spent_at(&new, Some("app"), &outcome, now)
+                .unwrap();
+            ledger.record_delivery_at(&new, Some("app"), now).unwrap();
+            // A message without an app context
+            ledger
+                .record_delivery_at(&message(&db, 2), None, now)
+                .unwrap();
+
+            let summaries = ledger.summarize_at(now);
+            assert_eq!(summaries.len(), 2);
+            let app = &summaries[1];
+            assert_eq!(app.app_context, "app");
+            assert_eq!(
+                app.windows["24h"],
+                WindowTotals {
+                    payments_in_origin_token: 50.into(),
+                    gas_spent_in_destination_token: 30.into(),
+                    gas_used: 3.into(),
+                    messages_delivered: 1,
+                }
+            );
+            assert_eq!(app.windows["7d"].payments_in_origin_token, 150.into());
+            assert_eq!(app.windows["7d"].messages_delivered, 2);
+            assert_eq!(summaries[0].app_context, UNKNOWN_APP_CONTEXT);
+            assert_eq!(
+                summaries[0].windows["1h"].payments_in_origin_token,
+                U256::zero()
+            );
+            assert_eq!(summaries[0].windows["1h"].messages_delivered, 1);
+
+            // The entries are loaded from the db on startup
+            let restarted = ProfitabilityLedger::new(
+                HashMap::from([(domain.id(), db.clone())]),
+                HashMap::new(),
+                &metrics,
+            );
+            assert_eq!(restarted.summarize_at(now), summaries);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_prunes_entries_outside_windows() {
+        test_utils::run_test_db(|db| async move {
+            let domain = HyperlaneDomain::new_test_domain("profitability_ledger");
+            let db = HyperlaneRocksDB::new(&domain, db);
+            let metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+            let ledger = ProfitabilityLedger::new(
+                HashMap::from([(domain.id(), db.clone())]),
+                HashMap::new(),
+                &metrics,
+            );
+
+            let now = 1_000 * HOUR;
+            for (nonce, age) in [(0, 31 * 24 * HOUR), (1, 29 * 24 * HOUR)] {
+                ledger
+                    .record_delivery_at(&message(&db, nonce), Some("app"), now - age)
+                    .unwrap();
+            }
+
+            assert_eq!(ledger.prune_at(now).unwrap(), 1);
+            let entries = db.retrieve_profitability_ledger_entries().unwrap();
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].period_start, now - 29 * 24 * HOUR);
+            assert_eq!(ledger.prune_at(now).unwrap(), 0);
+            let summaries = ledger.summarize_at(now);
+            assert_eq!(summaries[0].windows["30d"].messages_delivered, 1);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_records_gas_spent() {
+        test_utils::run_test_db(|db| async move {
+            let domain = HyperlaneDomain::new_test_domain("profitability_ledger");
+            let db = HyperlaneRocksDB::new(&domain, db);
+            let metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+            let ledger = ProfitabilityLedger::new(
+                HashMap::from([(domain.id(), db.clone())]),
+                HashMap::new(),
+                &metrics,
+            );
+
+            let outcome = TxOutcome {
+                transaction_id: H512::zero(),
+                executed: true,
+                gas_used: 1_000.into(),
+                gas_price: 20u32.into(),
+            };
+            ledger.record_gas_spent(&message(&db, 0), None, &outcome);
+            ledger.record_gas_spent(&message(&db, 1), None, &outcome);
+
+            let summaries = ledger.summarize();
+            assert_eq!(
+                summaries[0].windows["1h"].gas_spent_in_destination_token,
+                40_000.into()
+            );
+            assert_eq!(summaries[0].windows["1h"].gas_used, 2_000.into());
+
+            ledger.update_metrics();
+            assert_eq!(
+                metrics
+                    .route_gas_spent()
+                    .with_label_values(&[
+                        UNKNOWN_APP_CONTEXT,
+                        &domain.id().to_string(),
+                        &(domain.id() + 1).to_string(),
+                        "30d"
+                    ])
+                    .get(),
+                40_000.
+            );
+        })
+        .await;
+    }
+}
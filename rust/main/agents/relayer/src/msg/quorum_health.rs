@@ -0,0 +1,202 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use hyperlane_base::quorum_index;
+use hyperlane_core::{WeightedValidator, H160, H256};
+use serde::Serialize;
+
+/// The health of a validator of a multisig ISM
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorHealth {
+    pub validator: H160,
+    /// Weight of the validator's signature, 1 for threshold-count multisig ISMs
+    pub weight: u128,
+    /// Storage location of the validator's checkpoints, if it announced a
+    /// usable one
+    pub storage_location: Option<String>,
+    /// Latest checkpoint index signed by the validator, if it could be fetched
+    pub latest_index: Option<u32>,
+    /// How many leaves of the origin's merkle tree the validator is behind
+    pub lag: Option<u32>,
+}
+
+/// The health of the validator set of a multisig ISM, as last observed when
+/// building metadata for one of its messages
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IsmQuorumHealth {
+    pub origin: u32,
+    pub destination: u32,
+    pub ism: H256,
+    pub app_context: Option<String>,
+    /// Weight of the signatures needed for a quorum, which is the threshold
+    /// for threshold-count multisig ISMs
+    pub threshold: u128,
+    pub validators: Vec<ValidatorHealth>,
+    /// Index of the latest message dispatched from the origin, as far as the
+    /// relayer knows
+    pub highest_known_leaf_index: Option<u32>,
+    /// Highest checkpoint index signed by validators weighing at least
+    /// `threshold`
+    pub quorum_index: Option<u32>,
+    /// Whether enough validators signed checkpoints for a quorum. Messages
+    /// after `quorum_index` wait for the validators to catch up.
+    pub quorum_reachable: bool,
+    /// Seconds since the unix epoch
+    pub observed_at: u64,
+}
+
+impl IsmQuorumHealth {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        origin: u32,
+        destination: u32,
+        ism: H256,
+        app_context: Option<String>,
+        validators: &[WeightedValidator],
+        threshold: u128,
+        latest_indices: &HashMap<H160, Option<u32>>,
+        storage_locations: &HashMap<H160, String>,
+        highest_known_leaf_index: Option<u32>,
+    ) -> Self {
+        let quorum_index = quorum_index(validators, threshold, latest_indices);
+        let validators = validators
+            .iter()
+            .map(|&WeightedValidator { validator, weight }| {
+                let validator = H160::from(validator);
+                let latest_index = latest_indices.get(&validator).copied().flatten();
+                ValidatorHealth {
+                    validator,
+                    weight,
+                    storage_location: storage_locations.get(&validator).cloned(),
+                    latest_index,
+                    lag: highest_known_leaf_index
+                        .zip(latest_index)
+                        .map(|(highest, latest)| highest.saturating_sub(latest)),
+                }
+            })
+            .collect();
+
+        Self {
+            origin,
+            destination,
+            ism,
+            app_context,
+            threshold,
+            validators,
+            highest_known_leaf_index,
+            quorum_index,
+            quorum_reachable: quorum_index.is_some(),
+            observed_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Keeps the latest observed health of the validator set of every multisig
+/// ISM the relayer built metadata for
+#[derive(Debug, Default)]
+pub struct QuorumHealthTracker {
+    /// Keyed by origin, destination and ISM address
+    isms: Mutex<HashMap<(u32, u32, H256), IsmQuorumHealth>>,
+}
+
+impl QuorumHealthTracker {
+    pub fn record(&self, health: IsmQuorumHealth) {
+        let key = (health.origin, health.destination, health.ism);
+        self.isms
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, health);
+    }
+
+    /// The health of the ISMs of the messages from `origin`, or of all the
+    /// ISMs if it is `None`, ordered by origin, destination and ISM
+    pub fn snapshot(&self, origin: Option<u32>) -> Vec<IsmQuorumHealth> {
+        self.isms
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|((ism_origin, _, _), _)| origin.map_or(true, |o| o == *ism_origin))
+            .map(|(key, health)| (*key, health.clone()))
+            .collect::<BTreeMap<_, _>>()
+            .into_values()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn health(origin: u32, threshold: u128, indices: &[Option<u32>]) -> IsmQuorumHealth {
+        let validators = (0..indices.len() as u64)
+            .map(H256::from_low_u64_be)
+            .collect::<Vec<_>>();
+        let validators = WeightedValidator::with_unit_weights(&validators);
+        let latest_indices = validators
+            .iter()
+            .zip(indices)
+            .map(|(v, i)| (H160::from(v.validator), *i))
+            .collect();
+        let storage_locations = HashMap::from([(
+            H160::from(validators[0].validator),
+            "s3://bucket/us-east-1".to_owned(),
+        )]);
+        IsmQuorumHealth::new(
+            origin,
+            2,
+            H256::zero(),
+            None,
+            &validators,
+            threshold,
+            &latest_indices,
+            &storage_locations,
+            Some(100),
+        )
+    }
+
+    #[test]
+    fn test_quorum_index_and_lag() {
+        let health = health(1, 2, &[Some(100), None, Some(90), Some(95)]);
+        assert_eq!(health.quorum_index, Some(95));
+        assert!(health.quorum_reachable);
+        assert_eq!(
+            health.validators.iter().map(|v| v.lag).collect::<Vec<_>>(),
+            vec![Some(0), None, Some(10), Some(5)]
+        );
+        assert_eq!(
+            health.validators[0].storage_location.as_deref(),
+            Some("s3://bucket/us-east-1")
+        );
+        assert_eq!(health.validators[1].storage_location, None);
+    }
+
+    #[test]
+    fn test_quorum_unreachable() {
+        let health = health(1, 3, &[Some(100), None, Some(90), None]);
+        assert_eq!(health.quorum_index, None);
+        assert!(!health.quorum_reachable);
+    }
+
+    #[test]
+    fn test_snapshot_is_filtered_by_origin() {
+        let tracker = QuorumHealthTracker::default();
+        tracker.record(health(3, 1, &[Some(1)]));
+        tracker.record(health(1, 1, &[Some(1)]));
+        // Replaces the previous observation of the same ISM
+        tracker.record(health(1, 1, &[Some(2)]));
+
+        let all = tracker.snapshot(None);
+        assert_eq!(all.iter().map(|h| h.origin).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(all[0].quorum_index, Some(2));
+        assert_eq!(tracker.snapshot(Some(3)).len(), 1);
+        assert!(tracker.snapshot(Some(4)).is_empty());
+    }
+}
@@ -0,0 +1,353 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use ethers::utils::keccak256;
+use eyre::Result;
+use hyperlane_base::db::{HyperlaneRocksDB, RateLimitBucket};
+use hyperlane_core::{HyperlaneMessage, H256};
+use tokio::task::JoinHandle;
+use tracing::{debug, info_span, instrument::Instrumented, warn, Instrument};
+
+use crate::settings::{RateLimitConf, RateLimitKey};
+
+/// How often the buckets that went idle are pruned
+const PRUNE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Token buckets that limit how quickly the messages of a sender or app are
+/// relayed, so a single spamming sender can't starve everyone else of
+/// submission bandwidth. Buckets are persisted in the origin db, so restarting
+/// the relayer doesn't refill them, and pruned once they are full again.
+#[derive(Debug)]
+pub struct RateLimiter {
+    limits: Vec<RateLimitConf>,
+    db: HyperlaneRocksDB,
+    /// The buckets in use, loaded from the db the first time they are used.
+    /// Messages are prepared concurrently, so buckets are updated under the
+    /// lock, but the db is only accessed without holding it.
+    buckets: Mutex<HashMap<H256, RateLimitBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: impl IntoIterator<Item = RateLimitConf>, db: HyperlaneRocksDB) -> Self {
+        Self {
+            limits: limits.into_iter().collect(),
+            db,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a token from every bucket the message falls into. If any of them
+    /// is empty, nothing is taken and the time until all of them have a token
+    /// again is returned.
+    pub fn try_acquire(
+        &self,
+        message: &HyperlaneMessage,
+        app_context: Option<&str>,
+    ) -> Result<(), Duration> {
+        if self.limits.is_empty() {
+            return Ok(());
+        }
+        self.try_acquire_at(message, app_context, now_millis())
+    }
+
+    fn try_acquire_at(
+        &self,
+        message: &HyperlaneMessage,
+        app_context: Option<&str>,
+        now: u64,
+    ) -> Result<(), Duration> {
+        let limits = self
+            .limits
+            .iter()
+            .enumerate()
+            .filter(|(_, limit)| limit.matching_list.msg_matches(message, true))
+            .filter_map(|(index, limit)| {
+                Some((bucket_key(index, limit.key, message, app_context)?, limit))
+            })
+            .collect::<Vec<_>>();
+
+        let unloaded = {
+            let buckets = self.lock_buckets();
+            limits
+                .iter()
+                .map(|(key, _)| *key)
+                .filter(|key| !buckets.contains_key(key))
+                .collect::<Vec<_>>()
+        };
+        let loaded = unloaded
+            .into_iter()
+            .filter_map(|key| Some((key, self.retrieve_bucket(&key)?)))
+            .collect::<Vec<_>>();
+
+        let updated = {
+            let mut buckets = self.lock_buckets();
+            for (key, bucket) in loaded {
+                // Another message may have loaded and updated it meanwhile
+                buckets.entry(key).or_insert(bucket);
+            }
+            let refilled = limits
+                .iter()
+                .map(|(key, limit)| (*key, *limit, refilled(buckets.get(key), limit, now)))
+                .collect::<Vec<_>>();
+
+            let wait = refilled
+                .iter()
+                .filter(|(_, _, bucket)| bucket.tokens < 1.)
+                .map(|(_, limit, bucket)| {
+                    limit
+                        .period
+                        .mul_f64((1. - bucket.tokens) / limit.max_messages as f64)
+                })
+                .max();
+            if let Some(wait) = wait {
+                return Err(wait);
+            }
+
+            refilled
+                .into_iter()
+                .map(|(key, _, bucket)| {
+                    let bucket = RateLimitBucket {
+                        tokens: bucket.tokens - 1.,
+                        updated_at: now,
+                    };
+                    buckets.insert(key, bucket);
+                    (key, bucket)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for (key, bucket) in updated {
+            if let Err(err) = self.db.store_rate_limit_bucket(&key, &bucket) {
+                warn!(?err, ?key, "Failed to persist rate limit bucket");
+            }
+        }
+        Ok(())
+    }
+
+    fn lock_buckets(&self) -> std::sync::MutexGuard<'_, HashMap<H256, RateLimitBucket>> {
+        self.buckets.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn retrieve_bucket(&self, key: &H256) -> Option<RateLimitBucket> {
+        self.db
+            .retrieve_rate_limit_bucket(key)
+            .unwrap_or_else(|err| {
+                warn!(?err, ?key, "Failed to retrieve rate limit bucket");
+                None
+            })
+    }
+
+    /// Removes the buckets that weren't used for the longest period of the
+    /// limits from memory and the db, since they are full again. Returns how
+    /// many were deleted from the db.
+    fn prune_at(&self, now: u64) -> Result<usize> {
+        // Without limits, none of the persisted buckets are of use anymore
+        let max_period = self
+            .limits
+            .iter()
+            .map(|limit| limit.period)
+            .max()
+            .unwrap_or_default();
+        let is_idle = |bucket: &RateLimitBucket| {
+            Duration::from_millis(now.saturating_sub(bucket.updated_at)) >= max_period
+        };
+
+        self.lock_buckets().retain(|_, bucket| !is_idle(bucket));
+        let mut pruned = 0;
+        for (key, bucket) in self.db.retrieve_rate_limit_buckets()? {
+            if is_idle(&bucket) {
+                self.db.delete_rate_limit_bucket(&key)?;
+                pruned += 1;
+            }
+        }
+        Ok(pruned)
+    }
+
+    /// Periodically prune the buckets that went idle, which would otherwise
+    /// pile up since there is one for every sender
+    pub fn spawn(self: Arc<Self>) -> Instrumented<JoinHandle<()>> {
+        tokio::spawn(async move {
+            loop {
+                match self.prune_at(now_millis()) {
+                    Ok(pruned) => debug!(pruned, "Pruned idle rate limit buckets"),
+                    Err(err) => warn!(?err, "Failed to prune rate limit buckets"),
+                }
+                tokio::time::sleep(PRUNE_INTERVAL).await;
+            }
+        })
+        .instrument(info_span!("RateLimiter"))
+    }
+}
+
+/// The bucket as it is at `now`, which starts out full
+fn refilled(bucket: Option<&RateLimitBucket>, limit: &RateLimitConf, now: u64) -> RateLimitBucket {
+    let capacity = limit.max_messages as f64;
+    let Some(bucket) = bucket else {
+        return RateLimitBucket {
+            tokens: capacity,
+            updated_at: now,
+        };
+    };
+    let elapsed = Duration::from_millis(now.saturating_sub(bucket.updated_at));
+    let refilled = elapsed.as_secs_f64() / limit.period.as_secs_f64() * capacity;
+    RateLimitBucket {
+        tokens: (bucket.tokens + refilled).min(capacity),
+        updated_at: now,
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Buckets are keyed by the position of their limit in the config, so
+/// reordering the limits resets them.
+fn bucket_key(
+    index: usize,
+    key: RateLimitKey,
+    message: &HyperlaneMessage,
+    app_context: Option<&str>,
+) -> Option<H256> {
+    let key = match key {
+        RateLimitKey::Sender => format!("{index}/{}/sender/{:?}", message.origin, message.sender),
+        RateLimitKey::AppContext => {
+            format!("{index}/{}/app_context/{}", message.origin, app_context?)
+        }
+    };
+    Some(keccak256(key).into())
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_base::db::test_utils;
+    use hyperlane_core::HyperlaneDomain;
+
+    use super::*;
+
+    fn limit(key: RateLimitKey, max_messages: u32, period_secs: u64) -> RateLimitConf {
+        RateLimitConf {
+            matching_list: Default::default(),
+            key,
+            max_messages,
+            period: Duration::from_secs(period_secs),
+        }
+    }
+
+    fn message(sender: H256) -> HyperlaneMessage {
+        HyperlaneMessage {
+            origin: 1,
+            sender,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_limits_each_sender() {
+        test_utils::run_test_db(|db| async move {
+            let domain = HyperlaneDomain::new_test_domain("rate_limiter");
+            let db = HyperlaneRocksDB::new(&domain, db);
+            let limiter = RateLimiter::new([limit(RateLimitKey::Sender, 2, 10)], db);
+            let spammer = message(H256::random());
+            let other = message(H256::random());
+
+            assert_eq!(limiter.try_acquire_at(&spammer, None, 0), Ok(()));
+            assert_eq!(limiter.try_acquire_at(&spammer, None, 0), Ok(()));
+            assert_eq!(
+                limiter.try_acquire_at(&spammer, None, 0),
+                Err(Duration::from_secs(5))
+            );
+            assert_eq!(limiter.try_acquire_at(&other, None, 0), Ok(()));
+
+            // A token is refilled every 5 seconds
+            assert_eq!(
+                limiter.try_acquire_at(&spammer, None, 2_500),
+                Err(Duration::from_millis(2_500))
+            );
+            assert_eq!(limiter.try_acquire_at(&spammer, None, 5_000), Ok(()));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_limits_by_app_context() {
+        test_utils::run_test_db(|db| async move {
+            let domain = HyperlaneDomain::new_test_domain("rate_limiter");
+            let db = HyperlaneRocksDB::new(&domain, db);
+            let limiter = RateLimiter::new([limit(RateLimitKey::AppContext, 1, 10)], db);
+
+            let app = Some("app");
+            assert_eq!(
+                limiter.try_acquire_at(&message(H256::random()), app, 0),
+                Ok(())
+            );
+            assert!(limiter
+                .try_acquire_at(&message(H256::random()), app, 0)
+                .is_err());
+            // Messages without an app context aren't limited
+            assert_eq!(
+                limiter.try_acquire_at(&message(H256::random()), None, 0),
+                Ok(())
+            );
+            assert_eq!(
+                limiter.try_acquire_at(&message(H256::random()), None, 0),
+                Ok(())
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_buckets_survive_restarts() {
+        test_utils::run_test_db(|db| async move {
+            let domain = HyperlaneDomain::new_test_domain("rate_limiter");
+            let db = HyperlaneRocksDB::new(&domain, db);
+            let sender = message(H256::random());
+
+            let limiter = RateLimiter::new([limit(RateLimitKey::Sender, 1, 10)], db.clone());
+            assert_eq!(limiter.try_acquire_at(&sender, None, 0), Ok(()));
+
+            let limiter = RateLimiter::new([limit(RateLimitKey::Sender, 1, 10)], db);
+            assert!(limiter.try_acquire_at(&sender, None, 0).is_err());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_prunes_idle_buckets() {
+        test_utils::run_test_db(|db| async move {
+            let domain = HyperlaneDomain::new_test_domain("rate_limiter");
+            let db = HyperlaneRocksDB::new(&domain, db);
+            let limits = [
+                limit(RateLimitKey::Sender, 1, 10),
+                limit(RateLimitKey::Sender, 1, 20),
+            ];
+            let idle = message(H256::random());
+            let active = message(H256::random());
+
+            let limiter = RateLimiter::new(limits.clone(), db.clone());
+            assert_eq!(limiter.try_acquire_at(&idle, None, 0), Ok(()));
+            assert_eq!(limiter.try_acquire_at(&active, None, 10_000), Ok(()));
+            assert_eq!(db.retrieve_rate_limit_buckets().unwrap().len(), 4);
+
+            // The buckets of `idle` are only full again after the longest period
+            assert_eq!(limiter.prune_at(19_999).unwrap(), 0);
+            assert_eq!(limiter.prune_at(20_000).unwrap(), 2);
+            assert_eq!(db.retrieve_rate_limit_buckets().unwrap().len(), 2);
+            assert_eq!(limiter.lock_buckets().len(), 2);
+            assert_eq!(limiter.try_acquire_at(&idle, None, 20_000), Ok(()));
+            assert!(limiter.try_acquire_at(&active, None, 20_000).is_err());
+
+            // Buckets persisted before a restart are pruned too
+            let limiter = RateLimiter::new(limits, db.clone());
+            assert_eq!(limiter.prune_at(40_000).unwrap(), 4);
+            assert!(db.retrieve_rate_limit_buckets().unwrap().is_empty());
+        })
+        .await;
+    }
+}
@@ -6,25 +6,25 @@ use std::{
 
 use async_trait::async_trait;
 use derive_more::AsRef;
-use eyre::Result;
+use eyre::{Context, Result};
 use futures_util::future::try_join_all;
 use hyperlane_base::{
     broadcast::BroadcastMpscSender,
     db::{HyperlaneRocksDB, DB},
     metrics::{AgentMetrics, MetricsUpdater},
     settings::{ChainConf, IndexSettings},
-    AgentMetadata, BaseAgent, ChainMetrics, ContractSyncMetrics, ContractSyncer, CoreMetrics,
-    HyperlaneAgentCore, SyncOptions,
+    AgentMetadata, BaseAgent, ChainMetrics, CheckpointCache, ContractSyncMetrics, ContractSyncer,
+    CoreMetrics, HyperlaneAgentCore, SyncOptions,
 };
 use hyperlane_core::{
     rpc_clients::call_and_retry_n_times, ChainCommunicationError, ContractSyncCursor,
-    HyperlaneDomain, HyperlaneMessage, InterchainGasPayment, MerkleTreeInsertion, QueueOperation,
-    H512, U256,
+    HyperlaneDomain, HyperlaneMessage, InterchainGasPayment, Mailbox, MerkleTreeInsertion,
+    QueueOperation, SubmitterQueue, H512, U256,
 };
 use tokio::{
     sync::{
         broadcast::Sender as BroadcastSender,
-        mpsc::{self, Receiver as MpscReceiver, UnboundedSender},
+        mpsc::{self, Receiver as MpscReceiver, UnboundedReceiver, UnboundedSender},
         RwLock,
     },
     task::JoinHandle,
@@ -36,14 +36,21 @@ use crate::{
     merkle_tree::builder::MerkleTreeBuilder,
     msg::{
         blacklist::AddressBlacklist,
+        events::OperationEvents,
+        filters::{MessageFilters, SharedMessageFilters},
         gas_payment::GasPaymentEnforcer,
         metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier},
-        op_submitter::{SerialSubmitter, SerialSubmitterMetrics},
+        op_submitter::{
+            persist_queue_snapshots, SerialSubmitter, SerialSubmitterMetrics, SubmitterPause,
+        },
         pending_message::{MessageContext, MessageSubmissionMetrics},
         processor::{MessageProcessor, MessageProcessorMetrics},
+        profitability::ProfitabilityLedger,
+        quorum_health::QuorumHealthTracker,
+        rate_limiter::RateLimiter,
     },
     server::{self as relayer_server},
-    settings::{matching_list::MatchingList, RelayerSettings},
+    settings::{matching_list::MatchingList, PriorityLaneConf, RelayerSettings},
 };
 use crate::{
     merkle_tree::processor::{MerkleTreeProcessor, MerkleTreeProcessorMetrics},
@@ -76,13 +83,28 @@ pub struct Relayer {
     prover_syncs: HashMap<HyperlaneDomain, Arc<RwLock<MerkleTreeBuilder>>>,
     merkle_tree_hook_syncs: HashMap<HyperlaneDomain, Arc<dyn ContractSyncer<MerkleTreeInsertion>>>,
     dbs: HashMap<HyperlaneDomain, HyperlaneRocksDB>,
-    message_whitelist: Arc<MatchingList>,
-    message_blacklist: Arc<MatchingList>,
-    address_blacklist: Arc<AddressBlacklist>,
+    /// Databases of the destination chains, used to persist submitter state
+    destination_dbs: HashMap<HyperlaneDomain, HyperlaneRocksDB>,
+    /// The relayer database, for state that isn't scoped to a domain
+    db: DB,
+    message_filters: SharedMessageFilters,
     transaction_gas_limit: Option<U256>,
     skip_transaction_gas_limit_for: HashSet<u32>,
     allow_local_checkpoint_syncers: bool,
+    /// If true, messages are prepared but never submitted
+    dry_run: bool,
     metric_app_contexts: Vec<(MatchingList, String)>,
+    /// Priority lanes of the submission queues, keyed by destination domain id
+    priority_lanes: HashMap<u32, PriorityLaneConf>,
+    /// Mailboxes of the destinations with a signer pool, one per signer
+    submission_mailboxes: HashMap<HyperlaneDomain, Vec<Arc<dyn Mailbox>>>,
+    /// Rate limiters of the messages, by origin
+    rate_limiters: HashMap<HyperlaneDomain, Arc<RateLimiter>>,
+    profitability_ledger: Arc<ProfitabilityLedger>,
+    /// Lifecycle events of the relayed messages
+    operation_events: OperationEvents,
+    /// Health of the validator sets of the multisig ISMs, shared by all routes
+    quorum_health: Arc<QuorumHealthTracker>,
     core_metrics: Arc<CoreMetrics>,
     // TODO: decide whether to consolidate `agent_metrics` and `chain_metrics` into a single struct
     // or move them in `core_metrics`, like the validator metrics
@@ -96,15 +118,14 @@ impl Debug for Relayer {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Relayer {{ origin_chains: {:?}, destination_chains: {:?}, message_whitelist: {:?}, message_blacklist: {:?}, address_blacklist: {:?}, transaction_gas_limit: {:?}, skip_transaction_gas_limit_for: {:?}, allow_local_checkpoint_syncers: {:?} }}",
+            "Relayer {{ origin_chains: {:?}, destination_chains: {:?}, message_filters: {:?}, transaction_gas_limit: {:?}, skip_transaction_gas_limit_for: {:?}, allow_local_checkpoint_syncers: {:?}, dry_run: {:?} }}",
             self.origin_chains,
             self.destination_chains,
-            self.message_whitelist,
-            self.message_blacklist,
-            self.address_blacklist,
+            self.message_filters,
             self.transaction_gas_limit,
             self.skip_transaction_gas_limit_for,
-            self.allow_local_checkpoint_syncers
+            self.allow_local_checkpoint_syncers,
+            self.dry_run
         )
     }
 }
@@ -134,6 +155,16 @@ impl BaseAgent for Relayer {
             .iter()
             .map(|origin| (origin.clone(), HyperlaneRocksDB::new(origin, db.clone())))
             .collect::<HashMap<_, _>>();
+        let destination_dbs = settings
+            .destination_chains
+            .iter()
+            .map(|destination| {
+                (
+                    destination.clone(),
+                    HyperlaneRocksDB::new(destination, db.clone()),
+                )
+            })
+            .collect::<HashMap<_, _>>();
 
         let mailboxes = settings
             .build_mailboxes(settings.destination_chains.iter(), &core_metrics)
@@ -189,9 +220,9 @@ impl BaseAgent for Relayer {
             .map(|(k, v)| (k, v as _))
             .collect();
 
-        let message_whitelist = Arc::new(settings.whitelist);
-        let message_blacklist = Arc::new(settings.blacklist);
-        let address_blacklist = Arc::new(AddressBlacklist::new(settings.address_blacklist));
+        let message_whitelist = settings.whitelist;
+        let message_blacklist = settings.blacklist;
+        let address_blacklist = AddressBlacklist::new(settings.address_blacklist);
         let skip_transaction_gas_limit_for = settings.skip_transaction_gas_limit_for;
         let transaction_gas_limit = settings.transaction_gas_limit;
 
@@ -203,6 +234,17 @@ impl BaseAgent for Relayer {
             ?skip_transaction_gas_limit_for,
             "Whitelist configuration"
         );
+        if settings.dry_run {
+            warn!("Running in dry run mode, messages will be prepared but not submitted");
+        }
+
+        let message_filters = Arc::new(RwLock::new(
+            relayer_server::restore_filter_updates(
+                &db,
+                &MessageFilters::new(message_whitelist, message_blacklist, address_blacklist),
+            )
+            .context("Restoring the message filters updated at runtime")?,
+        ));
 
         // provers by origin chain
         let prover_syncs = settings
@@ -234,11 +276,55 @@ impl BaseAgent for Relayer {
             })
             .collect();
 
+        let rate_limiters: HashMap<_, _> = settings
+            .origin_chains
+            .iter()
+            .map(|domain| {
+                (
+                    domain.clone(),
+                    Arc::new(RateLimiter::new(
+                        settings.rate_limits.clone(),
+                        dbs.get(domain).unwrap().clone(),
+                    )),
+                )
+            })
+            .collect();
+
+        let profitability_ledger = Arc::new(ProfitabilityLedger::new(
+            dbs.iter()
+                .map(|(domain, db)| (domain.id(), db.clone()))
+                .collect(),
+            settings
+                .origin_chains
+                .iter()
+                .chain(&settings.destination_chains)
+                .map(|domain| (domain.id(), domain.name().to_owned()))
+                .collect(),
+            &core_metrics,
+        ));
+
+        // All routes from an origin share the checkpoints fetched from the
+        // checkpoint syncers of its validators
+        let checkpoint_cache = Arc::new(CheckpointCache::with_defaults(&core_metrics));
+        let quorum_health = Arc::new(QuorumHealthTracker::default());
+        let operation_events = OperationEvents::default();
+
         let mut msg_ctxs = HashMap::new();
         let mut destination_chains = HashMap::new();
+        let mut submission_mailboxes = HashMap::new();
         for destination in &settings.destination_chains {
             let destination_chain_setup = core.settings.chain_setup(destination).unwrap().clone();
             destination_chains.insert(destination.clone(), destination_chain_setup.clone());
+            // The chain's own signer submits alongside the signers of its pool
+            let signer_pool_mailboxes = destination_chain_setup
+                .build_signer_pool_mailboxes(&core_metrics)
+                .await?;
+            if !signer_pool_mailboxes.is_empty() {
+                let pool = std::iter::once(mailboxes[destination].clone())
+                    .chain(signer_pool_mailboxes.into_iter().map(Arc::from))
+                    .collect::<Vec<_>>();
+                submission_mailboxes.insert(destination.clone(), pool);
+            }
             let transaction_gas_limit: Option<U256> =
                 if skip_transaction_gas_limit_for.contains(&destination.id()) {
                     None
@@ -254,7 +340,10 @@ impl BaseAgent for Relayer {
                     prover_syncs[origin].clone(),
                     validator_announces[origin].clone(),
                     settings.allow_local_checkpoint_syncers,
+                    settings.sub_module_metadata_timeout,
                     core.metrics.clone(),
+                    checkpoint_cache.clone(),
+                    quorum_health.clone(),
                     db,
                     IsmAwareAppContextClassifier::new(
                         mailboxes[destination].clone(),
@@ -273,6 +362,12 @@ impl BaseAgent for Relayer {
                         metadata_builder: Arc::new(metadata_builder),
                         origin_gas_payment_enforcer: gas_payment_enforcers[origin].clone(),
                         transaction_gas_limit,
+                        dry_run: settings.dry_run,
+                        dead_letter: settings.dead_letter.clone(),
+                        rate_limiter: rate_limiters[origin].clone(),
+                        profitability_ledger: profitability_ledger.clone(),
+                        priority_lane: settings.priority_lanes.get(&destination.id()).cloned(),
+                        events: operation_events.clone(),
                         metrics: MessageSubmissionMetrics::new(&core_metrics, origin, destination),
                     }),
                 );
@@ -281,6 +376,8 @@ impl BaseAgent for Relayer {
 
         Ok(Self {
             dbs,
+            destination_dbs,
+            db,
             origin_chains: settings.origin_chains,
             destination_chains,
             msg_ctxs,
@@ -289,13 +386,18 @@ impl BaseAgent for Relayer {
             interchain_gas_payment_syncs,
             prover_syncs,
             merkle_tree_hook_syncs,
-            message_whitelist,
-            message_blacklist,
-            address_blacklist,
+            message_filters,
             transaction_gas_limit,
             skip_transaction_gas_limit_for,
             allow_local_checkpoint_syncers: settings.allow_local_checkpoint_syncers,
+            dry_run: settings.dry_run,
             metric_app_contexts: settings.metric_app_contexts,
+            priority_lanes: settings.priority_lanes,
+            submission_mailboxes,
+            rate_limiters,
+            profitability_ledger,
+            operation_events,
+            quorum_health,
             core_metrics,
             agent_metrics,
             chain_metrics,
@@ -322,23 +424,66 @@ impl BaseAgent for Relayer {
         // send channels by destination chain
         let mut send_channels = HashMap::with_capacity(self.destination_chains.len());
         let mut prep_queues = HashMap::with_capacity(self.destination_chains.len());
+        let mut submitter_queues = HashMap::with_capacity(self.destination_chains.len());
+        let mut submitter_pauses = HashMap::with_capacity(self.destination_chains.len());
+        // persisted on shutdown, and restored by the message processors on startup
+        let mut snapshot_queues = Vec::with_capacity(self.destination_chains.len() * 3);
         for (dest_domain, dest_conf) in &self.destination_chains {
             let (send_channel, receive_channel) = mpsc::unbounded_channel::<QueueOperation>();
             send_channels.insert(dest_domain.id(), send_channel);
+            // Batches are submitted without going through the operations, so they
+            // are disabled in dry run mode.
+            let batch_config = self.core.settings.chains[dest_domain.name()]
+                .connection
+                .operation_batch_config()
+                .filter(|_| !self.dry_run);
             let serial_submitter = SerialSubmitter::new(
                 dest_domain.clone(),
                 receive_channel,
                 sender.clone(),
                 SerialSubmitterMetrics::new(&self.core.metrics, dest_domain),
-                // Default to submitting one message at a time if there is no batch config
-                self.core.settings.chains[dest_domain.name()]
-                    .connection
-                    .operation_batch_config()
-                    .map(|c| c.max_batch_size)
-                    .unwrap_or(1),
+                // Default to submitting one message at a time if there is no batch config.
+                batch_config.map(|c| c.max_batch_size).unwrap_or(1),
+                batch_config.and_then(|c| c.max_batch_gas),
+                self.priority_lanes
+                    .get(&dest_domain.id())
+                    .map(|lane| lane.premium_weight),
                 task_monitor.clone(),
+                SubmitterPause::new(
+                    self.destination_dbs[dest_domain].clone(),
+                    &self.core.metrics,
+                ),
+            )
+            .with_submission_mailboxes(
+                self.submission_mailboxes
+                    .get(dest_domain)
+                    .cloned()
+                    .unwrap_or_default(),
             );
+            submitter_pauses.insert(dest_domain.id(), serial_submitter.pause());
             prep_queues.insert(dest_domain.id(), serial_submitter.prepare_queue().await);
+            submitter_queues.insert(
+                dest_domain.id(),
+                vec![
+                    ("prepare_queue", serial_submitter.prepare_queue().await),
+                    ("submit_queue", serial_submitter.submit_queue().await),
+                    ("confirm_queue", serial_submitter.confirm_queue().await),
+                ],
+            );
+            snapshot_queues.extend([
+                (
+                    SubmitterQueue::Prepare,
+                    serial_submitter.prepare_queue().await,
+                ),
+                (
+                    SubmitterQueue::Submit,
+                    serial_submitter.submit_queue().await,
+                ),
+                (
+                    SubmitterQueue::Confirm,
+                    serial_submitter.confirm_queue().await,
+                ),
+            ]);
 
             tasks.push(self.run_destination_submitter(
                 dest_domain,
@@ -384,10 +529,54 @@ impl BaseAgent for Relayer {
                 .await,
             );
         }
+        for rate_limiter in self.rate_limiters.values() {
+            tasks.push(rate_limiter.clone().spawn());
+        }
+        tasks.push(self.profitability_ledger.clone().spawn());
+
+        // channels to requeue dead-lettered messages, by origin chain
+        let mut requeue_txs = HashMap::with_capacity(self.origin_chains.len());
+        let mut requeue_rxs = HashMap::with_capacity(self.origin_chains.len());
+        // channels to expedite the messages of relayed transactions, by origin chain
+        let mut relay_txs = HashMap::with_capacity(self.origin_chains.len());
+        let mut relay_rxs = HashMap::with_capacity(self.origin_chains.len());
+        for origin in &self.origin_chains {
+            let (requeue_tx, requeue_rx) = mpsc::unbounded_channel::<HyperlaneMessage>();
+            requeue_txs.insert(origin.id(), requeue_tx);
+            requeue_rxs.insert(origin.id(), requeue_rx);
+            let (relay_tx, relay_rx) = mpsc::unbounded_channel::<HyperlaneMessage>();
+            relay_txs.insert(origin.id(), relay_tx);
+            relay_rxs.insert(origin.id(), relay_rx);
+        }
+        let message_syncs_by_id: HashMap<_, _> = self
+            .message_syncs
+            .iter()
+            .map(|(domain, sync)| (domain.id(), sync.clone()))
+            .collect();
+
+        let dbs_by_id: HashMap<_, _> = self
+            .dbs
+            .iter()
+            .map(|(domain, db)| (domain.id(), db.clone()))
+            .collect();
+
         // run server
         let custom_routes = relayer_server::Server::new()
             .with_op_retry(sender.clone())
             .with_message_queue(prep_queues)
+            .with_dead_letter_queue(dbs_by_id.clone(), requeue_txs)
+            .with_message_status(dbs_by_id.clone(), submitter_queues.clone())
+            .with_submitter_pause(submitter_pauses.clone())
+            .with_filters(
+                self.message_filters.clone(),
+                self.db.clone(),
+                dbs_by_id,
+                submitter_queues,
+            )
+            .with_profitability(self.profitability_ledger.clone())
+            .with_relay_tx(message_syncs_by_id, relay_txs)
+            .with_events(self.operation_events.clone())
+            .with_quorum_health(self.quorum_health.clone())
             .routes();
 
         let server = self
@@ -405,20 +594,62 @@ impl BaseAgent for Relayer {
             tasks.push(self.run_message_processor(
                 origin,
                 send_channels.clone(),
+                requeue_rxs.remove(&origin.id()).unwrap(),
+                relay_rxs.remove(&origin.id()).unwrap(),
                 task_monitor.clone(),
             ));
             tasks.push(self.run_merkle_tree_processor(origin, task_monitor.clone()));
         }
 
-        if let Err(err) = try_join_all(tasks).await {
-            tracing::error!(
-                error=?err,
-                "Relayer task panicked"
-            );
+        tokio::select! {
+            res = try_join_all(tasks) => {
+                if let Err(err) = res {
+                    tracing::error!(
+                        error=?err,
+                        "Relayer task panicked"
+                    );
+                }
+            }
+            _ = shutdown_signal() => {
+                info!("Shutting down, persisting the submission queues");
+                let pauses = submitter_pauses.into_values().collect::<Vec<_>>();
+                persist_queue_snapshots(&pauses, &snapshot_queues).await;
+            }
         }
     }
 }
 
+/// Resolves when the relayer is asked to shut down gracefully
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    res = tokio::signal::ctrl_c() => {
+                        if let Err(err) = res {
+                            error!(?err, "Failed to listen for ctrl-c, only listening for SIGTERM");
+                            sigterm.recv().await;
+                        }
+                    }
+                    _ = sigterm.recv() => {}
+                }
+                return;
+            }
+            Err(err) => {
+                error!(
+                    ?err,
+                    "Failed to install SIGTERM handler, only listening for ctrl-c"
+                );
+            }
+        }
+    }
+    if let Err(err) = tokio::signal::ctrl_c().await {
+        error!(?err, "Failed to listen for the shutdown signal");
+        std::future::pending::<()>().await;
+    }
+}
+
 impl Relayer {
     fn record_critical_error(
         &self,
@@ -542,6 +773,8 @@ impl Relayer {
         &self,
         origin: &HyperlaneDomain,
         send_channels: HashMap<u32, UnboundedSender<QueueOperation>>,
+        requeue_receiver: UnboundedReceiver<HyperlaneMessage>,
+        relay_tx_receiver: UnboundedReceiver<HyperlaneMessage>,
         task_monitor: TaskMonitor,
     ) -> Instrumented<JoinHandle<()>> {
         let metrics = MessageProcessorMetrics::new(
@@ -565,16 +798,17 @@ impl Relayer {
             })
             .collect();
 
-        let message_processor = MessageProcessor::new(
+        let mut message_processor = MessageProcessor::new(
             self.dbs.get(origin).unwrap().clone(),
-            self.message_whitelist.clone(),
-            self.message_blacklist.clone(),
-            self.address_blacklist.clone(),
+            self.message_filters.clone(),
             metrics,
             send_channels,
             destination_ctxs,
             self.metric_app_contexts.clone(),
+            requeue_receiver,
+            relay_tx_receiver,
         );
+        message_processor.restore_queue_snapshots(self.dbs.get(origin).unwrap());
 
         let span = info_span!("MessageProcessor", origin=%message_processor.domain());
         let processor = Processor::new(Box::new(message_processor), task_monitor.clone());
@@ -0,0 +1,321 @@
+use axum::{
+    extract::{Path, State},
+    routing, Router,
+};
+use derive_new::new;
+use eyre::{eyre, Result};
+use hyperlane_base::db::{DeadLetteredMessage, HyperlaneDb, HyperlaneRocksDB};
+use hyperlane_core::{HyperlaneMessage, H256};
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+const DEAD_LETTER_QUEUE_API_BASE: &str = "/dead_letter_queue";
+
+#[derive(new, Clone)]
+pub struct DeadLetterQueueApi {
+    /// Databases of the origin chains, keyed by domain id
+    dbs: HashMap<u32, HyperlaneRocksDB>,
+    /// Channels to send requeued messages to the message processor of their
+    /// origin chain, keyed by domain id
+    requeue_txs: HashMap<u32, UnboundedSender<HyperlaneMessage>>,
+}
+
+fn parse_message_id(message_id: &str) -> Result<H256> {
+    H256::from_str(message_id).map_err(|e| eyre!("Invalid message id `{message_id}`: {e}"))
+}
+
+fn find_entry(
+    dbs: &HashMap<u32, HyperlaneRocksDB>,
+    message_id: &H256,
+) -> Result<Option<(HyperlaneRocksDB, DeadLetteredMessage)>> {
+    for db in dbs.values() {
+        if let Some(entry) = db.retrieve_dead_lettered_message_by_id(message_id)? {
+            return Ok(Some((db.clone(), entry)));
+        }
+    }
+    Ok(None)
+}
+
+fn to_json_string<T: serde::Serialize>(value: &T) -> String {
+    match serde_json::to_string_pretty(value) {
+        Ok(s) => s,
+        Err(e) => format!("Error formatting dead-letter queue: {}", e),
+    }
+}
+
+async fn list_dead_lettered(State(api): State<DeadLetterQueueApi>) -> String {
+    let mut entries = vec![];
+    for db in api.dbs.values() {
+        match db.retrieve_dead_lettered_messages() {
+            Ok(mut db_entries) => entries.append(&mut db_entries),
+            Err(e) => return format!("Error reading dead-letter queue: {}", e),
+        }
+    }
+    entries.sort_by_key(|e| (e.origin, e.nonce));
+    to_json_string(&entries)
+}
+
+async fn inspect_dead_lettered(
+    State(api): State<DeadLetterQueueApi>,
+    Path(message_id): Path<String>,
+) -> String {
+    let res = parse_message_id(&message_id).and_then(|id| find_entry(&api.dbs, &id));
+    match res {
+        Ok(Some((_, entry))) => to_json_string(&entry),
+        Ok(None) => format!("Message {} is not in the dead-letter queue", message_id),
+        Err(e) => format!("Error reading dead-letter queue: {}", e),
+    }
+}
+
+async fn requeue_dead_lettered(
+    State(api): State<DeadLetterQueueApi>,
+    Path(message_id): Path<String>,
+) -> String {
+    match requeue(&api, &message_id) {
+        Ok(Some(entry)) => format!(
+            "Requeued message {:?} (origin {}, nonce {})",
+            entry.message_id, entry.origin, entry.nonce
+        ),
+        Ok(None) => format!("Message {} is not in the dead-letter queue", message_id),
+        Err(e) => format!("Failed to requeue message {}: {}", message_id, e),
+    }
+}
+
+fn requeue(api: &DeadLetterQueueApi, message_id: &str) -> Result<Option<DeadLetteredMessage>> {
+    let id = parse_message_id(message_id)?;
+    let Some((db, entry)) = find_entry(&api.dbs, &id)? else {
+        return Ok(None);
+    };
+    let message = db
+        .retrieve_message_by_id(&id)?
+        .ok_or_else(|| eyre!("Message {id:?} not found in the origin database"))?;
+    let tx = api
+        .requeue_txs
+        .get(&entry.origin)
+        .ok_or_else(|| eyre!("No message processor running for origin {}", entry.origin))?;
+
+    // Start the backoff from scratch, otherwise the message would wait for as
+    // long as it did before being dead-lettered
+    db.store_pending_message_retry_count_by_message_id(&id, &0)?;
+    db.remove_dead_lettered_message_by_id(&id)?;
+    if let Err(e) = tx.send(message) {
+        db.store_dead_lettered_message_by_id(&id, &entry)?;
+        return Err(e.into());
+    }
+    Ok(Some(entry))
+}
+
+async fn drop_dead_lettered(
+    State(api): State<DeadLetterQueueApi>,
+    Path(message_id): Path<String>,
+) -> String {
+    match drop_entry(&api, &message_id) {
+        Ok(Some(entry)) => format!(
+            "Dropped message {:?} (origin {}, nonce {})",
+            entry.message_id, entry.origin, entry.nonce
+        ),
+        Ok(None) => format!("Message {} is not in the dead-letter queue", message_id),
+        Err(e) => format!("Failed to drop message {}: {}", message_id, e),
+    }
+}
+
+fn drop_entry(api: &DeadLetterQueueApi, message_id: &str) -> Result<Option<DeadLetteredMessage>> {
+    let id = parse_message_id(message_id)?;
+    let Some((db, mut entry)) = find_entry(&api.dbs, &id)? else {
+        return Ok(None);
+    };
+    // The entry is kept with its drop time, which stops the message processor
+    // from picking the message up again and tells it apart from a delivered one
+    if entry.dropped_at.is_none() {
+        entry.dropped_at = Some(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        );
+        db.store_dead_lettered_message_by_id(&id, &entry)?;
+    }
+    Ok(Some(entry))
+}
+
+impl DeadLetterQueueApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(list_dead_lettered))
+            .route(
+                "/:message_id",
+                routing::get(inspect_dead_lettered).delete(drop_dead_lettered),
+            )
+            .route("/:message_id/requeue", routing::post(requeue_dead_lettered))
+            .with_state(self.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (DEAD_LETTER_QUEUE_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use hyperlane_base::db::{test_utils, DB};
+    use hyperlane_core::{HyperlaneDomain, ReprepareReason};
+    use std::net::SocketAddr;
+    use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+    fn setup_test_server(
+        db: DB,
+    ) -> (
+        SocketAddr,
+        HyperlaneRocksDB,
+        UnboundedReceiver<HyperlaneMessage>,
+    ) {
+        let domain = HyperlaneDomain::new_test_domain("dead_letter_queue_api");
+        let db = HyperlaneRocksDB::new(&domain, db);
+        let (requeue_tx, requeue_rx) = mpsc::unbounded_channel();
+
+        let api = DeadLetterQueueApi::new(
+            HashMap::from([(domain.id(), db.clone())]),
+            HashMap::from([(domain.id(), requeue_tx)]),
+        );
+        let (path, router) = api.get_route();
+        let app = Router::new().nest(path, router);
+
+        // Running the app in the background using a test server
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        (addr, db, requeue_rx)
+    }
+
+    fn dead_letter_message(db: &HyperlaneRocksDB, nonce: u32) -> HyperlaneMessage {
+        let message = HyperlaneMessage {
+            nonce,
+            origin: db.domain().id(),
+            destination: db.domain().id() + 1,
+            ..Default::default()
+        };
+        db.store_message(&message, Default::default()).unwrap();
+        db.store_pending_message_retry_count_by_message_id(&message.id(), &20)
+            .unwrap();
+        db.store_dead_lettered_message_by_id(
+            &message.id(),
+            &DeadLetteredMessage {
+                message_id: message.id(),
+                origin: message.origin,
+                destination: message.destination,
+                nonce,
+                num_retries: 20,
+                reason: ReprepareReason::ErrorEstimatingGas,
+                dead_lettered_at: 1_700_000_000,
+                dropped_at: None,
+            },
+        )
+        .unwrap();
+        message
+    }
+
+    #[tokio::test]
+    async fn test_list_and_inspect_dead_lettered_messages() {
+        test_utils::run_test_db(|db| async move {
+            let (addr, db, _rx) = setup_test_server(db);
+            let first = dead_letter_message(&db, 0);
+            let second = dead_letter_message(&db, 1);
+
+            let response = reqwest::get(format!("http://{}{}", addr, DEAD_LETTER_QUEUE_API_BASE))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let entries: Vec<DeadLetteredMessage> =
+                serde_json::from_str(&response.text().await.unwrap()).unwrap();
+            assert_eq!(
+                entries.iter().map(|e| e.message_id).collect::<Vec<_>>(),
+                vec![first.id(), second.id()]
+            );
+
+            let response = reqwest::get(format!(
+                "http://{}{}/{:?}",
+                addr,
+                DEAD_LETTER_QUEUE_API_BASE,
+                second.id()
+            ))
+            .await
+            .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let entry: DeadLetteredMessage =
+                serde_json::from_str(&response.text().await.unwrap()).unwrap();
+            assert_eq!(entry, entries[1]);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_requeue_dead_lettered_message() {
+        test_utils::run_test_db(|db| async move {
+            let (addr, db, mut rx) = setup_test_server(db);
+            let message = dead_letter_message(&db, 0);
+
+            let response = reqwest::Client::new()
+                .post(format!(
+                    "http://{}{}/{:?}/requeue",
+                    addr,
+                    DEAD_LETTER_QUEUE_API_BASE,
+                    message.id()
+                ))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            assert_eq!(rx.recv().await.unwrap(), message);
+            assert_eq!(
+                db.retrieve_dead_lettered_message_by_id(&message.id())
+                    .unwrap(),
+                None
+            );
+            assert_eq!(
+                db.retrieve_pending_message_retry_count_by_message_id(&message.id())
+                    .unwrap(),
+                Some(0)
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_drop_dead_lettered_message() {
+        test_utils::run_test_db(|db| async move {
+            let (addr, db, mut rx) = setup_test_server(db);
+            let message = dead_letter_message(&db, 0);
+
+            let response = reqwest::Client::new()
+                .delete(format!(
+                    "http://{}{}/{:?}",
+                    addr,
+                    DEAD_LETTER_QUEUE_API_BASE,
+                    message.id()
+                ))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            assert!(rx.try_recv().is_err());
+            let entry = db
+                .retrieve_dead_lettered_message_by_id(&message.id())
+                .unwrap()
+                .unwrap();
+            assert!(entry.dropped_at.is_some());
+            // A dropped message isn't reported as delivered
+            assert_eq!(db.retrieve_processed_by_nonce(&0).unwrap(), None);
+        })
+        .await;
+    }
+}
@@ -0,0 +1,144 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+    routing, Router,
+};
+use derive_new::new;
+use futures::{stream, Stream};
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{msg::events::OperationEvents, settings::matching_list::MatchingList};
+
+const EVENTS_API_BASE: &str = "/events";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct EventsQuery {
+    /// JSON encoded `MatchingList` the messages of the streamed events must
+    /// match. All events are streamed if it is omitted.
+    filter: Option<String>,
+}
+
+#[derive(new, Clone)]
+pub struct EventsApi {
+    events: OperationEvents,
+}
+
+/// Streams the lifecycle events of the relayed messages as server-sent events,
+/// named after the kind of the event. Subscribers that fall behind receive a
+/// `lagged` event with the number of events they missed.
+async fn stream_events(
+    State(events): State<OperationEvents>,
+    Query(query): Query<EventsQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, (StatusCode, String)> {
+    let filter = match query.filter {
+        Some(filter) => serde_json::from_str::<MatchingList>(&filter)
+            .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid filter: {}", e)))?,
+        None => MatchingList::default(),
+    };
+    let stream = stream::unfold(
+        (events.subscribe(), filter),
+        |(mut receiver, filter)| async move {
+            let event = loop {
+                match receiver.recv().await {
+                    Ok(event) if event.matches(&filter, true) => {
+                        break Event::default().event(event.kind.name()).json_data(&event);
+                    }
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(missed)) => {
+                        break Ok(Event::default().event("lagged").data(missed.to_string()));
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            };
+            Some((event, (receiver, filter)))
+        },
+    );
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+impl EventsApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(stream_events))
+            .with_state(self.events.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (EVENTS_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::events::OperationEventKind;
+    use hyperlane_core::{HyperlaneMessage, PendingOperationStatus};
+    use std::net::SocketAddr;
+
+    fn setup_test_server() -> (SocketAddr, OperationEvents) {
+        let events = OperationEvents::default();
+        let api = EventsApi::new(events.clone());
+        let (path, router) = api.get_route();
+        let app = Router::new().nest(path, router);
+
+        // Running the app in the background using a test server
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        (addr, events)
+    }
+
+    #[tokio::test]
+    async fn test_stream_filtered_events() {
+        let (addr, events) = setup_test_server();
+        let filter = serde_json::json!([{ "origindomain": 1 }]).to_string();
+        let mut response = reqwest::Client::new()
+            .get(format!("http://{}{}", addr, EVENTS_API_BASE))
+            .query(&[("filter", filter)])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let filtered_out = HyperlaneMessage {
+            origin: 2,
+            ..Default::default()
+        };
+        let matching = HyperlaneMessage {
+            origin: 1,
+            nonce: 7,
+            ..Default::default()
+        };
+        for message in [&filtered_out, &matching] {
+            events.publish(
+                message,
+                None,
+                OperationEventKind::StatusChanged {
+                    status: PendingOperationStatus::ReadyToSubmit,
+                },
+            );
+        }
+
+        let chunk = response.chunk().await.unwrap().unwrap();
+        let chunk = String::from_utf8(chunk.to_vec()).unwrap();
+        assert!(chunk.starts_with("event: status_changed\n"));
+        assert!(chunk.contains(&format!("{:?}", matching.id())));
+        assert!(!chunk.contains(&format!("{:?}", filtered_out.id())));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_filter_is_rejected() {
+        let (addr, _) = setup_test_server();
+        let response = reqwest::Client::new()
+            .get(format!("http://{}{}", addr, EVENTS_API_BASE))
+            .query(&[("filter", "not json")])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,404 @@
+use axum::{extract::State, routing, Json, Router};
+use derive_new::new;
+use ethers::utils::hex;
+use eyre::{Context, Result};
+use hyperlane_base::db::{HyperlaneDb, HyperlaneRocksDB, DB};
+use hyperlane_core::H256;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use tracing::info;
+
+use crate::{
+    msg::{
+        blacklist::AddressBlacklist,
+        filters::{MessageFilters, SharedMessageFilters},
+    },
+    server::SubmitterQueues,
+    settings::matching_list::MatchingList,
+};
+
+const FILTERS_API_BASE: &str = "/filters";
+
+/// Key of the filter updates in the relayer db. The filters apply to every
+/// origin, so unlike the other keys it isn't scoped to a domain.
+const FILTER_UPDATES_KEY: &[u8] = b"relayer_message_filter_updates";
+
+/// Replaces the filters that are set. Filters that are omitted are left
+/// unchanged. Updates are persisted and applied over the configured filters
+/// again after a restart.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateFiltersRequest {
+    #[serde(default)]
+    whitelist: Option<MatchingList>,
+    #[serde(default)]
+    blacklist: Option<MatchingList>,
+    /// Hex encoded addresses, with or without a `0x` prefix
+    #[serde(default)]
+    address_blacklist: Option<Vec<String>>,
+}
+
+#[derive(new, Clone)]
+pub struct FiltersApi {
+    filters: SharedMessageFilters,
+    /// The relayer db, where the filter updates are persisted
+    db: DB,
+    /// Databases of the origin chains, keyed by domain id
+    dbs: HashMap<u32, HyperlaneRocksDB>,
+    /// Submitter queues, keyed by destination domain id
+    queues: HashMap<u32, SubmitterQueues>,
+}
+
+async fn get_filters(State(api): State<FiltersApi>) -> String {
+    let filters = api.filters.read().await;
+    format!(
+        "whitelist: {}\nblacklist: {}\naddress_blacklist: [{}]",
+        filters.whitelist,
+        filters.blacklist,
+        filters
+            .address_blacklist
+            .blacklist
+            .iter()
+            .map(|address| format!("0x{}", hex::encode(address)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+async fn update_filters(
+    State(api): State<FiltersApi>,
+    Json(request): Json<Map<String, Value>>,
+) -> String {
+    let filters = {
+        let mut filters = api.filters.write().await;
+        let updated = match api.persist_update(&filters, request) {
+            Ok(updated) => updated,
+            Err(e) => return format!("Failed to update filters: {:#}", e),
+        };
+        *filters = updated.clone();
+        updated
+    };
+    info!(?filters, "Updated message filters");
+    let evicted = api.evict_filtered_operations(&filters).await;
+    format!("Updated filters, evicted {} queued operation(s)", evicted)
+}
+
+fn updated_filters(
+    current: &MessageFilters,
+    request: UpdateFiltersRequest,
+) -> Result<MessageFilters> {
+    let address_blacklist = match request.address_blacklist {
+        Some(addresses) => AddressBlacklist::new(
+            addresses
+                .iter()
+                .map(|address| {
+                    let trimmed = address.trim();
+                    hex::decode(trimmed.strip_prefix("0x").unwrap_or(trimmed))
+                        .with_context(|| format!("Invalid address `{address}`"))
+                })
+                .collect::<Result<_>>()?,
+        ),
+        None => current.address_blacklist.clone(),
+    };
+    Ok(MessageFilters {
+        whitelist: request
+            .whitelist
+            .unwrap_or_else(|| current.whitelist.clone()),
+        blacklist: request
+            .blacklist
+            .unwrap_or_else(|| current.blacklist.clone()),
+        address_blacklist,
+    })
+}
+
+fn retrieve_filter_updates(db: &DB) -> Result<Map<String, Value>> {
+    match db.retrieve(FILTER_UPDATES_KEY)? {
+        Some(updates) => {
+            serde_json::from_slice(&updates).context("Invalid persisted filter updates")
+        }
+        None => Ok(Map::new()),
+    }
+}
+
+/// Applies the filter updates that were made through the API before the
+/// relayer restarted over the configured `filters`
+pub fn restore_filter_updates(db: &DB, filters: &MessageFilters) -> Result<MessageFilters> {
+    let updates = retrieve_filter_updates(db)?;
+    if updates.is_empty() {
+        return Ok(filters.clone());
+    }
+    info!(?updates, "Restoring the message filters updated at runtime");
+    updated_filters(filters, serde_json::from_value(Value::Object(updates))?)
+}
+
+impl FiltersApi {
+    /// Returns `current` with the filters of the `request` applied, after
+    /// persisting them along with the earlier updates
+    fn persist_update(
+        &self,
+        current: &MessageFilters,
+        request: Map<String, Value>,
+    ) -> Result<MessageFilters> {
+        let updated = updated_filters(
+            current,
+            serde_json::from_value(Value::Object(request.clone()))?,
+        )?;
+        // Omitted filters are left unchanged, and so are the filters set to null
+        let mut updates = retrieve_filter_updates(&self.db)?;
+        updates.extend(request.into_iter().filter(|(_, value)| !value.is_null()));
+        self.db
+            .store(FILTER_UPDATES_KEY, &serde_json::to_vec(&updates)?)?;
+        Ok(updated)
+    }
+
+    /// Remove the queued operations whose message isn't allowed by `filters`
+    /// anymore, including the ones being confirmed. Returns how many were
+    /// removed.
+    async fn evict_filtered_operations(&self, filters: &MessageFilters) -> usize {
+        let mut evicted = 0;
+        for (_, queue) in self.queues.values().flatten() {
+            // The messages are looked up without holding the queue lock
+            let queued = queue
+                .lock()
+                .await
+                .iter()
+                .map(|op| (op.0.origin_domain_id(), op.0.id()))
+                .collect::<Vec<_>>();
+            let blocked = queued
+                .into_iter()
+                .filter(|(origin, id)| self.is_blocked(filters, *origin, id))
+                .map(|(_, id)| id)
+                .collect::<HashSet<_>>();
+            if blocked.is_empty() {
+                continue;
+            }
+
+            let mut queue = queue.lock().await;
+            let len_before = queue.len();
+            queue.retain(|op| {
+                let blocked = blocked.contains(&op.0.id());
+                if blocked {
+                    info!(id = ?op.0.id(), "Evicting operation blocked by the updated filters");
+                    op.0.decrement_metric_if_exists();
+                }
+                !blocked
+            });
+            evicted += len_before - queue.len();
+        }
+        evicted
+    }
+
+    fn is_blocked(&self, filters: &MessageFilters, origin: u32, id: &H256) -> bool {
+        // The address blacklist also applies to the message body, which
+        // operations don't expose, so the message is looked up in the db
+        self.dbs
+            .get(&origin)
+            .and_then(|db| db.retrieve_message_by_id(id).ok().flatten())
+            .map_or(false, |message| !filters.allows(&message))
+    }
+
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(get_filters).put(update_filters))
+            .with_state(self.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (FILTERS_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::msg::{op_queue::test::MockPendingOperation, op_queue::OperationPriorityQueue};
+
+    use super::*;
+    use axum::http::StatusCode;
+    use hyperlane_base::db::{test_utils, DB};
+    use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, QueueOperation, H256};
+    use serde_json::json;
+    use std::{cmp::Reverse, net::SocketAddr};
+
+    fn setup_test_server(
+        db: DB,
+    ) -> (
+        SocketAddr,
+        HyperlaneRocksDB,
+        SharedMessageFilters,
+        OperationPriorityQueue,
+        OperationPriorityQueue,
+    ) {
+        let domain = HyperlaneDomain::new_test_domain("filters_api");
+        let rocks_db = HyperlaneRocksDB::new(&domain, db.clone());
+        let filters = SharedMessageFilters::default();
+        let queue = OperationPriorityQueue::default();
+        let confirm_queue = OperationPriorityQueue::default();
+
+        let api = FiltersApi::new(
+            filters.clone(),
+            db,
+            HashMap::from([(domain.id(), rocks_db.clone())]),
+            HashMap::from([(
+                domain.id() + 1,
+                vec![
+                    ("prepare_queue", queue.clone()),
+                    ("confirm_queue", confirm_queue.clone()),
+                ],
+            )]),
+        );
+        let (path, router) = api.get_route();
+        let app = Router::new().nest(path, router);
+
+        // Running the app in the background using a test server
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        (addr, rocks_db, filters, queue, confirm_queue)
+    }
+
+    async fn queue_message(
+        db: &HyperlaneRocksDB,
+        queue: &OperationPriorityQueue,
+        nonce: u32,
+    ) -> HyperlaneMessage {
+        let message = HyperlaneMessage {
+            nonce,
+            origin: db.domain().id(),
+            sender: H256::random(),
+            destination: db.domain().id() + 1,
+            ..Default::default()
+        };
+        db.store_message(&message, Default::default()).unwrap();
+        let op =
+            Box::new(MockPendingOperation::with_message_data(message.clone())) as QueueOperation;
+        queue.lock().await.push(Reverse(op));
+        message
+    }
+
+    #[tokio::test]
+    async fn test_update_filters_evicts_blocked_operations() {
+        test_utils::run_test_db(|db| async move {
+            let (addr, db, filters, queue, confirm_queue) = setup_test_server(db);
+            let blocked = queue_message(&db, &queue, 0).await;
+            let allowed = queue_message(&db, &queue, 1).await;
+            // Already submitted, but not confirmed anymore either
+            let submitted = queue_message(&db, &confirm_queue, 2).await;
+
+            let response = reqwest::Client::new()
+                .put(format!("http://{}{}", addr, FILTERS_API_BASE))
+                .json(&json!({
+                    "addressBlacklist": [
+                        format!("{:?}", blocked.sender),
+                        format!("{:?}", submitted.sender)
+                    ]
+                }))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                response.text().await.unwrap(),
+                "Updated filters, evicted 2 queued operation(s)"
+            );
+
+            let remaining = queue
+                .lock()
+                .await
+                .iter()
+                .map(|op| op.0.id())
+                .collect::<Vec<_>>();
+            assert_eq!(remaining, vec![allowed.id()]);
+            assert!(confirm_queue.lock().await.is_empty());
+            let filters = filters.read().await;
+            assert!(!filters.allows(&blocked));
+            assert!(filters.allows(&allowed));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_filter_updates_are_restored() {
+        test_utils::run_test_db(|db| async move {
+            let (addr, rocks_db, _, _, _) = setup_test_server(db.clone());
+            let message = HyperlaneMessage {
+                origin: rocks_db.domain().id(),
+                sender: H256::random(),
+                ..Default::default()
+            };
+            let client = reqwest::Client::new();
+            for update in [
+                json!({ "addressBlacklist": [format!("{:?}", message.sender)] }),
+                json!({ "whitelist": [{ "origindomain": message.origin }], "blacklist": null }),
+            ] {
+                let response = client
+                    .put(format!("http://{}{}", addr, FILTERS_API_BASE))
+                    .json(&update)
+                    .send()
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+            }
+
+            // Both updates apply over the configured filters after a restart
+            let configured = MessageFilters {
+                blacklist: serde_json::from_value(json!([{ "origindomain": 1 }])).unwrap(),
+                ..Default::default()
+            };
+            let restored = restore_filter_updates(&db, &configured).unwrap();
+            assert_eq!(
+                restored.address_blacklist.blacklist,
+                vec![message.sender.as_bytes().to_vec()]
+            );
+            let other_origin = HyperlaneMessage {
+                origin: message.origin + 1,
+                ..Default::default()
+            };
+            assert!(restored.whitelist.msg_matches(&message, true));
+            assert!(!restored.whitelist.msg_matches(&other_origin, true));
+            // The blacklist wasn't updated, since it was set to null
+            let blacklisted = HyperlaneMessage {
+                origin: 1,
+                ..Default::default()
+            };
+            assert!(restored.blacklist.msg_matches(&blacklisted, false));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_invalid_update_leaves_filters_unchanged() {
+        test_utils::run_test_db(|db| async move {
+            let (addr, _, filters, _, _) = setup_test_server(db);
+            *filters.write().await = MessageFilters {
+                address_blacklist: AddressBlacklist::new(vec![vec![1, 2, 3]]),
+                ..Default::default()
+            };
+
+            let response = reqwest::Client::new()
+                .put(format!("http://{}{}", addr, FILTERS_API_BASE))
+                .json(&json!({
+                    "blacklist": [{ "origindomain": 1 }],
+                    "addressBlacklist": ["0xzz"]
+                }))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert!(response
+                .text()
+                .await
+                .unwrap()
+                .starts_with("Failed to update filters"));
+
+            let filters = filters.read().await;
+            assert_eq!(filters.address_blacklist.blacklist, vec![vec![1, 2, 3]]);
+            assert!(filters
+                .blacklist
+                .msg_matches(&HyperlaneMessage::default(), true));
+        })
+        .await;
+    }
+}
@@ -0,0 +1,303 @@
+use axum::{
+    extract::{Path, State},
+    routing, Router,
+};
+use derive_new::new;
+use eyre::{eyre, Result};
+use hyperlane_base::db::{DeadLetteredMessage, HyperlaneDb, HyperlaneRocksDB};
+use hyperlane_core::{
+    GasPaymentKey, HyperlaneMessage, PendingOperationStatus, ReprepareReason, H256, U256,
+};
+use serde::Serialize;
+use std::{collections::HashMap, str::FromStr};
+
+use crate::msg::op_queue::OperationPriorityQueue;
+
+const MESSAGE_STATUS_API_BASE: &str = "/messages";
+
+/// The queues of a destination's submitter, labelled by the stage their
+/// operations are in
+pub type SubmitterQueues = Vec<(&'static str, OperationPriorityQueue)>;
+
+#[derive(new, Clone)]
+pub struct MessageStatusApi {
+    /// Databases of the origin chains, keyed by domain id
+    dbs: HashMap<u32, HyperlaneRocksDB>,
+    /// Submitter queues, keyed by destination domain id
+    queues: HashMap<u32, SubmitterQueues>,
+}
+
+/// Everything the relayer knows about a message
+#[derive(Debug, Serialize)]
+pub struct MessageStatusResponse {
+    pub message_id: H256,
+    pub origin: u32,
+    pub destination: u32,
+    pub nonce: u32,
+    pub sender: H256,
+    pub recipient: H256,
+    /// Whether the message was delivered
+    pub processed: bool,
+    /// Whether the message was dropped from the dead-letter queue, so that it
+    /// won't be relayed
+    pub dropped: bool,
+    /// The last status the operation was persisted with
+    pub status: Option<PendingOperationStatus>,
+    pub num_retries: Option<u32>,
+    /// Amount of native tokens paid on the origin for the message's gas
+    pub gas_payment: U256,
+    /// Amount of destination gas paid for
+    pub gas_amount_paid: U256,
+    /// Amount of destination tokens spent attempting to relay the message
+    pub tokens_used: U256,
+    /// Amount of destination gas spent attempting to relay the message
+    pub gas_used: U256,
+    /// The submitter queue the message currently sits in. `None` if it is not
+    /// queued, e.g. because it is being operated on or isn't being relayed
+    pub queue: Option<&'static str>,
+    /// Why the message was last sent back to be prepared again, if known
+    pub last_reprepare_reason: Option<ReprepareReason>,
+    /// Set if the message was moved to the dead-letter queue
+    pub dead_lettered: Option<DeadLetteredMessage>,
+}
+
+async fn get_message_status(
+    State(api): State<MessageStatusApi>,
+    Path(message_id): Path<String>,
+) -> String {
+    let res = match H256::from_str(&message_id) {
+        Ok(id) => api.message_status(&id).await,
+        Err(e) => Err(eyre!("Invalid message id `{message_id}`: {e}")),
+    };
+    match res {
+        Ok(Some(status)) => match serde_json::to_string_pretty(&status) {
+            Ok(s) => s,
+            Err(e) => format!("Error formatting message status: {}", e),
+        },
+        Ok(None) => format!("Message {} not found", message_id),
+        Err(e) => format!("Error looking up message {}: {}", message_id, e),
+    }
+}
+
+impl MessageStatusApi {
+    async fn message_status(&self, id: &H256) -> Result<Option<MessageStatusResponse>> {
+        let Some((db, message)) = self.find_message(id)? else {
+            return Ok(None);
+        };
+        let status = db.retrieve_status_by_message_id(id)?;
+        let dead_lettered = db.retrieve_dead_lettered_message_by_id(id)?;
+        let gas_payment = db
+            .retrieve_gas_payment_by_gas_payment_key(GasPaymentKey {
+                message_id: *id,
+                destination: message.destination,
+            })?
+            .unwrap_or_default();
+        let gas_expenditure = db.retrieve_gas_expenditure_by_message_id(*id)?;
+        let last_reprepare_reason = match &status {
+            Some(PendingOperationStatus::Retry(reason)) => Some(reason.clone()),
+            _ => dead_lettered.as_ref().map(|entry| entry.reason.clone()),
+        };
+
+        Ok(Some(MessageStatusResponse {
+            message_id: *id,
+            origin: message.origin,
+            destination: message.destination,
+            nonce: message.nonce,
+            sender: message.sender,
+            recipient: message.recipient,
+            processed: db
+                .retrieve_processed_by_nonce(&message.nonce)?
+                .unwrap_or(false),
+            dropped: dead_lettered
+                .as_ref()
+                .is_some_and(|entry| entry.dropped_at.is_some()),
+            status,
+            num_retries: db.retrieve_pending_message_retry_count_by_message_id(id)?,
+            gas_payment: gas_payment.payment,
+            gas_amount_paid: gas_payment.gas_amount,
+            tokens_used: gas_expenditure.tokens_used,
+            gas_used: gas_expenditure.gas_used,
+            queue: self.find_queue(id, message.destination).await,
+            last_reprepare_reason,
+            dead_lettered,
+        }))
+    }
+
+    fn find_message(&self, id: &H256) -> Result<Option<(&HyperlaneRocksDB, HyperlaneMessage)>> {
+        for db in self.dbs.values() {
+            if let Some(message) = db.retrieve_message_by_id(id)? {
+                return Ok(Some((db, message)));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn find_queue(&self, id: &H256, destination: u32) -> Option<&'static str> {
+        for (label, queue) in self.queues.get(&destination)? {
+            if queue.lock().await.iter().any(|op| op.0.id() == *id) {
+                return Some(*label);
+            }
+        }
+        None
+    }
+
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/:message_id", routing::get(get_message_status))
+            .with_state(self.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (MESSAGE_STATUS_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::msg::op_queue::test::MockPendingOperation;
+
+    use super::*;
+    use axum::http::StatusCode;
+    use hyperlane_base::db::{test_utils, DB};
+    use hyperlane_core::{HyperlaneDomain, QueueOperation};
+    use serde_json::Value;
+    use std::{cmp::Reverse, net::SocketAddr};
+
+    fn setup_test_server(
+        db: DB,
+    ) -> (
+        SocketAddr,
+        HyperlaneRocksDB,
+        HashMap<&'static str, OperationPriorityQueue>,
+    ) {
+        let domain = HyperlaneDomain::new_test_domain("message_status_api");
+        let db = HyperlaneRocksDB::new(&domain, db);
+        let queues = HashMap::from([
+            ("prepare_queue", OperationPriorityQueue::default()),
+            ("submit_queue", OperationPriorityQueue::default()),
+            ("confirm_queue", OperationPriorityQueue::default()),
+        ]);
+
+        let api = MessageStatusApi::new(
+            HashMap::from([(domain.id(), db.clone())]),
+            HashMap::from([(
+                domain.id() + 1,
+                queues.iter().map(|(l, q)| (*l, q.clone())).collect(),
+            )]),
+        );
+        let (path, router) = api.get_route();
+        let app = Router::new().nest(path, router);
+
+        // Running the app in the background using a test server
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        (addr, db, queues)
+    }
+
+    async fn get_status(addr: SocketAddr, id: &str) -> String {
+        let response = reqwest::get(format!("http://{}{}/{}", addr, MESSAGE_STATUS_API_BASE, id))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        response.text().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_message_status() {
+        test_utils::run_test_db(|db| async move {
+            let (addr, db, queues) = setup_test_server(db);
+            let message = HyperlaneMessage {
+                nonce: 7,
+                origin: db.domain().id(),
+                destination: db.domain().id() + 1,
+                ..Default::default()
+            };
+            db.store_message(&message, Default::default()).unwrap();
+            db.store_status_by_message_id(
+                &message.id(),
+                &PendingOperationStatus::Retry(ReprepareReason::ErrorEstimatingGas),
+            )
+            .unwrap();
+            db.store_pending_message_retry_count_by_message_id(&message.id(), &3)
+                .unwrap();
+            let operation = Box::new(MockPendingOperation::with_message_data(message.clone()))
+                as QueueOperation;
+            queues["prepare_queue"]
+                .lock()
+                .await
+                .push(Reverse(operation));
+
+            let response: Value =
+                serde_json::from_str(&get_status(addr, &format!("{:?}", message.id())).await)
+                    .unwrap();
+
+            assert_eq!(response["nonce"], 7);
+            assert_eq!(response["destination"], message.destination);
+            assert_eq!(response["processed"], false);
+            assert_eq!(response["dropped"], false);
+            assert_eq!(response["num_retries"], 3);
+            assert_eq!(response["queue"], "prepare_queue");
+            assert_eq!(
+                response["status"],
+                serde_json::json!({ "Retry": "ErrorEstimatingGas" })
+            );
+            assert_eq!(response["last_reprepare_reason"], "ErrorEstimatingGas");
+            assert_eq!(response["dead_lettered"], Value::Null);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_dropped_message_status() {
+        test_utils::run_test_db(|db| async move {
+            let (addr, db, _) = setup_test_server(db);
+            let message = HyperlaneMessage {
+                nonce: 3,
+                origin: db.domain().id(),
+                destination: db.domain().id() + 1,
+                ..Default::default()
+            };
+            db.store_message(&message, Default::default()).unwrap();
+            db.store_dead_lettered_message_by_id(
+                &message.id(),
+                &DeadLetteredMessage {
+                    message_id: message.id(),
+                    origin: message.origin,
+                    destination: message.destination,
+                    nonce: message.nonce,
+                    num_retries: 20,
+                    reason: ReprepareReason::ErrorEstimatingGas,
+                    dead_lettered_at: 1_700_000_000,
+                    dropped_at: Some(1_700_000_100),
+                },
+            )
+            .unwrap();
+
+            let response: Value =
+                serde_json::from_str(&get_status(addr, &format!("{:?}", message.id())).await)
+                    .unwrap();
+
+            assert_eq!(response["processed"], false);
+            assert_eq!(response["dropped"], true);
+            assert_eq!(response["dead_lettered"]["dropped_at"], 1_700_000_100);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_unknown_message_status() {
+        test_utils::run_test_db(|db| async move {
+            let (addr, _, _) = setup_test_server(db);
+            let id = format!("{:?}", H256::random());
+
+            assert_eq!(
+                get_status(addr, &id).await,
+                format!("Message {} not found", id)
+            );
+        })
+        .await;
+    }
+}
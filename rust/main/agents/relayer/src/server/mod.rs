@@ -1,17 +1,45 @@
 use axum::Router;
 use derive_new::new;
-use std::collections::HashMap;
-use tokio::sync::broadcast::Sender;
+use hyperlane_base::{
+    db::{HyperlaneRocksDB, DB},
+    ContractSyncer,
+};
+use hyperlane_core::HyperlaneMessage;
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::{broadcast::Sender, mpsc::UnboundedSender};
 
-use crate::{msg::op_queue::OperationPriorityQueue, settings::matching_list::MatchingList};
+use crate::{
+    msg::{
+        events::OperationEvents, filters::SharedMessageFilters, op_queue::OperationPriorityQueue,
+        op_submitter::SubmitterPause, profitability::ProfitabilityLedger,
+        quorum_health::QuorumHealthTracker,
+    },
+    settings::matching_list::MatchingList,
+};
 
 pub const ENDPOINT_MESSAGES_QUEUE_SIZE: usize = 100;
 
+pub use dead_letter_queue::*;
+pub use events::*;
+pub use filters::*;
 pub use list_messages::*;
 pub use message_retry::*;
+pub use message_status::*;
+pub use profitability::*;
+pub use quorum_health::*;
+pub use relay_tx::*;
+pub use submitter_pause::*;
 
+mod dead_letter_queue;
+mod events;
+mod filters;
 mod list_messages;
 mod message_retry;
+mod message_status;
+mod profitability;
+mod quorum_health;
+mod relay_tx;
+mod submitter_pause;
 
 #[derive(new)]
 pub struct Server {
@@ -19,6 +47,36 @@ pub struct Server {
     retry_transmitter: Option<Sender<MatchingList>>,
     #[new(default)]
     op_queues: Option<HashMap<u32, OperationPriorityQueue>>,
+    #[new(default)]
+    dead_letter_queue: Option<(
+        HashMap<u32, HyperlaneRocksDB>,
+        HashMap<u32, UnboundedSender<HyperlaneMessage>>,
+    )>,
+    #[new(default)]
+    message_status: Option<(
+        HashMap<u32, HyperlaneRocksDB>,
+        HashMap<u32, SubmitterQueues>,
+    )>,
+    #[new(default)]
+    submitter_pauses: Option<HashMap<u32, SubmitterPause>>,
+    #[new(default)]
+    filters: Option<(
+        SharedMessageFilters,
+        DB,
+        HashMap<u32, HyperlaneRocksDB>,
+        HashMap<u32, SubmitterQueues>,
+    )>,
+    #[new(default)]
+    profitability_ledger: Option<Arc<ProfitabilityLedger>>,
+    #[new(default)]
+    relay_tx: Option<(
+        HashMap<u32, Arc<dyn ContractSyncer<HyperlaneMessage>>>,
+        HashMap<u32, UnboundedSender<HyperlaneMessage>>,
+    )>,
+    #[new(default)]
+    events: Option<OperationEvents>,
+    #[new(default)]
+    quorum_health: Option<Arc<QuorumHealthTracker>>,
 }
 
 impl Server {
@@ -32,6 +90,64 @@ impl Server {
         self
     }
 
+    pub fn with_dead_letter_queue(
+        mut self,
+        dbs: HashMap<u32, HyperlaneRocksDB>,
+        requeue_txs: HashMap<u32, UnboundedSender<HyperlaneMessage>>,
+    ) -> Self {
+        self.dead_letter_queue = Some((dbs, requeue_txs));
+        self
+    }
+
+    pub fn with_message_status(
+        mut self,
+        dbs: HashMap<u32, HyperlaneRocksDB>,
+        queues: HashMap<u32, SubmitterQueues>,
+    ) -> Self {
+        self.message_status = Some((dbs, queues));
+        self
+    }
+
+    pub fn with_submitter_pause(mut self, pauses: HashMap<u32, SubmitterPause>) -> Self {
+        self.submitter_pauses = Some(pauses);
+        self
+    }
+
+    pub fn with_filters(
+        mut self,
+        filters: SharedMessageFilters,
+        db: DB,
+        dbs: HashMap<u32, HyperlaneRocksDB>,
+        queues: HashMap<u32, SubmitterQueues>,
+    ) -> Self {
+        self.filters = Some((filters, db, dbs, queues));
+        self
+    }
+
+    pub fn with_profitability(mut self, ledger: Arc<ProfitabilityLedger>) -> Self {
+        self.profitability_ledger = Some(ledger);
+        self
+    }
+
+    pub fn with_relay_tx(
+        mut self,
+        message_syncs: HashMap<u32, Arc<dyn ContractSyncer<HyperlaneMessage>>>,
+        relay_txs: HashMap<u32, UnboundedSender<HyperlaneMessage>>,
+    ) -> Self {
+        self.relay_tx = Some((message_syncs, relay_txs));
+        self
+    }
+
+    pub fn with_events(mut self, events: OperationEvents) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    pub fn with_quorum_health(mut self, tracker: Arc<QuorumHealthTracker>) -> Self {
+        self.quorum_health = Some(tracker);
+        self
+    }
+
     /// Returns a vector of agent-specific endpoint routes to be served.
     /// Can be extended with additional routes and feature flags to enable/disable individually.
     pub fn routes(self) -> Vec<(&'static str, Router)> {
@@ -42,6 +158,30 @@ impl Server {
         if let Some(op_queues) = self.op_queues {
             routes.push(ListOperationsApi::new(op_queues).get_route());
         }
+        if let Some((dbs, requeue_txs)) = self.dead_letter_queue {
+            routes.push(DeadLetterQueueApi::new(dbs, requeue_txs).get_route());
+        }
+        if let Some((dbs, queues)) = self.message_status {
+            routes.push(MessageStatusApi::new(dbs, queues).get_route());
+        }
+        if let Some(pauses) = self.submitter_pauses {
+            routes.push(SubmitterPauseApi::new(pauses).get_route());
+        }
+        if let Some((filters, db, dbs, queues)) = self.filters {
+            routes.push(FiltersApi::new(filters, db, dbs, queues).get_route());
+        }
+        if let Some(ledger) = self.profitability_ledger {
+            routes.push(ProfitabilityApi::new(ledger).get_route());
+        }
+        if let Some((message_syncs, relay_txs)) = self.relay_tx {
+            routes.push(RelayTxApi::new(message_syncs, relay_txs).get_route());
+        }
+        if let Some(events) = self.events {
+            routes.push(EventsApi::new(events).get_route());
+        }
+        if let Some(tracker) = self.quorum_health {
+            routes.push(QuorumHealthApi::new(tracker).get_route());
+        }
 
         routes
     }
@@ -0,0 +1,99 @@
+use axum::{extract::State, routing, Router};
+use derive_new::new;
+use std::sync::Arc;
+
+use crate::msg::profitability::ProfitabilityLedger;
+
+const PROFITABILITY_API_BASE: &str = "/profitability";
+
+#[derive(new, Clone)]
+pub struct ProfitabilityApi {
+    ledger: Arc<ProfitabilityLedger>,
+}
+
+async fn get_profitability(State(ledger): State<Arc<ProfitabilityLedger>>) -> String {
+    match serde_json::to_string_pretty(&ledger.summarize()) {
+        Ok(s) => s,
+        Err(e) => format!("Error formatting the profitability ledger: {}", e),
+    }
+}
+
+impl ProfitabilityApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(get_profitability))
+            .with_state(self.ledger.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (PROFITABILITY_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use hyperlane_base::{
+        db::{test_utils, HyperlaneRocksDB, DB},
+        CoreMetrics,
+    };
+    use hyperlane_core::{HyperlaneDomain, HyperlaneMessage};
+    use prometheus::Registry;
+    use serde_json::{json, Value};
+    use std::{collections::HashMap, net::SocketAddr};
+
+    fn setup_test_server(db: DB) -> (SocketAddr, HyperlaneRocksDB, Arc<ProfitabilityLedger>) {
+        let domain = HyperlaneDomain::new_test_domain("profitability_api");
+        let db = HyperlaneRocksDB::new(&domain, db);
+        let metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+        let ledger = Arc::new(ProfitabilityLedger::new(
+            HashMap::from([(domain.id(), db.clone())]),
+            HashMap::new(),
+            &metrics,
+        ));
+
+        let api = ProfitabilityApi::new(ledger.clone());
+        let (path, router) = api.get_route();
+        let app = Router::new().nest(path, router);
+
+        // Running the app in the background using a test server
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        (addr, db, ledger)
+    }
+
+    #[tokio::test]
+    async fn test_get_profitability() {
+        test_utils::run_test_db(|db| async move {
+            let (addr, db, ledger) = setup_test_server(db);
+            let message = HyperlaneMessage {
+                origin: db.domain().id(),
+                destination: db.domain().id() + 1,
+                ..Default::default()
+            };
+            ledger.record_delivery(&message, Some("app"));
+
+            let response = reqwest::get(format!("http://{}{}", addr, PROFITABILITY_API_BASE))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let summaries: Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+            assert_eq!(summaries[0]["appContext"], "app");
+            assert_eq!(summaries[0]["origin"], db.domain().id());
+            assert_eq!(
+                summaries[0]["windows"]["24h"],
+                json!({
+                    "paymentsInOriginToken": "0",
+                    "gasSpentInDestinationToken": "0",
+                    "gasUsed": "0",
+                    "messagesDelivered": 1
+                })
+            );
+        })
+        .await;
+    }
+}
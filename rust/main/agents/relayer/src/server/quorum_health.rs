@@ -0,0 +1,115 @@
+use axum::{
+    extract::{Path, State},
+    routing, Router,
+};
+use derive_new::new;
+use std::sync::Arc;
+
+use crate::msg::quorum_health::QuorumHealthTracker;
+
+const QUORUM_HEALTH_API_BASE: &str = "/validator_health";
+
+/// Serves the health of the validator sets of the multisig ISMs the relayer
+/// built metadata for: their validators' latest signed checkpoint, lag and
+/// storage location, and whether a quorum of them signed checkpoints
+#[derive(new, Clone)]
+pub struct QuorumHealthApi {
+    tracker: Arc<QuorumHealthTracker>,
+}
+
+fn format_quorum_health(tracker: &QuorumHealthTracker, origin: Option<u32>) -> String {
+    match serde_json::to_string_pretty(&tracker.snapshot(origin)) {
+        Ok(s) => s,
+        Err(e) => format!("Error formatting the validator health: {}", e),
+    }
+}
+
+async fn get_quorum_health(State(tracker): State<Arc<QuorumHealthTracker>>) -> String {
+    format_quorum_health(&tracker, None)
+}
+
+async fn get_origin_quorum_health(
+    State(tracker): State<Arc<QuorumHealthTracker>>,
+    Path(origin): Path<u32>,
+) -> String {
+    format_quorum_health(&tracker, Some(origin))
+}
+
+impl QuorumHealthApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(get_quorum_health))
+            .route("/:origin", routing::get(get_origin_quorum_health))
+            .with_state(self.tracker.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (QUORUM_HEALTH_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::quorum_health::IsmQuorumHealth;
+    use axum::http::StatusCode;
+    use hyperlane_core::{WeightedValidator, H160, H256};
+    use serde_json::Value;
+    use std::{collections::HashMap, net::SocketAddr};
+
+    fn setup_test_server() -> (SocketAddr, Arc<QuorumHealthTracker>) {
+        let tracker = Arc::new(QuorumHealthTracker::default());
+        let api = QuorumHealthApi::new(tracker.clone());
+        let (path, router) = api.get_route();
+        let app = Router::new().nest(path, router);
+
+        // Running the app in the background using a test server
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        (addr, tracker)
+    }
+
+    async fn get_json(url: String) -> Value {
+        let response = reqwest::get(url).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        serde_json::from_str(&response.text().await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_get_quorum_health() {
+        let (addr, tracker) = setup_test_server();
+        let validator = H256::from_low_u64_be(1);
+        for origin in [1, 2] {
+            tracker.record(IsmQuorumHealth::new(
+                origin,
+                3,
+                H256::zero(),
+                Some("app".to_owned()),
+                &[WeightedValidator {
+                    validator,
+                    weight: 2,
+                }],
+                2,
+                &HashMap::from([(H160::from(validator), Some(9))]),
+                &HashMap::new(),
+                Some(10),
+            ));
+        }
+
+        let all = get_json(format!("http://{}{}", addr, QUORUM_HEALTH_API_BASE)).await;
+        assert_eq!(all.as_array().unwrap().len(), 2);
+
+        let origin = get_json(format!("http://{}{}/2", addr, QUORUM_HEALTH_API_BASE)).await;
+        assert_eq!(origin.as_array().unwrap().len(), 1);
+        assert_eq!(origin[0]["origin"], 2);
+        assert_eq!(origin[0]["appContext"], "app");
+        assert_eq!(origin[0]["quorumReachable"], true);
+        assert_eq!(origin[0]["validators"][0]["weight"], 2);
+        assert_eq!(origin[0]["validators"][0]["latestIndex"], 9);
+        assert_eq!(origin[0]["validators"][0]["lag"], 1);
+        assert_eq!(origin[0]["validators"][0]["storageLocation"], Value::Null);
+    }
+}
@@ -0,0 +1,137 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use axum::{extract::State, routing, Json, Router};
+use derive_new::new;
+use ethers::utils::hex;
+use eyre::{eyre, Result};
+use hyperlane_base::ContractSyncer;
+use hyperlane_core::{HyperlaneMessage, H256, H512};
+use serde::Deserialize;
+use tokio::{sync::mpsc::UnboundedSender, time::timeout};
+use tracing::info;
+
+const RELAY_TX_API_BASE: &str = "/relay_tx";
+
+/// How long to wait for the logs of a transaction before giving up
+const FETCH_LOGS_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Label of the message syncs in the indexing metrics
+const MESSAGE_SYNC_LABEL: &str = "dispatched_messages";
+
+/// Requests the messages dispatched in an origin transaction to be relayed
+/// ahead of the others
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayTxRequest {
+    /// Domain id of the origin chain
+    origin: u32,
+    /// Hex encoded transaction hash, with or without a `0x` prefix
+    #[serde(alias = "tx_hash")]
+    tx_hash: String,
+}
+
+#[derive(new, Clone)]
+pub struct RelayTxApi {
+    /// Message syncs of the origin chains, keyed by domain id
+    message_syncs: HashMap<u32, Arc<dyn ContractSyncer<HyperlaneMessage>>>,
+    /// Channels to send the messages of relayed transactions to the message
+    /// processor of their origin chain, keyed by domain id
+    relay_txs: HashMap<u32, UnboundedSender<HyperlaneMessage>>,
+}
+
+/// Parses a transaction hash, which is 32 bytes long on most chains and 64
+/// bytes long on e.g. Sealevel
+fn parse_tx_hash(tx_hash: &str) -> Result<H512> {
+    let trimmed = tx_hash.trim();
+    let bytes = hex::decode(trimmed.strip_prefix("0x").unwrap_or(trimmed))
+        .map_err(|e| eyre!("Invalid tx hash `{tx_hash}`: {e}"))?;
+    match bytes.len() {
+        32 => Ok(H256::from_slice(&bytes).into()),
+        64 => Ok(H512::from_slice(&bytes)),
+        len => Err(eyre!(
+            "Invalid tx hash `{tx_hash}`: expected 32 or 64 bytes, got {len}"
+        )),
+    }
+}
+
+async fn relay_tx(State(api): State<RelayTxApi>, Json(request): Json<RelayTxRequest>) -> String {
+    match api.relay(&request).await {
+        Ok(messages) if messages.is_empty() => format!(
+            "No messages were dispatched in tx {} on origin {}",
+            request.tx_hash, request.origin
+        ),
+        Ok(messages) => format!(
+            "Relaying {} message(s) of tx {} on origin {}: {:?}",
+            messages.len(),
+            request.tx_hash,
+            request.origin,
+            messages.iter().map(|m| m.id()).collect::<Vec<_>>()
+        ),
+        Err(e) => format!("Failed to relay tx {}: {:#}", request.tx_hash, e),
+    }
+}
+
+impl RelayTxApi {
+    /// Indexes the messages dispatched in the transaction, regardless of the
+    /// position of the message sync's cursor, and sends them to the message
+    /// processor to be relayed first
+    async fn relay(&self, request: &RelayTxRequest) -> Result<Vec<HyperlaneMessage>> {
+        let tx_id = parse_tx_hash(&request.tx_hash)?;
+        let (Some(message_sync), Some(relay_tx)) = (
+            self.message_syncs.get(&request.origin),
+            self.relay_txs.get(&request.origin),
+        ) else {
+            return Err(eyre!("Origin {} is not relayed from", request.origin));
+        };
+        let messages = timeout(
+            FETCH_LOGS_TIMEOUT,
+            message_sync.index_tx(MESSAGE_SYNC_LABEL, tx_id),
+        )
+        .await
+        .map_err(|_| eyre!("Timed out fetching the logs of the tx"))??;
+        info!(
+            origin = request.origin,
+            ?tx_id,
+            messages = ?messages.iter().map(|m| m.id()).collect::<Vec<_>>(),
+            "Relaying messages of tx"
+        );
+        for message in &messages {
+            relay_tx.send(message.clone())?;
+        }
+        Ok(messages)
+    }
+
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::post(relay_tx))
+            .with_state(self.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (RELAY_TX_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tx_hash() {
+        let tx_hash = "0x0d2f4d1d1d4b6bd0ef7a1b1b0f1c8a8e5f2a6a3a4c2b1d0e9f8a7b6c5d4e3f2a";
+        let parsed = parse_tx_hash(tx_hash).unwrap();
+        // 32 byte hashes are left padded with zeros
+        assert_eq!(parsed[..32], [0; 32]);
+        assert_eq!(
+            H256::from_slice(&parsed[32..]),
+            tx_hash.parse::<H256>().unwrap()
+        );
+        assert_eq!(parse_tx_hash(&tx_hash[2..]).unwrap(), parsed);
+
+        let long_hash = format!("{}{}", tx_hash, &tx_hash[2..]);
+        assert_eq!(parse_tx_hash(&long_hash).unwrap().as_bytes().len(), 64);
+
+        assert!(parse_tx_hash("0x1234").is_err());
+        assert!(parse_tx_hash("not a hash").is_err());
+    }
+}
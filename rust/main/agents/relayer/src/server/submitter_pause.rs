@@ -0,0 +1,150 @@
+use axum::{
+    extract::{Path, State},
+    routing, Router,
+};
+use derive_new::new;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::msg::op_submitter::SubmitterPause;
+
+const SUBMITTER_PAUSE_API_BASE: &str = "/submitter_pause";
+
+#[derive(new, Clone)]
+pub struct SubmitterPauseApi {
+    /// Pause state of each submitter, keyed by destination domain id
+    pauses: HashMap<u32, SubmitterPause>,
+}
+
+async fn list_paused(State(pauses): State<HashMap<u32, SubmitterPause>>) -> String {
+    let paused: BTreeMap<_, _> = pauses
+        .iter()
+        .map(|(domain, pause)| (*domain, pause.is_paused()))
+        .collect();
+    match serde_json::to_string_pretty(&paused) {
+        Ok(s) => s,
+        Err(e) => format!("Error formatting pause state: {}", e),
+    }
+}
+
+fn set_paused(pauses: &HashMap<u32, SubmitterPause>, domain: u32, paused: bool) -> String {
+    let Some(pause) = pauses.get(&domain) else {
+        return format!("No submitter found for domain {}", domain);
+    };
+    match pause.set_paused(paused) {
+        Ok(()) if paused => format!("Paused submitter for domain {}", domain),
+        Ok(()) => format!("Resumed submitter for domain {}", domain),
+        Err(e) => format!("Failed to update submitter for domain {}: {}", domain, e),
+    }
+}
+
+async fn pause_submitter(
+    State(pauses): State<HashMap<u32, SubmitterPause>>,
+    Path(domain): Path<u32>,
+) -> String {
+    set_paused(&pauses, domain, true)
+}
+
+async fn resume_submitter(
+    State(pauses): State<HashMap<u32, SubmitterPause>>,
+    Path(domain): Path<u32>,
+) -> String {
+    set_paused(&pauses, domain, false)
+}
+
+impl SubmitterPauseApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(list_paused))
+            .route("/:destination_domain/pause", routing::post(pause_submitter))
+            .route(
+                "/:destination_domain/resume",
+                routing::post(resume_submitter),
+            )
+            .with_state(self.pauses.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (SUBMITTER_PAUSE_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use hyperlane_base::{
+        db::{test_utils, HyperlaneRocksDB, DB},
+        CoreMetrics,
+    };
+    use hyperlane_core::HyperlaneDomain;
+    use prometheus::Registry;
+    use std::net::SocketAddr;
+
+    fn setup_test_server(db: DB) -> (SocketAddr, HyperlaneRocksDB, SubmitterPause) {
+        let domain = HyperlaneDomain::new_test_domain("submitter_pause_api");
+        let db = HyperlaneRocksDB::new(&domain, db);
+        let metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+        let pause = SubmitterPause::new(db.clone(), &metrics);
+
+        let api = SubmitterPauseApi::new(HashMap::from([(domain.id(), pause.clone())]));
+        let (path, router) = api.get_route();
+        let app = Router::new().nest(path, router);
+
+        // Running the app in the background using a test server
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        (addr, db, pause)
+    }
+
+    async fn post(addr: SocketAddr, path: String) {
+        let response = reqwest::Client::new()
+            .post(format!(
+                "http://{}{}{}",
+                addr, SUBMITTER_PAUSE_API_BASE, path
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume_submitter() {
+        test_utils::run_test_db(|db| async move {
+            let (addr, db, pause) = setup_test_server(db);
+            let domain = db.domain().id();
+            assert!(!pause.is_paused());
+
+            post(addr, format!("/{}/pause", domain)).await;
+            assert!(pause.is_paused());
+            assert_eq!(db.retrieve_submitter_paused().unwrap(), Some(true));
+
+            let response = reqwest::get(format!("http://{}{}", addr, SUBMITTER_PAUSE_API_BASE))
+                .await
+                .unwrap();
+            let paused: HashMap<u32, bool> =
+                serde_json::from_str(&response.text().await.unwrap()).unwrap();
+            assert_eq!(paused, HashMap::from([(domain, true)]));
+
+            post(addr, format!("/{}/resume", domain)).await;
+            assert!(!pause.is_paused());
+            assert_eq!(db.retrieve_submitter_paused().unwrap(), Some(false));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_pause_state_is_restored() {
+        test_utils::run_test_db(|db| async move {
+            let (_, db, pause) = setup_test_server(db);
+            pause.set_paused(true).unwrap();
+
+            let metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+            assert!(SubmitterPause::new(db, &metrics).is_paused());
+        })
+        .await;
+    }
+}
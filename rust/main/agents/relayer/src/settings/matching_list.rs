@@ -8,6 +8,7 @@ use std::{
     marker::PhantomData,
 };
 
+use ethers::utils::hex;
 use hyperlane_core::{
     config::StrOrInt, utils::hex_or_base58_to_h256, HyperlaneMessage, QueueOperation, H256,
 };
@@ -24,6 +25,15 @@ use serde::{
 /// - wildcard "*"
 /// - single value in decimal or hex (must start with `0x`) format
 /// - list of values in decimal or hex format
+///
+/// Rules can additionally constrain the message body with
+/// - `bodyprefix`: hex bytes the body must start with
+/// - `bodypattern`: one or a list of `{"offset": n, "bytes": "0x.."}`, of
+///   which at least one must be found in the body at its offset
+/// - `minbodylength` / `maxbodylength`: inclusive bounds on the body length
+///
+/// and can exclude messages with `not`, one or a list of rules none of which
+/// may match the message.
 #[derive(Debug, Default, Clone)]
 pub struct MatchingList(Option<Vec<ListElement>>);
 
@@ -48,6 +58,45 @@ impl<T: PartialEq> Filter<T> {
     }
 }
 
+/// Bytes that must be found in a message body at a given offset
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct BodyPattern {
+    #[serde(default, deserialize_with = "deserialize_u32")]
+    offset: u32,
+    #[serde(deserialize_with = "deserialize_bytes")]
+    bytes: Vec<u8>,
+}
+
+impl BodyPattern {
+    fn matches(&self, body: &[u8]) -> bool {
+        body.get(self.offset as usize..)
+            .map_or(false, |rest| rest.starts_with(&self.bytes))
+    }
+}
+
+impl Display for BodyPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}@{}", hex::encode(&self.bytes), self.offset)
+    }
+}
+
+/// Accepts either a single value or a list of them
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    Many(Vec<T>),
+    One(T),
+}
+
+impl<T> From<OneOrMany<T>> for Vec<T> {
+    fn from(v: OneOrMany<T>) -> Self {
+        match v {
+            OneOrMany::Many(v) => v,
+            OneOrMany::One(v) => vec![v],
+        }
+    }
+}
+
 impl<T: Debug> Display for Filter<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -222,7 +271,7 @@ impl<'de> Deserialize<'de> for Filter<H256> {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Default, Deserialize, Clone)]
 #[serde(tag = "type")]
 struct ListElement {
     #[serde(default, rename = "messageid")]
@@ -235,29 +284,102 @@ struct ListElement {
     destination_domain: Filter<u32>,
     #[serde(default, rename = "recipientaddress")]
     recipient_address: Filter<H256>,
+    #[serde(
+        default,
+        rename = "bodyprefix",
+        deserialize_with = "deserialize_body_prefix"
+    )]
+    body_prefix: Option<BodyPattern>,
+    #[serde(
+        default,
+        rename = "bodypattern",
+        deserialize_with = "deserialize_one_or_many"
+    )]
+    body_patterns: Vec<BodyPattern>,
+    #[serde(
+        default,
+        rename = "minbodylength",
+        deserialize_with = "deserialize_optional_u32"
+    )]
+    min_body_length: Option<u32>,
+    #[serde(
+        default,
+        rename = "maxbodylength",
+        deserialize_with = "deserialize_optional_u32"
+    )]
+    max_body_length: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_one_or_many")]
+    not: Vec<ListElement>,
+}
+
+impl ListElement {
+    fn matches(&self, info: &MatchInfo) -> bool {
+        self.message_id.matches(&info.src_msg_id)
+            && self.origin_domain.matches(&info.src_domain)
+            && self.sender_address.matches(info.src_addr)
+            && self.destination_domain.matches(&info.dst_domain)
+            && self.recipient_address.matches(info.dst_addr)
+            && self.body_matches(info.body)
+            && !self.not.iter().any(|rule| rule.matches(info))
+    }
+
+    fn body_matches(&self, body: &[u8]) -> bool {
+        let len = body.len() as u64;
+        self.body_prefix
+            .as_ref()
+            .map_or(true, |prefix| prefix.matches(body))
+            && (self.body_patterns.is_empty() || self.body_patterns.iter().any(|p| p.matches(body)))
+            && self.min_body_length.map_or(true, |min| len >= min as u64)
+            && self.max_body_length.map_or(true, |max| len <= max as u64)
+    }
 }
 
 impl Display for ListElement {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{{messageId: {}, originDomain: {}, senderAddress: {}, destinationDomain: {}, recipientAddress: {}}}",
+            "{{messageId: {}, originDomain: {}, senderAddress: {}, destinationDomain: {}, recipientAddress: {}",
             self.message_id,
             self.origin_domain,
             self.sender_address,
             self.destination_domain,
             self.recipient_address
-        )
+        )?;
+        if let Some(prefix) = &self.body_prefix {
+            write!(f, ", bodyPrefix: 0x{}", hex::encode(&prefix.bytes))?;
+        }
+        if !self.body_patterns.is_empty() {
+            write!(f, ", bodyPattern: [")?;
+            for p in &self.body_patterns {
+                write!(f, "{p},")?;
+            }
+            write!(f, "]")?;
+        }
+        if let Some(min) = self.min_body_length {
+            write!(f, ", minBodyLength: {min}")?;
+        }
+        if let Some(max) = self.max_body_length {
+            write!(f, ", maxBodyLength: {max}")?;
+        }
+        if !self.not.is_empty() {
+            write!(f, ", not: [")?;
+            for rule in &self.not {
+                write!(f, "{rule},")?;
+            }
+            write!(f, "]")?;
+        }
+        write!(f, "}}")
     }
 }
 
 #[derive(Copy, Clone, Debug)]
-struct MatchInfo<'a> {
-    src_msg_id: H256,
-    src_domain: u32,
-    src_addr: &'a H256,
-    dst_domain: u32,
-    dst_addr: &'a H256,
+pub(crate) struct MatchInfo<'a> {
+    pub src_msg_id: H256,
+    pub src_domain: u32,
+    pub src_addr: &'a H256,
+    pub dst_domain: u32,
+    pub dst_addr: &'a H256,
+    pub body: &'a [u8],
 }
 
 impl<'a> From<&'a HyperlaneMessage> for MatchInfo<'a> {
@@ -268,6 +390,7 @@ impl<'a> From<&'a HyperlaneMessage> for MatchInfo<'a> {
             src_addr: &msg.sender,
             dst_domain: msg.destination,
             dst_addr: &msg.recipient,
+            body: &msg.body,
         }
     }
 }
@@ -280,6 +403,7 @@ impl<'a> From<&'a QueueOperation> for MatchInfo<'a> {
             src_addr: op.sender_address(),
             dst_domain: op.destination_domain().id(),
             dst_addr: op.recipient_address(),
+            body: op.body(),
         }
     }
 }
@@ -288,20 +412,14 @@ impl MatchingList {
     pub fn with_message_id(message_id: H256) -> Self {
         Self(Some(vec![ListElement {
             message_id: Filter::Enumerated(vec![message_id]),
-            origin_domain: Default::default(),
-            sender_address: Default::default(),
-            destination_domain: Default::default(),
-            recipient_address: Default::default(),
+            ..Default::default()
         }]))
     }
 
     pub fn with_destination_domain(destination_domain: u32) -> Self {
         Self(Some(vec![ListElement {
-            message_id: Default::default(),
-            origin_domain: Default::default(),
-            sender_address: Default::default(),
             destination_domain: Filter::Enumerated(vec![destination_domain]),
-            recipient_address: Default::default(),
+            ..Default::default()
         }]))
     }
 
@@ -319,7 +437,7 @@ impl MatchingList {
 
     /// Check if a message matches any of the rules.
     /// - `default`: What to return if the matching list is empty.
-    fn matches(&self, info: MatchInfo, default: bool) -> bool {
+    pub(crate) fn matches(&self, info: MatchInfo, default: bool) -> bool {
         if let Some(rules) = &self.0 {
             matches_any_rule(rules.iter(), info)
         } else {
@@ -329,13 +447,7 @@ impl MatchingList {
 }
 
 fn matches_any_rule<'a>(mut rules: impl Iterator<Item = &'a ListElement>, info: MatchInfo) -> bool {
-    rules.any(|rule| {
-        rule.message_id.matches(&info.src_msg_id)
-            && rule.origin_domain.matches(&info.src_domain)
-            && rule.sender_address.matches(info.src_addr)
-            && rule.destination_domain.matches(&info.dst_domain)
-            && rule.recipient_address.matches(info.dst_addr)
-    })
+    rules.any(|rule| rule.matches(&info))
 }
 
 impl Display for MatchingList {
@@ -360,12 +472,42 @@ fn parse_addr<E: Error>(addr_str: &str) -> Result<H256, E> {
     hex_or_base58_to_h256(addr_str).map_err(to_serde_err)
 }
 
+fn deserialize_u32<'de, D: Deserializer<'de>>(d: D) -> Result<u32, D::Error> {
+    StrOrInt::deserialize(d)?.try_into().map_err(to_serde_err)
+}
+
+fn deserialize_optional_u32<'de, D: Deserializer<'de>>(d: D) -> Result<Option<u32>, D::Error> {
+    deserialize_u32(d).map(Some)
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+    let s = String::deserialize(d)?;
+    hex::decode(s.strip_prefix("0x").unwrap_or(&s)).map_err(to_serde_err)
+}
+
+fn deserialize_body_prefix<'de, D: Deserializer<'de>>(
+    d: D,
+) -> Result<Option<BodyPattern>, D::Error> {
+    Ok(Some(BodyPattern {
+        offset: 0,
+        bytes: deserialize_bytes(d)?,
+    }))
+}
+
+fn deserialize_one_or_many<'de, D, T>(d: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    OneOrMany::deserialize(d).map(Into::into)
+}
+
 #[cfg(test)]
 mod test {
-    use hyperlane_core::{H160, H256};
+    use hyperlane_core::{HyperlaneMessage, QueueOperation, H160, H256};
 
     use super::{Filter::*, MatchingList};
-    use crate::settings::matching_list::MatchInfo;
+    use crate::{msg::op_queue::test::MockPendingOperation, settings::matching_list::MatchInfo};
 
     #[test]
     fn basic_config() {
@@ -392,7 +534,8 @@ mod test {
                 src_domain: 0,
                 src_addr: &H256::default(),
                 dst_domain: 0,
-                dst_addr: &H256::default()
+                dst_addr: &H256::default(),
+                body: &[],
             },
             false
         ));
@@ -406,7 +549,8 @@ mod test {
                     .unwrap()
                     .into(),
                 dst_domain: 5456,
-                dst_addr: &H256::default()
+                dst_addr: &H256::default(),
+                body: &[],
             },
             false
         ))
@@ -448,7 +592,8 @@ mod test {
                 dst_addr: &"9d4454B023096f34B160D6B654540c56A1F81688"
                     .parse::<H160>()
                     .unwrap()
-                    .into()
+                    .into(),
+                body: &[],
             },
             false
         ));
@@ -462,7 +607,8 @@ mod test {
                     .unwrap()
                     .into(),
                 dst_domain: 5456,
-                dst_addr: &H256::default()
+                dst_addr: &H256::default(),
+                body: &[],
             },
             false
         ));
@@ -496,6 +642,7 @@ mod test {
             src_addr: &H256::default(),
             dst_domain: 0,
             dst_addr: &H256::default(),
+            body: &[],
         };
         // whitelist use
         assert!(MatchingList(None).matches(info, true));
@@ -503,6 +650,76 @@ mod test {
         assert!(!MatchingList(None).matches(info, false));
     }
 
+    #[test]
+    fn matches_body_predicates() {
+        let recipient = H256::random();
+        let list: MatchingList = serde_json::from_value(serde_json::json!([{
+            "bodyprefix": format!("{recipient:?}"),
+            "bodypattern": [{ "offset": "32", "bytes": "0x01" }, { "offset": 32, "bytes": "0x02" }],
+            "minbodylength": 33,
+            "maxbodylength": "64"
+        }]))
+        .unwrap();
+        let message = |body: Vec<u8>| HyperlaneMessage {
+            body,
+            ..Default::default()
+        };
+
+        let mut body = recipient.as_bytes().to_vec();
+        body.push(2);
+        assert!(list.msg_matches(&message(body.clone()), false));
+        let op = Box::new(MockPendingOperation::with_message_data(message(
+            body.clone(),
+        ))) as QueueOperation;
+        assert!(list.op_matches(&op));
+
+        // wrong prefix
+        assert!(!list.msg_matches(&message([&[0u8; 32][..], &[2]].concat()), false));
+        // none of the patterns are found
+        assert!(!list.msg_matches(&message([recipient.as_bytes(), &[3]].concat()), false));
+        // too short
+        assert!(!list.msg_matches(&message(recipient.as_bytes().to_vec()), false));
+        // too long
+        body.extend([0; 32]);
+        assert!(!list.msg_matches(&message(body), false));
+    }
+
+    #[test]
+    fn matches_negated_rules() {
+        let sender = H256::random();
+        let list: MatchingList = serde_json::from_value(serde_json::json!([{
+            "destinationdomain": 1,
+            "not": { "senderaddress": format!("{sender:?}") }
+        }]))
+        .unwrap();
+        let mut message = HyperlaneMessage {
+            destination: 1,
+            ..Default::default()
+        };
+        assert!(list.msg_matches(&message, false));
+
+        message.sender = sender;
+        assert!(!list.msg_matches(&message, false));
+
+        message.sender = H256::random();
+        message.destination = 2;
+        assert!(!list.msg_matches(&message, false));
+
+        // any of the negated rules excludes the message
+        let list: MatchingList = serde_json::from_value(serde_json::json!([{
+            "not": [{ "origindomain": 5 }, { "maxbodylength": 0 }]
+        }]))
+        .unwrap();
+        assert!(!list.msg_matches(&HyperlaneMessage::default(), false));
+        assert!(list.msg_matches(
+            &HyperlaneMessage {
+                body: vec![1],
+                ..Default::default()
+            },
+            false
+        ));
+    }
+
     #[test]
     fn supports_base58() {
         serde_json::from_str::<MatchingList>(
@@ -4,7 +4,11 @@
 //! and validations it defines are not applied here, we should mirror them.
 //! ANY CHANGES HERE NEED TO BE REFLECTED IN THE TYPESCRIPT SDK.
 
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    time::Duration,
+};
 
 use convert_case::Case;
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
@@ -17,7 +21,7 @@ use hyperlane_base::{
         Settings,
     },
 };
-use hyperlane_core::{cfg_unwrap_all, config::*, HyperlaneDomain, U256};
+use hyperlane_core::{cfg_unwrap_all, config::*, HyperlaneDomain, ReprepareReason, U256};
 use itertools::Itertools;
 use serde::Deserialize;
 use serde_json::Value;
@@ -59,10 +63,89 @@ pub struct RelayerSettings {
     /// If true, allows local storage based checkpoint syncers.
     /// Not intended for production use.
     pub allow_local_checkpoint_syncers: bool,
+    /// How long building the metadata of a sub-module of an aggregation or
+    /// routing ISM may take before it is abandoned.
+    pub sub_module_metadata_timeout: Duration,
     /// App contexts used for metrics.
     pub metric_app_contexts: Vec<(MatchingList, String)>,
+    /// When operations should be moved to the dead-letter queue.
+    pub dead_letter: DeadLetterConf,
+    /// Limits on how quickly messages of a sender or app are relayed.
+    pub rate_limits: Vec<RateLimitConf>,
+    /// If true, messages are fully prepared but only logged instead of being
+    /// submitted.
+    pub dry_run: bool,
+    /// Priority lanes of the submission queues, keyed by destination domain
+    /// id. Destinations without an entry serve all messages the same.
+    pub priority_lanes: HashMap<u32, PriorityLaneConf>,
+}
+
+const DEFAULT_SUB_MODULE_METADATA_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Config for moving operations that keep failing to the dead-letter queue
+#[derive(Debug, Clone, Default)]
+pub struct DeadLetterConf {
+    /// Dead-letter an operation once it has been retried this many times. If
+    /// not specified, operations are retried indefinitely.
+    pub max_retries: Option<u32>,
+    /// Dead-letter an operation as soon as it is reprepared for any of these
+    /// reasons, regardless of how many times it has been retried.
+    pub reasons: Vec<ReprepareReason>,
+}
+
+impl DeadLetterConf {
+    /// Whether an operation that has been retried `num_retries` times and is
+    /// being reprepared for `reason` should be dead-lettered.
+    pub fn should_dead_letter(&self, num_retries: u32, reason: &ReprepareReason) -> bool {
+        self.reasons.contains(reason)
+            || self
+                .max_retries
+                .map_or(false, |max_retries| num_retries >= max_retries)
+    }
+}
+
+/// Config for a token bucket that limits how quickly matching messages are
+/// relayed. Each sender or app gets its own bucket, which holds up to
+/// `max_messages` tokens and refills at `max_messages` per `period`.
+#[derive(Debug, Clone)]
+pub struct RateLimitConf {
+    /// Messages that match are subject to the limit. By default all messages
+    /// match.
+    pub matching_list: MatchingList,
+    /// How messages are grouped into buckets
+    pub key: RateLimitKey,
+    /// How many messages can be relayed in a burst
+    pub max_messages: u32,
+    /// How long it takes to refill an empty bucket
+    pub period: Duration,
 }
 
+/// How rate limited messages are grouped into buckets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitKey {
+    /// One bucket per origin chain and sender
+    Sender,
+    /// One bucket per origin chain and app context. Messages without an app
+    /// context aren't limited.
+    AppContext,
+}
+
+/// Config for serving some of the messages to a destination from a premium
+/// lane, ahead of the rest
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriorityLaneConf {
+    /// Messages of these app contexts are premium
+    pub premium_app_contexts: Vec<String>,
+    /// Messages that paid for at least this multiple of their estimated gas
+    /// limit are premium. Disabled if not set.
+    pub min_gas_overpayment: Option<f64>,
+    /// How many premium messages are served for every standard one while
+    /// there are messages in both lanes
+    pub premium_weight: u32,
+}
+
+const DEFAULT_PREMIUM_WEIGHT: u32 = 4;
+
 /// Config for gas payment enforcement
 #[derive(Debug, Clone, Default)]
 pub struct GasPaymentEnforcementConf {
@@ -87,6 +170,50 @@ pub enum GasPaymentEnforcementPolicy {
         gas_fraction_numerator: u64,
         gas_fraction_denominator: u64,
     },
+    /// The USD value of the payment, minus what was already spent relaying
+    /// the message, is at least `margin` times the USD value of the estimated
+    /// transaction cost.
+    UsdValue {
+        margin: f64,
+        price_source: TokenPriceSourceConf,
+    },
+    /// Every one of the policies is met. The smallest of the gas limits they
+    /// approve is used.
+    AllOf(Vec<GasPaymentEnforcementPolicy>),
+    /// At least one of the policies is met. The largest of the gas limits
+    /// approved by the policies that are met is used.
+    AnyOf(Vec<GasPaymentEnforcementPolicy>),
+}
+
+/// Where the native token prices used by the `UsdValue` policy come from
+#[derive(Debug, Clone)]
+pub enum TokenPriceSourceConf {
+    /// Prices set in the config, keyed by domain id
+    Static(HashMap<u32, TokenPrice>),
+    /// Prices read from a JSON file mapping domain ids to prices. The file is
+    /// read again every `refresh_interval`, and the prices are stale once they
+    /// weren't refreshed for a few intervals.
+    File {
+        path: PathBuf,
+        refresh_interval: Duration,
+    },
+}
+
+/// The price of a chain's native token
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct TokenPrice {
+    /// USD price of one whole token
+    pub price: f64,
+    /// Decimals of the token, used to convert amounts into whole tokens
+    #[serde(default = "default_token_decimals")]
+    pub decimals: u32,
+}
+
+const DEFAULT_TOKEN_DECIMALS: u32 = 18;
+const DEFAULT_PRICE_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+fn default_token_decimals() -> u32 {
+    DEFAULT_TOKEN_DECIMALS
 }
 
 #[derive(Debug, Deserialize)]
@@ -135,51 +262,26 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             raw_gas_payment_enforcement_path,
             &raw_gas_payment_enforcement,
         );
-        let mut gas_payment_enforcement = gas_payment_enforcement_parser.into_array_iter().map(|itr| {
-            itr.filter_map(|policy| {
-                let policy_type = policy.chain(&mut err).get_opt_key("type").parse_string().end();
-                let minimum_is_defined = matches!(policy.get_opt_key("minimum"), Ok(Some(_)));
-
-                let matching_list = policy.chain(&mut err).get_opt_key("matchingList").and_then(parse_matching_list).unwrap_or_default();
-
-                let parse_minimum = |p| GasPaymentEnforcementPolicy::Minimum { payment: p };
-                match policy_type {
-                    Some("minimum") => policy.chain(&mut err).get_opt_key("payment").parse_u256().end().map(parse_minimum),
-                    None if minimum_is_defined => policy.chain(&mut err).get_opt_key("payment").parse_u256().end().map(parse_minimum),
-                    Some("none") | None => Some(GasPaymentEnforcementPolicy::None),
-                    Some("onChainFeeQuoting") => {
-                        let gas_fraction = policy.chain(&mut err)
-                            .get_opt_key("gasFraction")
-                            .parse_string()
-                            .map(|v| v.replace(' ', ""))
-                            .unwrap_or_else(|| "1/2".to_owned());
-                        let (numerator, denominator) = gas_fraction
-                            .split_once('/')
-                            .ok_or_else(|| eyre!("Invalid `gas_fraction` for OnChainFeeQuoting gas payment enforcement policy; expected `numerator / denominator`"))
-                            .take_err(&mut err, || &policy.cwp + "gas_fraction")
-                            .unwrap_or(("1", "1"));
-
-                        Some(GasPaymentEnforcementPolicy::OnChainFeeQuoting {
-                            gas_fraction_numerator: numerator
-                                .parse()
-                                .context("Error parsing gas fraction numerator")
-                                .take_err(&mut err, || &policy.cwp + "gas_fraction")
-                                .unwrap_or(1),
-                            gas_fraction_denominator: denominator
-                                .parse()
-                                .context("Error parsing gas fraction denominator")
-                                .take_err(&mut err, || &policy.cwp + "gas_fraction")
-                                .unwrap_or(1),
-                        })
-                    }
-                    Some(pt) => Err(eyre!("Unknown gas payment enforcement policy type `{pt}`"))
-                        .take_err(&mut err, || cwp + "type"),
-                }.map(|policy| GasPaymentEnforcementConf {
-                    policy,
-                    matching_list,
+        let mut gas_payment_enforcement = gas_payment_enforcement_parser
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|policy| {
+                    let matching_list = policy
+                        .chain(&mut err)
+                        .get_opt_key("matchingList")
+                        .and_then(parse_matching_list)
+                        .unwrap_or_default();
+
+                    parse_gas_payment_policy(&policy, &mut err).map(|policy| {
+                        GasPaymentEnforcementConf {
+                            policy,
+                            matching_list,
+                        }
+                    })
                 })
-            }).collect_vec()
-        }).unwrap_or_default();
+                .collect_vec()
+            })
+            .unwrap_or_default();
 
         if gas_payment_enforcement.is_empty() {
             gas_payment_enforcement.push(GasPaymentEnforcementConf::default());
@@ -223,6 +325,49 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             .parse_bool()
             .unwrap_or(false);
 
+        let sub_module_metadata_timeout = p
+            .chain(&mut err)
+            .get_opt_key("subModuleMetadataTimeout")
+            .parse_u64()
+            .end()
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_SUB_MODULE_METADATA_TIMEOUT);
+
+        let dry_run = p
+            .chain(&mut err)
+            .get_opt_key("dryRun")
+            .parse_bool()
+            .unwrap_or(false);
+
+        let dead_letter_max_retries = p
+            .chain(&mut err)
+            .get_opt_key("deadLetterMaxRetries")
+            .parse_u32()
+            .end();
+
+        let dead_letter_reasons = p
+            .chain(&mut err)
+            .get_opt_key("deadLetterReasons")
+            .parse_string()
+            .end()
+            .map(|str| parse_reprepare_reasons(str, &mut err, || &p.cwp + "dead_letter_reasons"))
+            .unwrap_or_default();
+
+        let (raw_rate_limits_path, raw_rate_limits) = p
+            .get_opt_key("rateLimits")
+            .take_config_err_flat(&mut err)
+            .and_then(parse_json_array)
+            .unwrap_or_else(|| (&p.cwp + "rate_limits", Value::Array(vec![])));
+
+        let rate_limits_parser = ValueParser::new(raw_rate_limits_path, &raw_rate_limits);
+        let rate_limits = rate_limits_parser
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|limit| parse_rate_limit(&limit, &mut err))
+                    .collect_vec()
+            })
+            .unwrap_or_default();
+
         cfg_unwrap_all!(cwp, err: [base]);
 
         let skip_transaction_gas_limit_for = skip_transaction_gas_limit_for_names
@@ -247,6 +392,33 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             })
             .collect();
 
+        let (raw_priority_lanes_path, raw_priority_lanes) = p
+            .get_opt_key("priorityLanes")
+            .take_config_err_flat(&mut err)
+            .and_then(parse_json_array)
+            .unwrap_or_else(|| (&p.cwp + "priority_lanes", Value::Array(vec![])));
+
+        let priority_lanes_parser = ValueParser::new(raw_priority_lanes_path, &raw_priority_lanes);
+        let priority_lanes = priority_lanes_parser
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|lane| {
+                    let destination = lane
+                        .chain(&mut err)
+                        .get_key("destination")
+                        .parse_string()
+                        .end()?;
+                    let domain = base
+                        .lookup_domain(destination)
+                        .context("Missing configuration for a chain in `priorityLanes`")
+                        .into_config_result(|| &lane.cwp + "destination")
+                        .take_config_err(&mut err)?;
+                    parse_priority_lane(&lane, &mut err).map(|conf| (domain.id(), conf))
+                })
+                .collect()
+            })
+            .unwrap_or_default();
+
         let (raw_metric_app_contexts_path, raw_metric_app_contexts) = p
             .get_opt_key("metricAppContexts")
             .take_config_err_flat(&mut err)
@@ -285,7 +457,15 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             transaction_gas_limit,
             skip_transaction_gas_limit_for,
             allow_local_checkpoint_syncers,
+            sub_module_metadata_timeout,
             metric_app_contexts,
+            dead_letter: DeadLetterConf {
+                max_retries: dead_letter_max_retries,
+                reasons: dead_letter_reasons,
+            },
+            rate_limits,
+            dry_run,
+            priority_lanes,
         })
     }
 }
@@ -326,6 +506,233 @@ fn parse_matching_list(p: ValueParser) -> ConfigResult<MatchingList> {
     err.into_result(ml)
 }
 
+fn parse_gas_payment_policy(
+    policy: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<GasPaymentEnforcementPolicy> {
+    let policy_type = policy.chain(err).get_opt_key("type").parse_string().end();
+    let minimum_is_defined = matches!(policy.get_opt_key("minimum"), Ok(Some(_)));
+
+    let parse_minimum = |p| GasPaymentEnforcementPolicy::Minimum { payment: p };
+    match policy_type {
+        Some("minimum") => policy
+            .chain(err)
+            .get_opt_key("payment")
+            .parse_u256()
+            .end()
+            .map(parse_minimum),
+        None if minimum_is_defined => policy
+            .chain(err)
+            .get_opt_key("payment")
+            .parse_u256()
+            .end()
+            .map(parse_minimum),
+        Some("none") | None => Some(GasPaymentEnforcementPolicy::None),
+        Some("onChainFeeQuoting") => {
+            let gas_fraction = policy
+                .chain(err)
+                .get_opt_key("gasFraction")
+                .parse_string()
+                .map(|v| v.replace(' ', ""))
+                .unwrap_or_else(|| "1/2".to_owned());
+            let (numerator, denominator) = gas_fraction
+                .split_once('/')
+                .ok_or_else(|| eyre!("Invalid `gas_fraction` for OnChainFeeQuoting gas payment enforcement policy; expected `numerator / denominator`"))
+                .take_err(err, || &policy.cwp + "gas_fraction")
+                .unwrap_or(("1", "1"));
+
+            Some(GasPaymentEnforcementPolicy::OnChainFeeQuoting {
+                gas_fraction_numerator: numerator
+                    .parse()
+                    .context("Error parsing gas fraction numerator")
+                    .take_err(err, || &policy.cwp + "gas_fraction")
+                    .unwrap_or(1),
+                gas_fraction_denominator: denominator
+                    .parse()
+                    .context("Error parsing gas fraction denominator")
+                    .take_err(err, || &policy.cwp + "gas_fraction")
+                    .unwrap_or(1),
+            })
+        }
+        Some("usdValue") => {
+            let margin = policy
+                .chain(err)
+                .get_opt_key("margin")
+                .parse_f64()
+                .unwrap_or(1.0);
+            policy
+                .chain(err)
+                .get_key("priceSource")
+                .and_then(parse_token_price_source)
+                .end()
+                .map(|price_source| GasPaymentEnforcementPolicy::UsdValue {
+                    margin,
+                    price_source,
+                })
+        }
+        Some(combinator @ ("allOf" | "anyOf")) => {
+            let policies = policy
+                .chain(err)
+                .get_key("policies")
+                .end()?
+                .into_array_iter()
+                .take_config_err(err)?
+                .filter_map(|p| parse_gas_payment_policy(&p, err))
+                .collect_vec();
+            if policies.is_empty() {
+                return Err(eyre!(
+                    "Expected `{combinator}` to combine at least one policy"
+                ))
+                .take_err(err, || &policy.cwp + "policies");
+            }
+            Some(if combinator == "allOf" {
+                GasPaymentEnforcementPolicy::AllOf(policies)
+            } else {
+                GasPaymentEnforcementPolicy::AnyOf(policies)
+            })
+        }
+        Some(pt) => Err(eyre!("Unknown gas payment enforcement policy type `{pt}`"))
+            .take_err(err, || &policy.cwp + "type"),
+    }
+}
+
+fn parse_rate_limit(limit: &ValueParser, err: &mut ConfigParsingError) -> Option<RateLimitConf> {
+    let matching_list = limit
+        .chain(err)
+        .get_opt_key("matchingList")
+        .and_then(parse_matching_list)
+        .unwrap_or_default();
+    let key = match limit.chain(err).get_opt_key("keyBy").parse_string().end() {
+        Some("sender") | None => RateLimitKey::Sender,
+        Some("appContext") => RateLimitKey::AppContext,
+        Some(key) => {
+            return Err(eyre!("Unknown rate limit key `{key}`"))
+                .take_err(err, || &limit.cwp + "key_by")
+        }
+    };
+    let max_messages = limit.chain(err).get_key("maxMessages").parse_u32().end()?;
+    let period = limit
+        .chain(err)
+        .get_key("period")
+        .parse_u64()
+        .end()
+        .map(Duration::from_secs)?;
+    if max_messages == 0 || period.is_zero() {
+        return Err(eyre!(
+            "Rate limit `maxMessages` and `period` must be positive"
+        ))
+        .take_err(err, || limit.cwp.clone());
+    }
+
+    Some(RateLimitConf {
+        matching_list,
+        key,
+        max_messages,
+        period,
+    })
+}
+
+fn parse_priority_lane(
+    lane: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<PriorityLaneConf> {
+    let premium_app_contexts = lane
+        .chain(err)
+        .get_opt_key("premiumAppContexts")
+        .into_array_iter()
+        .map(|itr| {
+            itr.filter_map(|app_context| {
+                app_context
+                    .chain(err)
+                    .parse_string()
+                    .end()
+                    .map(str::to_owned)
+            })
+            .collect_vec()
+        })
+        .unwrap_or_default();
+    let min_gas_overpayment = lane
+        .chain(err)
+        .get_opt_key("minGasOverpayment")
+        .parse_f64()
+        .end();
+    let premium_weight = lane
+        .chain(err)
+        .get_opt_key("premiumWeight")
+        .parse_u32()
+        .unwrap_or(DEFAULT_PREMIUM_WEIGHT);
+    if premium_weight == 0 {
+        return Err(eyre!("Priority lane `premiumWeight` must be positive"))
+            .take_err(err, || &lane.cwp + "premium_weight");
+    }
+
+    Some(PriorityLaneConf {
+        premium_app_contexts,
+        min_gas_overpayment,
+        premium_weight,
+    })
+}
+
+fn parse_token_price_source(p: ValueParser) -> ConfigResult<TokenPriceSourceConf> {
+    let mut err = ConfigParsingError::default();
+
+    let source_type = p.chain(&mut err).get_opt_key("type").parse_string().end();
+    let source = match source_type {
+        Some("static") | None => {
+            let prices = p
+                .chain(&mut err)
+                .get_key("prices")
+                .end()
+                .and_then(|prices| prices.into_obj_iter().take_config_err(&mut err))
+                .map(|itr| {
+                    itr.filter_map(|(domain, price)| {
+                        let domain = domain
+                            .parse::<u32>()
+                            .with_context(|| format!("Expected a domain id, got `{domain}`"))
+                            .take_err(&mut err, || price.cwp.clone())?;
+                        let usd = price.chain(&mut err).get_key("price").parse_f64().end()?;
+                        let decimals = price
+                            .chain(&mut err)
+                            .get_opt_key("decimals")
+                            .parse_u32()
+                            .unwrap_or(DEFAULT_TOKEN_DECIMALS);
+                        Some((
+                            domain,
+                            TokenPrice {
+                                price: usd,
+                                decimals,
+                            },
+                        ))
+                    })
+                    .collect()
+                })
+                .unwrap_or_default();
+            Some(TokenPriceSourceConf::Static(prices))
+        }
+        Some("file") => {
+            let refresh_interval = p
+                .chain(&mut err)
+                .get_opt_key("refreshInterval")
+                .parse_u64()
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_PRICE_REFRESH_INTERVAL);
+            p.chain(&mut err)
+                .get_key("path")
+                .parse_from_str("Expected price file path")
+                .end()
+                .map(|path| TokenPriceSourceConf::File {
+                    path,
+                    refresh_interval,
+                })
+        }
+        Some(t) => Err(eyre!("Unknown token price source type `{t}`"))
+            .take_err(&mut err, || &p.cwp + "type"),
+    };
+
+    // `source` is only missing if an error was recorded
+    err.into_result(source.unwrap_or_else(|| TokenPriceSourceConf::Static(HashMap::new())))
+}
+
 fn parse_address_list(
     str: &str,
     err: &mut ConfigParsingError,
@@ -342,10 +749,26 @@ fn parse_address_list(
         .collect_vec()
 }
 
+fn parse_reprepare_reasons(
+    str: &str,
+    err: &mut ConfigParsingError,
+    err_path: impl Fn() -> ConfigPath,
+) -> Vec<ReprepareReason> {
+    str.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| {
+            serde_json::from_value::<ReprepareReason>(Value::String(s.to_owned()))
+                .with_context(|| format!("Unknown reprepare reason `{s}`"))
+                .take_err(err, &err_path)
+        })
+        .collect_vec()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use hyperlane_core::H160;
+    use hyperlane_core::{HyperlaneMessage, H160};
 
     #[test]
     fn test_parse_address_blacklist() {
@@ -374,4 +797,201 @@ mod test {
         assert_eq!(res, vec![valid_address1, valid_address2]);
         assert!(!err.is_ok());
     }
+
+    #[test]
+    fn test_parse_reprepare_reasons() {
+        let mut err = ConfigParsingError::default();
+        let res = parse_reprepare_reasons(
+            "ErrorEstimatingGas, ExceedsMaxGasLimit",
+            &mut err,
+            ConfigPath::default,
+        );
+        assert_eq!(
+            res,
+            vec![
+                ReprepareReason::ErrorEstimatingGas,
+                ReprepareReason::ExceedsMaxGasLimit
+            ]
+        );
+        assert!(err.is_ok());
+
+        let mut err = ConfigParsingError::default();
+        let res = parse_reprepare_reasons(
+            "ErrorEstimatingGas,NotARealReason",
+            &mut err,
+            ConfigPath::default,
+        );
+        assert_eq!(res, vec![ReprepareReason::ErrorEstimatingGas]);
+        assert!(!err.is_ok());
+    }
+
+    #[test]
+    fn test_parse_combined_gas_payment_policies() {
+        let raw = serde_json::json!({
+            "type": "allOf",
+            "policies": [
+                { "type": "minimum", "payment": "100" },
+                {
+                    "type": "anyOf",
+                    "policies": [{ "type": "onChainFeeQuoting" }, { "type": "none" }]
+                }
+            ]
+        });
+        let mut err = ConfigParsingError::default();
+        let policy =
+            parse_gas_payment_policy(&ValueParser::new(Default::default(), &raw), &mut err);
+        assert!(err.is_ok());
+        let Some(GasPaymentEnforcementPolicy::AllOf(policies)) = policy else {
+            panic!("Expected an allOf policy, got {policy:?}");
+        };
+        assert!(matches!(
+            policies[0],
+            GasPaymentEnforcementPolicy::Minimum { payment } if payment == U256::from(100)
+        ));
+        assert!(matches!(
+            &policies[1],
+            GasPaymentEnforcementPolicy::AnyOf(any_of) if matches!(
+                any_of[..],
+                [
+                    GasPaymentEnforcementPolicy::OnChainFeeQuoting { .. },
+                    GasPaymentEnforcementPolicy::None
+                ]
+            )
+        ));
+
+        let raw = serde_json::json!({ "type": "anyOf", "policies": [] });
+        let mut err = ConfigParsingError::default();
+        parse_gas_payment_policy(&ValueParser::new(Default::default(), &raw), &mut err);
+        assert!(!err.is_ok());
+    }
+
+    #[test]
+    fn test_parse_token_price_source() {
+        let raw = serde_json::json!({
+            "type": "static",
+            "prices": {
+                "1": { "price": 2500.5 },
+                "1399811149": { "price": "150", "decimals": 9 }
+            }
+        });
+        let source = parse_token_price_source(ValueParser::new(Default::default(), &raw)).unwrap();
+        let TokenPriceSourceConf::Static(prices) = source else {
+            panic!("Expected a static price source, got {source:?}");
+        };
+        assert_eq!(
+            prices,
+            HashMap::from([
+                (
+                    1,
+                    TokenPrice {
+                        price: 2500.5,
+                        decimals: 18
+                    }
+                ),
+                (
+                    1399811149,
+                    TokenPrice {
+                        price: 150.,
+                        decimals: 9
+                    }
+                ),
+            ])
+        );
+
+        let raw =
+            serde_json::json!({ "type": "file", "path": "/prices.json", "refreshinterval": 30 });
+        let source = parse_token_price_source(ValueParser::new(Default::default(), &raw)).unwrap();
+        assert!(matches!(
+            source,
+            TokenPriceSourceConf::File { path, refresh_interval }
+                if path == PathBuf::from("/prices.json") && refresh_interval == Duration::from_secs(30)
+        ));
+
+        let raw = serde_json::json!({ "type": "static", "prices": { "ethereum": { "price": 1 } } });
+        assert!(parse_token_price_source(ValueParser::new(Default::default(), &raw)).is_err());
+    }
+
+    #[test]
+    fn test_parse_rate_limit() {
+        let raw = serde_json::json!({
+            "matchinglist": [{ "origindomain": 1 }],
+            "keyby": "appContext",
+            "maxmessages": 10,
+            "period": "60"
+        });
+        let mut err = ConfigParsingError::default();
+        let limit = parse_rate_limit(&ValueParser::new(Default::default(), &raw), &mut err);
+        assert!(err.is_ok());
+        let limit = limit.unwrap();
+        assert_eq!(limit.key, RateLimitKey::AppContext);
+        assert_eq!(limit.max_messages, 10);
+        assert_eq!(limit.period, Duration::from_secs(60));
+        let message = HyperlaneMessage {
+            origin: 1,
+            ..Default::default()
+        };
+        assert!(limit.matching_list.msg_matches(&message, false));
+
+        let raw = serde_json::json!({ "maxmessages": 10, "period": 60 });
+        let mut err = ConfigParsingError::default();
+        let limit = parse_rate_limit(&ValueParser::new(Default::default(), &raw), &mut err);
+        assert!(err.is_ok());
+        assert_eq!(limit.unwrap().key, RateLimitKey::Sender);
+
+        let raw = serde_json::json!({ "keyby": "recipient", "maxmessages": 10, "period": 60 });
+        let mut err = ConfigParsingError::default();
+        assert!(parse_rate_limit(&ValueParser::new(Default::default(), &raw), &mut err).is_none());
+        assert!(!err.is_ok());
+
+        let raw = serde_json::json!({ "maxmessages": 0, "period": 60 });
+        let mut err = ConfigParsingError::default();
+        assert!(parse_rate_limit(&ValueParser::new(Default::default(), &raw), &mut err).is_none());
+        assert!(!err.is_ok());
+    }
+
+    #[test]
+    fn test_parse_priority_lane() {
+        let raw = serde_json::json!({
+            "premiumappcontexts": ["app_a", "app_b"],
+            "mingasoverpayment": 1.5,
+            "premiumweight": 2
+        });
+        let mut err = ConfigParsingError::default();
+        let lane = parse_priority_lane(&ValueParser::new(Default::default(), &raw), &mut err);
+        assert!(err.is_ok());
+        assert_eq!(
+            lane,
+            Some(PriorityLaneConf {
+                premium_app_contexts: vec!["app_a".to_owned(), "app_b".to_owned()],
+                min_gas_overpayment: Some(1.5),
+                premium_weight: 2,
+            })
+        );
+
+        let raw = serde_json::json!({});
+        let mut err = ConfigParsingError::default();
+        let lane = parse_priority_lane(&ValueParser::new(Default::default(), &raw), &mut err);
+        assert!(err.is_ok());
+        assert_eq!(lane.unwrap().premium_weight, DEFAULT_PREMIUM_WEIGHT);
+
+        let raw = serde_json::json!({ "premiumweight": 0 });
+        let mut err = ConfigParsingError::default();
+        assert!(
+            parse_priority_lane(&ValueParser::new(Default::default(), &raw), &mut err).is_none()
+        );
+        assert!(!err.is_ok());
+    }
+
+    #[test]
+    fn test_should_dead_letter() {
+        let conf = DeadLetterConf {
+            max_retries: Some(10),
+            reasons: vec![ReprepareReason::ExceedsMaxGasLimit],
+        };
+        assert!(!conf.should_dead_letter(9, &ReprepareReason::ErrorEstimatingGas));
+        assert!(conf.should_dead_letter(10, &ReprepareReason::ErrorEstimatingGas));
+        assert!(conf.should_dead_letter(1, &ReprepareReason::ExceedsMaxGasLimit));
+        assert!(!DeadLetterConf::default()
+            .should_dead_letter(u32::MAX, &ReprepareReason::ErrorSubmitting));
+    }
 }
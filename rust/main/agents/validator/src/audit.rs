@@ -0,0 +1,426 @@
+//! Self-audit of the checkpoints published by the validator.
+//!
+//! Partial writes or a misconfigured checkpoint syncer can leave published
+//! checkpoints missing or wrong without the submitter noticing. The auditor
+//! walks the checkpoints published up to the latest index of the checkpoint
+//! syncer, and checks each of them against the checkpoint recomputed from the
+//! locally indexed merkle tree insertions. Each storage location of the
+//! checkpoint syncer is audited on its own, so that a location that missed
+//! some writes doesn't go unnoticed behind the others. The latest audited
+//! index is persisted, so that a restart only rebuilds the merkle tree from
+//! the local insertions instead of fetching every published checkpoint again.
+
+use std::{sync::Arc, time::Duration};
+
+use eyre::Result;
+use prometheus::{IntCounterVec, IntGauge};
+use tokio::time::sleep;
+use tracing::{debug, error, info, warn};
+
+use hyperlane_base::{
+    db::{HyperlaneDb, HyperlaneRocksDB},
+    CheckpointSyncer, CoreMetrics,
+};
+use hyperlane_core::{
+    accumulator::incremental::IncrementalMerkle, Checkpoint, CheckpointWithMessageId,
+    HyperlaneDomain, H160, H256,
+};
+
+/// Why a published checkpoint failed the audit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AuditFailure {
+    /// No checkpoint was published for the index
+    Missing,
+    /// The checkpoint wasn't signed by the validator
+    WrongSigner,
+    /// The checkpoint doesn't match the locally indexed merkle tree
+    Mismatch,
+}
+
+impl AuditFailure {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AuditFailure::Missing => "missing",
+            AuditFailure::WrongSigner => "wrong_signer",
+            AuditFailure::Mismatch => "mismatch",
+        }
+    }
+}
+
+/// Periodically re-verifies the checkpoints published by the validator
+pub(crate) struct CheckpointAuditor {
+    interval: Duration,
+    validator: H160,
+    merkle_tree_hook_address: H256,
+    mailbox_domain: u32,
+    checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    db: HyperlaneRocksDB,
+    metrics: CheckpointAuditorMetrics,
+}
+
+impl CheckpointAuditor {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        interval: Duration,
+        validator: H160,
+        merkle_tree_hook_address: H256,
+        mailbox_domain: u32,
+        checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+        db: HyperlaneRocksDB,
+        metrics: CheckpointAuditorMetrics,
+    ) -> Self {
+        Self {
+            interval,
+            validator,
+            merkle_tree_hook_address,
+            mailbox_domain,
+            checkpoint_syncer,
+            db,
+            metrics,
+        }
+    }
+
+    /// Audits the published checkpoints indefinitely. Every index is audited
+    /// once after it is published, resuming after the latest audited index
+    /// after a restart.
+    pub(crate) async fn run(self) {
+        let mut tree = match self.audited_tree() {
+            Ok(tree) => tree,
+            Err(err) => {
+                warn!(
+                    ?err,
+                    "Failed to restore the audited merkle tree, auditing from the first index"
+                );
+                IncrementalMerkle::default()
+            }
+        };
+        loop {
+            match self.audit(&mut tree).await {
+                Ok(failures) if !failures.is_empty() => {
+                    warn!(
+                        failures = failures.len(),
+                        "Published checkpoints failed the self-audit"
+                    );
+                }
+                Ok(_) => {}
+                Err(err) => warn!(?err, "Failed to audit the published checkpoints"),
+            }
+            sleep(self.interval).await;
+        }
+    }
+
+    /// The merkle tree up to the latest audited index, rebuilt from the local
+    /// merkle tree insertions
+    pub(crate) fn audited_tree(&self) -> Result<IncrementalMerkle> {
+        let mut tree = IncrementalMerkle::default();
+        let Some(latest_audited_index) = self
+            .db
+            .retrieve_latest_audited_checkpoint_index(&self.merkle_tree_hook_address)?
+        else {
+            return Ok(tree);
+        };
+        for index in 0..=latest_audited_index {
+            let Some(insertion) = self
+                .db
+                .retrieve_merkle_tree_insertion_by_leaf_index(&index)?
+            else {
+                // The audit resumes from the first index missing locally
+                warn!(index, "Merkle tree insertion of an audited index not found");
+                break;
+            };
+            tree.ingest(insertion.message_id());
+        }
+        if let Some(index) = tree.count().checked_sub(1) {
+            info!(
+                index,
+                "Resuming the audit of published checkpoints after index"
+            );
+            self.metrics.latest_checkpoint_audited.set(index as i64);
+        }
+        Ok(tree)
+    }
+
+    /// The checkpoint syncer of each storage location
+    fn audited_syncers(&self) -> Vec<Arc<dyn CheckpointSyncer>> {
+        let backends = self.checkpoint_syncer.backends();
+        if backends.is_empty() {
+            vec![self.checkpoint_syncer.clone()]
+        } else {
+            backends
+        }
+    }
+
+    /// Audits the checkpoints published after the last index of `tree`, which
+    /// is extended with the merkle tree insertion of every audited index.
+    /// Returns the indexes that failed the audit, along with the storage
+    /// location they failed it in.
+    pub(crate) async fn audit(
+        &self,
+        tree: &mut IncrementalMerkle,
+    ) -> Result<Vec<(u32, String, AuditFailure)>> {
+        let Some(latest_index) = self.checkpoint_syncer.latest_index().await? else {
+            debug!("No checkpoint published yet");
+            return Ok(vec![]);
+        };
+
+        let syncers = self.audited_syncers();
+        let audited_count = tree.count();
+        let mut failures = vec![];
+        while (tree.count() as u32) <= latest_index {
+            let index = tree.count() as u32;
+            let Some(insertion) = self
+                .db
+                .retrieve_merkle_tree_insertion_by_leaf_index(&index)?
+            else {
+                debug!(
+                    index,
+                    "Merkle tree insertion not indexed yet, resuming the audit later"
+                );
+                break;
+            };
+            // Only move on to the next index once this one was audited
+            let mut next_tree = tree.clone();
+            next_tree.ingest(insertion.message_id());
+            let expected = CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: self.merkle_tree_hook_address,
+                    mailbox_domain: self.mailbox_domain,
+                    root: next_tree.root(),
+                    index,
+                },
+                message_id: insertion.message_id(),
+            };
+            for syncer in &syncers {
+                let Some(failure) = self.audit_checkpoint(syncer.as_ref(), &expected).await? else {
+                    continue;
+                };
+                let location = syncer.announcement_location();
+                error!(
+                    index,
+                    %location,
+                    ?failure,
+                    ?expected,
+                    "Published checkpoint failed the self-audit"
+                );
+                self.metrics.failure(&location, failure).inc();
+                failures.push((index, location, failure));
+            }
+            *tree = next_tree;
+            self.db
+                .store_latest_audited_checkpoint_index(&self.merkle_tree_hook_address, &index)?;
+            self.metrics.latest_checkpoint_audited.set(index as i64);
+        }
+        if tree.count() > audited_count {
+            info!(
+                index = tree.count() - 1,
+                failures = failures.len(),
+                "Audited published checkpoints until index"
+            );
+        }
+        Ok(failures)
+    }
+
+    async fn audit_checkpoint(
+        &self,
+        syncer: &dyn CheckpointSyncer,
+        expected: &CheckpointWithMessageId,
+    ) -> Result<Option<AuditFailure>> {
+        let Some(signed) = syncer.fetch_checkpoint(expected.index).await? else {
+            return Ok(Some(AuditFailure::Missing));
+        };
+        match signed.recover() {
+            Ok(signer) if signer == self.validator => {}
+            _ => return Ok(Some(AuditFailure::WrongSigner)),
+        }
+        if signed.value != *expected {
+            return Ok(Some(AuditFailure::Mismatch));
+        }
+        Ok(None)
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct CheckpointAuditorMetrics {
+    latest_checkpoint_audited: IntGauge,
+    failures: IntCounterVec,
+    chain_name: String,
+}
+
+impl CheckpointAuditorMetrics {
+    pub fn new(metrics: &CoreMetrics, mailbox_chain: &HyperlaneDomain) -> Self {
+        let chain_name = mailbox_chain.name();
+        Self {
+            latest_checkpoint_audited: metrics
+                .latest_checkpoint()
+                .with_label_values(&["validator_audited", chain_name]),
+            failures: metrics.checkpoint_audit_failures(),
+            chain_name: chain_name.to_owned(),
+        }
+    }
+
+    fn failure(&self, location: &str, failure: AuditFailure) -> prometheus::IntCounter {
+        self.failures
+            .with_label_values(&[&self.chain_name, location, failure.as_str()])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::signers::LocalWallet;
+    use hyperlane_base::{
+        db::{HyperlaneRocksDB, DB},
+        FanOutCheckpointSyncer, FanOutWritePolicy, LocalStorage,
+    };
+    use hyperlane_core::{
+        test_utils::dummy_domain, HyperlaneSigner, HyperlaneSignerExt, MerkleTreeInsertion,
+    };
+    use hyperlane_ethereum::Signers;
+    use prometheus::Registry;
+
+    use super::*;
+
+    fn signer(key: &str) -> Signers {
+        key.repeat(64).parse::<LocalWallet>().unwrap().into()
+    }
+
+    #[tokio::test]
+    async fn audit_reports_missing_foreign_and_mismatched_checkpoints() {
+        let domain = dummy_domain(1, "origin");
+        let hook = H256::repeat_byte(1);
+        let validator = signer("1");
+
+        let db_dir = tempfile::tempdir().unwrap();
+        let db = HyperlaneRocksDB::new(&domain, DB::from_path(db_dir.path()).unwrap());
+        let syncer_dir = tempfile::tempdir().unwrap();
+        let checkpoint_syncer = LocalStorage::new(syncer_dir.path().to_owned(), None).unwrap();
+        let location = checkpoint_syncer.announcement_location();
+
+        // Index 0 is published correctly, 1 is missing, 2 is signed by another
+        // key and 3 has a wrong root. Index 4 isn't indexed locally yet.
+        let mut tree = IncrementalMerkle::default();
+        for index in 0..4 {
+            let insertion = MerkleTreeInsertion::new(index, H256::random());
+            db.store_merkle_tree_insertion_by_leaf_index(&index, &insertion)
+                .unwrap();
+            tree.ingest(insertion.message_id());
+            let mut checkpoint = CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: hook,
+                    mailbox_domain: domain.id(),
+                    root: tree.root(),
+                    index,
+                },
+                message_id: insertion.message_id(),
+            };
+            let signed = match index {
+                1 => continue,
+                2 => signer("2").sign(checkpoint).await.unwrap(),
+                3 => {
+                    checkpoint.checkpoint.root = H256::random();
+                    validator.sign(checkpoint).await.unwrap()
+                }
+                _ => validator.sign(checkpoint).await.unwrap(),
+            };
+            checkpoint_syncer.write_checkpoint(&signed).await.unwrap();
+        }
+        checkpoint_syncer.write_latest_index(4).await.unwrap();
+
+        let core_metrics = CoreMetrics::new("validator", 37582, Registry::new()).unwrap();
+        let auditor = CheckpointAuditor::new(
+            Duration::from_secs(1),
+            validator.eth_address(),
+            hook,
+            domain.id(),
+            Arc::new(checkpoint_syncer),
+            db,
+            CheckpointAuditorMetrics::new(&core_metrics, &domain),
+        );
+
+        let mut audited_tree = IncrementalMerkle::default();
+        assert_eq!(
+            auditor.audit(&mut audited_tree).await.unwrap(),
+            vec![
+                (1, location.clone(), AuditFailure::Missing),
+                (2, location.clone(), AuditFailure::WrongSigner),
+                (3, location.clone(), AuditFailure::Mismatch),
+            ]
+        );
+        assert_eq!(audited_tree.root(), tree.root());
+        assert_eq!(
+            core_metrics
+                .checkpoint_audit_failures()
+                .with_label_values(&["origin", &location, "missing"])
+                .get(),
+            1
+        );
+        // Audited indexes aren't audited again, including after a restart
+        assert!(auditor.audit(&mut audited_tree).await.unwrap().is_empty());
+        let mut restored_tree = auditor.audited_tree().unwrap();
+        assert_eq!(restored_tree.root(), tree.root());
+        assert!(auditor.audit(&mut restored_tree).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn audit_checks_each_storage_location() {
+        let domain = dummy_domain(1, "origin");
+        let hook = H256::repeat_byte(1);
+        let validator = signer("1");
+
+        let db_dir = tempfile::tempdir().unwrap();
+        let db = HyperlaneRocksDB::new(&domain, DB::from_path(db_dir.path()).unwrap());
+        let syncer_dirs = [tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap()];
+        let syncers = syncer_dirs
+            .iter()
+            .map(|dir| {
+                Arc::new(LocalStorage::new(dir.path().to_owned(), None).unwrap())
+                    as Arc<dyn CheckpointSyncer>
+            })
+            .collect::<Vec<_>>();
+
+        // The second storage location missed the write of index 1
+        let mut tree = IncrementalMerkle::default();
+        for index in 0..3 {
+            let insertion = MerkleTreeInsertion::new(index, H256::random());
+            db.store_merkle_tree_insertion_by_leaf_index(&index, &insertion)
+                .unwrap();
+            tree.ingest(insertion.message_id());
+            let checkpoint = CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: hook,
+                    mailbox_domain: domain.id(),
+                    root: tree.root(),
+                    index,
+                },
+                message_id: insertion.message_id(),
+            };
+            let signed = validator.sign(checkpoint).await.unwrap();
+            for (position, syncer) in syncers.iter().enumerate() {
+                if (position, index) != (1, 1) {
+                    syncer.write_checkpoint(&signed).await.unwrap();
+                }
+                syncer.write_latest_index(index).await.unwrap();
+            }
+        }
+
+        let core_metrics = CoreMetrics::new("validator", 37582, Registry::new()).unwrap();
+        let fan_out = FanOutCheckpointSyncer::new(syncers.clone(), FanOutWritePolicy::All).unwrap();
+        let auditor = CheckpointAuditor::new(
+            Duration::from_secs(1),
+            validator.eth_address(),
+            hook,
+            domain.id(),
+            Arc::new(fan_out),
+            db,
+            CheckpointAuditorMetrics::new(&core_metrics, &domain),
+        );
+
+        // Reading through the fan-out would find index 1 in the first location
+        assert_eq!(
+            auditor
+                .audit(&mut IncrementalMerkle::default())
+                .await
+                .unwrap(),
+            vec![(1, syncers[1].announcement_location(), AuditFailure::Missing)]
+        );
+    }
+}
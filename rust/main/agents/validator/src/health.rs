@@ -0,0 +1,249 @@
+//! Liveness of the validator services, as reported by the EigenNodeApi.
+//!
+//! The task of each service is spawned through `ServiceHealth`, which records
+//! whether it is running, finished or panicked. Running services also record
+//! when they last made progress, and are reported down if they stop doing so.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    panic::AssertUnwindSafe,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use futures::FutureExt;
+use prometheus::IntGauge;
+use tokio::{task::JoinHandle, time::sleep};
+
+/// How long a running service may go without making progress before it is
+/// reported down
+pub(crate) const MAX_PROGRESS_AGE: Duration = Duration::from_secs(300);
+/// How many checkpoints the submitter may lag behind the latest observed
+/// checkpoint before it is reported down
+pub(crate) const MAX_CHECKPOINT_LAG: i64 = 10;
+
+/// A service of the validator whose health is reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum ValidatorService {
+    /// Indexes the merkle tree hook insertions
+    Indexer,
+    /// Signs the checkpoints of new messages
+    Submitter,
+    /// Signs the checkpoints of the messages sent before the validator started
+    BackfillSubmitter,
+}
+
+impl ValidatorService {
+    pub(crate) const ALL: [ValidatorService; 3] = [
+        ValidatorService::Indexer,
+        ValidatorService::Submitter,
+        ValidatorService::BackfillSubmitter,
+    ];
+
+    pub(crate) fn id(&self) -> &'static str {
+        match self {
+            ValidatorService::Indexer => "hyperlane-validator-indexer",
+            ValidatorService::Submitter => "hyperlane-validator-submitter",
+            ValidatorService::BackfillSubmitter => "hyperlane-validator-backfill-submitter",
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            ValidatorService::Indexer => "indexer",
+            ValidatorService::Submitter => "submitter",
+            ValidatorService::BackfillSubmitter => "backfill-submitter",
+        }
+    }
+
+    pub(crate) fn description(&self) -> &'static str {
+        match self {
+            ValidatorService::Indexer => "indexes the messages from the origin chain mailbox",
+            ValidatorService::Submitter => "signs messages indexed from the indexer",
+            ValidatorService::BackfillSubmitter => {
+                "signs messages sent before the validator started"
+            }
+        }
+    }
+
+    pub(crate) fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|service| service.id() == id)
+    }
+}
+
+/// The state of the task of a service
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TaskState {
+    Running,
+    Finished,
+    Panicked,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ServiceState {
+    task: TaskState,
+    started_at: Instant,
+    last_progress: Option<Instant>,
+}
+
+/// The health of a service
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Health {
+    /// The service hasn't started or made progress yet
+    Initializing,
+    /// The service is running and making progress
+    Up,
+    /// The service stopped, stalled or lags behind
+    Down,
+}
+
+/// Tracks the liveness of the validator services
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ServiceHealth {
+    services: Arc<Mutex<HashMap<ValidatorService, ServiceState>>>,
+}
+
+impl ServiceHealth {
+    /// Spawns the task of `service`, recording when it finishes or panics.
+    /// Panics are still propagated through the returned handle.
+    pub(crate) fn spawn<F>(&self, service: ValidatorService, task: F) -> JoinHandle<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.services().insert(
+            service,
+            ServiceState {
+                task: TaskState::Running,
+                started_at: Instant::now(),
+                last_progress: None,
+            },
+        );
+        let health = self.clone();
+        tokio::spawn(async move {
+            let result = AssertUnwindSafe(task).catch_unwind().await;
+            let state = match result {
+                Ok(()) => TaskState::Finished,
+                Err(_) => TaskState::Panicked,
+            };
+            if let Some(service_state) = health.services().get_mut(&service) {
+                service_state.task = state;
+            }
+            if let Err(panic) = result {
+                std::panic::resume_unwind(panic);
+            }
+        })
+    }
+
+    /// Records that `service` made progress
+    pub(crate) fn record_progress(&self, service: ValidatorService) {
+        if let Some(state) = self.services().get_mut(&service) {
+            state.last_progress = Some(Instant::now());
+        }
+    }
+
+    /// Records that `service` made progress whenever `gauge` changes, e.g. the
+    /// block height indexed by a contract sync. Never returns.
+    pub(crate) async fn record_gauge_progress(
+        self,
+        service: ValidatorService,
+        gauge: IntGauge,
+        interval: Duration,
+    ) {
+        let mut last_value = gauge.get();
+        loop {
+            sleep(interval).await;
+            let value = gauge.get();
+            if value != last_value {
+                last_value = value;
+                self.record_progress(service);
+            }
+        }
+    }
+
+    /// The health of `service`, given how many checkpoints the submitter lags
+    /// behind
+    pub(crate) fn health(&self, service: ValidatorService, checkpoint_lag: i64) -> Health {
+        self.health_at(service, checkpoint_lag, Instant::now())
+    }
+
+    fn health_at(&self, service: ValidatorService, checkpoint_lag: i64, now: Instant) -> Health {
+        let Some(state) = self.services().get(&service).copied() else {
+            return Health::Initializing;
+        };
+        match state.task {
+            TaskState::Panicked => Health::Down,
+            // Only the backfill submitter is expected to be done at some point
+            TaskState::Finished if service == ValidatorService::BackfillSubmitter => Health::Up,
+            TaskState::Finished => Health::Down,
+            // The backfill submitter signs all its checkpoints in one go, so
+            // it doesn't report progress
+            TaskState::Running if service == ValidatorService::BackfillSubmitter => Health::Up,
+            TaskState::Running => {
+                let since = state.last_progress.unwrap_or(state.started_at);
+                if now.saturating_duration_since(since) > MAX_PROGRESS_AGE {
+                    Health::Down
+                } else if state.last_progress.is_none() {
+                    Health::Initializing
+                } else if service == ValidatorService::Submitter
+                    && checkpoint_lag > MAX_CHECKPOINT_LAG
+                {
+                    Health::Down
+                } else {
+                    Health::Up
+                }
+            }
+        }
+    }
+
+    fn services(&self) -> std::sync::MutexGuard<'_, HashMap<ValidatorService, ServiceState>> {
+        self.services.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_health() {
+        let health = ServiceHealth::default();
+        assert_eq!(
+            health.health(ValidatorService::Indexer, 0),
+            Health::Initializing
+        );
+
+        let indexer = health.spawn(ValidatorService::Indexer, futures::future::pending());
+        let submitter = health.spawn(ValidatorService::Submitter, futures::future::pending());
+        let backfill = health.spawn(ValidatorService::BackfillSubmitter, async {});
+        backfill.await.unwrap();
+        assert_eq!(
+            health.health(ValidatorService::Indexer, 0),
+            Health::Initializing
+        );
+        assert_eq!(
+            health.health(ValidatorService::BackfillSubmitter, 0),
+            Health::Up
+        );
+
+        health.record_progress(ValidatorService::Indexer);
+        health.record_progress(ValidatorService::Submitter);
+        assert_eq!(health.health(ValidatorService::Indexer, 0), Health::Up);
+        assert_eq!(health.health(ValidatorService::Submitter, 0), Health::Up);
+        assert_eq!(
+            health.health(ValidatorService::Submitter, MAX_CHECKPOINT_LAG + 1),
+            Health::Down
+        );
+        let later = Instant::now() + MAX_PROGRESS_AGE + Duration::from_secs(1);
+        assert_eq!(
+            health.health_at(ValidatorService::Indexer, 0, later),
+            Health::Down
+        );
+
+        indexer.abort();
+        submitter.abort();
+        let panicked = health.spawn(ValidatorService::Indexer, async { panic!("indexer") });
+        assert!(panicked.await.unwrap_err().is_panic());
+        assert_eq!(health.health(ValidatorService::Indexer, 0), Health::Down);
+    }
+}
@@ -9,8 +9,11 @@ use hyperlane_base::agent_main;
 
 use crate::validator::Validator;
 
+mod audit;
+mod health;
 mod server;
 mod settings;
+mod slashing_protection;
 mod submit;
 mod validator;
 
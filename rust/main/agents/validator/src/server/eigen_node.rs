@@ -10,11 +10,12 @@
 //! - /node/health - Node Health
 //!  eg. response 200 - healthy, 206 - partially healthy, 503 - unhealthy
 //! - /node/services - List of Services
-//!  eg. response [{"id":"hyperlane-validator-indexer","name":"indexer","description":"indexes the messages from the origin chain mailbox","status":"up"},{"id":"hyperlane-validator-submitter","name":"submitter","description":"signs messages indexed from the indexer","status":"up"}]
+//!  eg. response [{"id":"hyperlane-validator-indexer","name":"indexer","description":"indexes the messages from the origin chain mailbox","status":"Up"},{"id":"hyperlane-validator-submitter","name":"submitter","description":"signs messages indexed from the indexer","status":"Up"},{"id":"hyperlane-validator-backfill-submitter","name":"backfill-submitter","description":"signs messages sent before the validator started","status":"Up"}]
 //! - /node/services/:service_id/health - Service Health
-//! eg. response 200 - healthy, 503 - unhealthy  
+//! eg. response 200 - healthy, 503 - unhealthy or initializing, 404 - unknown service
 
 use axum::{
+    extract::Path,
     http::StatusCode,
     response::IntoResponse,
     routing::{get, Router},
@@ -26,6 +27,8 @@ use hyperlane_core::HyperlaneDomain;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+use crate::health::{Health, ServiceHealth, ValidatorService};
+
 const EIGEN_NODE_API_BASE: &str = "/eigen";
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -35,6 +38,16 @@ enum ServiceStatus {
     Initializing,
 }
 
+impl From<Health> for ServiceStatus {
+    fn from(health: Health) -> Self {
+        match health {
+            Health::Up => ServiceStatus::Up,
+            Health::Down => ServiceStatus::Down,
+            Health::Initializing => ServiceStatus::Initializing,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 struct NodeInfo {
     node_name: String,
@@ -50,10 +63,11 @@ struct Service {
     status: ServiceStatus,
 }
 
-#[derive(new)]
+#[derive(new, Clone)]
 pub struct EigenNodeApi {
     origin_chain: HyperlaneDomain,
     core_metrics: Arc<CoreMetrics>,
+    service_health: ServiceHealth,
 }
 
 impl EigenNodeApi {
@@ -70,9 +84,15 @@ impl EigenNodeApi {
         let health_route = get(move || {
             Self::node_health_handler(origin_chain.clone(), core_metrics_clone.clone())
         });
+        let api = self.clone();
+        let node_services_route = get(move || api.clone().node_services_handler());
+        let api = self.clone();
+        let service_health_route = get(move |Path(service_id): Path<String>| {
+            api.clone().service_health_handler(service_id)
+        });
         let services_route = Router::new()
-            .route("/", get(Self::node_services_handler))
-            .route("/:service_id/health", get(Self::service_health_handler));
+            .route("/", node_services_route)
+            .route("/:service_id/health", service_health_route);
 
         let node_route = Router::new()
             .route("/health", health_route)
@@ -114,31 +134,36 @@ impl EigenNodeApi {
         }
     }
 
-    /// Method to return a list of services
-    /// NB: hardcoded for now
-    pub async fn node_services_handler() -> impl IntoResponse {
-        let services = vec![
-            Service {
-                id: "hyperlane-validator-indexer".to_string(),
-                name: "indexer".to_string(),
-                description: "indexes the messages from the origin chain mailbox".to_string(),
-                status: ServiceStatus::Up,
-            },
-            Service {
-                id: "hyperlane-validator-submitter".to_string(),
-                name: "submitter".to_string(),
-                description: "signs messages indexed from the indexer".to_string(),
-                status: ServiceStatus::Up,
-            },
-        ];
+    /// Method to return the list of services and their status
+    pub async fn node_services_handler(self) -> impl IntoResponse {
+        let checkpoint_lag = self.checkpoint_lag();
+        let services = ValidatorService::ALL
+            .into_iter()
+            .map(|service| Service {
+                id: service.id().to_string(),
+                name: service.name().to_string(),
+                description: service.description().to_string(),
+                status: self.service_health.health(service, checkpoint_lag).into(),
+            })
+            .collect::<Vec<_>>();
         Json(services)
     }
 
     /// Method to return the health of a service
-    pub async fn service_health_handler(_service_id: String) -> impl IntoResponse {
-        // TODO: implement logic to check if the service is healthy
-        // now just return 200
-        StatusCode::OK
+    /// 200 - healthy, 503 - unhealthy or initializing, 404 - unknown service
+    pub async fn service_health_handler(self, service_id: String) -> impl IntoResponse {
+        let Some(service) = ValidatorService::from_id(&service_id) else {
+            return StatusCode::NOT_FOUND;
+        };
+        match self.service_health.health(service, self.checkpoint_lag()) {
+            Health::Up => StatusCode::OK,
+            Health::Down | Health::Initializing => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    fn checkpoint_lag(&self) -> i64 {
+        self.core_metrics
+            .get_latest_checkpoint_validator_delta(self.origin_chain.clone())
     }
 }
 
@@ -147,13 +172,14 @@ mod tests {
     use std::net::SocketAddr;
 
     use super::*;
+    use crate::health::MAX_CHECKPOINT_LAG;
     use axum::http::StatusCode;
     use prometheus::Registry;
 
     const PARTIALLY_HEALTHY_OBSERVED_CHECKPOINT: i64 = 34;
     const HEALTHY_OBSERVED_CHECKPOINT: i64 = 42;
 
-    async fn setup_test_server() -> (reqwest::Client, SocketAddr, Arc<CoreMetrics>) {
+    async fn setup_test_server() -> (reqwest::Client, SocketAddr, Arc<CoreMetrics>, ServiceHealth) {
         let core_metrics =
             Arc::new(CoreMetrics::new("dummy_validator", 37582, Registry::new()).unwrap());
         // Initialize the Prometheus registry
@@ -162,9 +188,11 @@ mod tests {
             .with_label_values(&["validator_observed", "ethereum"])
             .set(HEALTHY_OBSERVED_CHECKPOINT);
 
+        let service_health = ServiceHealth::default();
         let node_api = EigenNodeApi::new(
             HyperlaneDomain::new_test_domain("ethereum"),
             Arc::clone(&core_metrics),
+            service_health.clone(),
         );
         let app = node_api.router();
 
@@ -177,12 +205,12 @@ mod tests {
         // Create a client
         let client = reqwest::Client::new();
 
-        (client, addr, core_metrics)
+        (client, addr, core_metrics, service_health)
     }
 
     #[tokio::test]
     async fn test_eigen_node_api() {
-        let (client, addr, _) = setup_test_server().await;
+        let (client, addr, _, _) = setup_test_server().await;
         let res = client
             .get(format!("http://{}/node", addr))
             .send()
@@ -206,7 +234,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_eigen_node_health_api() {
-        let (client, addr, core_metrics) = setup_test_server().await;
+        let (client, addr, core_metrics, _) = setup_test_server().await;
         let res = client
             .get(format!("http://{}/node/health", addr))
             .send()
@@ -239,7 +267,17 @@ mod tests {
 
     #[tokio::test]
     async fn test_eigen_node_services_handler() {
-        let (client, addr, _) = setup_test_server().await;
+        let (client, addr, core_metrics, service_health) = setup_test_server().await;
+        service_health.spawn(ValidatorService::Indexer, futures::future::pending());
+        service_health.spawn(ValidatorService::Submitter, futures::future::pending());
+        service_health.record_progress(ValidatorService::Indexer);
+        service_health.record_progress(ValidatorService::Submitter);
+        // The submitter lags too far behind the observed checkpoint
+        core_metrics
+            .latest_checkpoint()
+            .with_label_values(&["validator_processed", "ethereum"])
+            .set(HEALTHY_OBSERVED_CHECKPOINT - MAX_CHECKPOINT_LAG - 1);
+
         let res = client
             .get(format!("http://{}/node/services", addr))
             .send()
@@ -258,7 +296,13 @@ mod tests {
                 id: "hyperlane-validator-submitter".to_string(),
                 name: "submitter".to_string(),
                 description: "signs messages indexed from the indexer".to_string(),
-                status: ServiceStatus::Up,
+                status: ServiceStatus::Down,
+            },
+            Service {
+                id: "hyperlane-validator-backfill-submitter".to_string(),
+                name: "backfill-submitter".to_string(),
+                description: "signs messages sent before the validator started".to_string(),
+                status: ServiceStatus::Initializing,
             },
         ];
         let services: Vec<Service> = res.json().await.expect("Failed to parse json");
@@ -267,17 +311,39 @@ mod tests {
 
     #[tokio::test]
     async fn test_service_health_handler() {
-        let (client, addr, _) = setup_test_server().await;
-        let res = client
-            .get(format!(
-                "http://{}/node/services/hyperlane-validator-indexer/health",
-                addr
-            ))
-            .send()
-            .await
-            .expect("Failed to send request");
+        let (client, addr, _, service_health) = setup_test_server().await;
+        let service_health_status = |service_id: &str| {
+            let request = client
+                .get(format!(
+                    "http://{}/node/services/{}/health",
+                    addr, service_id
+                ))
+                .send();
+            async move { request.await.expect("Failed to send request").status() }
+        };
 
-        // Check that the response status is OK
-        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            service_health_status("hyperlane-validator-indexer").await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        service_health.spawn(ValidatorService::Indexer, futures::future::pending());
+        service_health.record_progress(ValidatorService::Indexer);
+        assert_eq!(
+            service_health_status("hyperlane-validator-indexer").await,
+            StatusCode::OK
+        );
+
+        let panicked = service_health.spawn(ValidatorService::Indexer, async { panic!("indexer") });
+        assert!(panicked.await.unwrap_err().is_panic());
+        assert_eq!(
+            service_health_status("hyperlane-validator-indexer").await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        assert_eq!(
+            service_health_status("hyperlane-validator-relayer").await,
+            StatusCode::NOT_FOUND
+        );
     }
 }
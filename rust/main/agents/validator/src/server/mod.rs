@@ -1,19 +1,29 @@
 pub mod eigen_node;
+pub mod slashing_protection;
 use std::{sync::Arc, vec};
 
 use axum::Router;
 pub use eigen_node::EigenNodeApi;
+pub use slashing_protection::SlashingProtectionApi;
 
 use hyperlane_base::CoreMetrics;
 use hyperlane_core::HyperlaneDomain;
 
+use crate::{health::ServiceHealth, slashing_protection::SlashingProtection};
+
 /// Returns a vector of validator-specific endpoint routes to be served.
 /// Can be extended with additional routes and feature flags to enable/disable individually.
 pub fn routes(
     origin_chain: HyperlaneDomain,
     metrics: Arc<CoreMetrics>,
+    slashing_protection: SlashingProtection,
+    service_health: ServiceHealth,
 ) -> Vec<(&'static str, Router)> {
-    let eigen_node_api = EigenNodeApi::new(origin_chain, metrics);
+    let eigen_node_api = EigenNodeApi::new(origin_chain, metrics, service_health);
+    let slashing_protection_api = SlashingProtectionApi::new(slashing_protection);
 
-    vec![eigen_node_api.get_route()]
+    vec![
+        eigen_node_api.get_route(),
+        slashing_protection_api.get_route(),
+    ]
 }
@@ -0,0 +1,44 @@
+//! A route exporting the checkpoints signed by the validator in the slashing
+//! protection interchange format
+//!
+//! Routes
+//! - /slashing_protection/export - the slashing protection interchange
+//!   eg. response {"interchange_format_version":1,"validator":"0x...","signed_checkpoints":[...]}
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, Router},
+    Json,
+};
+use derive_new::new;
+
+use crate::slashing_protection::SlashingProtection;
+
+const SLASHING_PROTECTION_API_BASE: &str = "/slashing_protection";
+
+#[derive(new)]
+pub struct SlashingProtectionApi {
+    slashing_protection: SlashingProtection,
+}
+
+impl SlashingProtectionApi {
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (SLASHING_PROTECTION_API_BASE, self.router())
+    }
+
+    pub fn router(&self) -> Router {
+        let slashing_protection = self.slashing_protection.clone();
+        Router::new().route(
+            "/export",
+            get(move || Self::export_handler(slashing_protection.clone())),
+        )
+    }
+
+    async fn export_handler(slashing_protection: SlashingProtection) -> Response {
+        match slashing_protection.export() {
+            Ok(interchange) => Json(interchange).into_response(),
+            Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+        }
+    }
+}
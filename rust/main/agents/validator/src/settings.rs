@@ -14,6 +14,7 @@ use hyperlane_base::{
         parser::{RawAgentConf, RawAgentSignerConf, ValueParser},
         CheckpointSyncerConf, Settings, SignerConf,
     },
+    FanOutWritePolicy,
 };
 use hyperlane_core::{
     cfg_unwrap_all, config::*, HyperlaneDomain, HyperlaneDomainProtocol, ReorgPeriod,
@@ -32,6 +33,12 @@ pub struct ValidatorSettings {
 
     /// Database path
     pub db: PathBuf,
+    /// Path of the slashing protection database, which must not be wiped
+    /// along with the main database. Defaults to the database path suffixed
+    /// with `_slashing_protection`.
+    pub slashing_protection_db: PathBuf,
+    /// Path of a slashing protection interchange to import on startup
+    pub slashing_protection_import: Option<PathBuf>,
     /// Chain to validate messages on
     pub origin_chain: HyperlaneDomain,
     /// The validator attestation signer
@@ -42,6 +49,8 @@ pub struct ValidatorSettings {
     pub reorg_period: ReorgPeriod,
     /// How frequently to check for new checkpoints
     pub interval: Duration,
+    /// How frequently to audit the published checkpoints, if at all
+    pub self_audit_interval: Option<Duration>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -103,6 +112,22 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
                     .join(format!("validator_db_{}", origin_chain_name.unwrap_or("")))
             });
 
+        let slashing_protection_db = p
+            .chain(&mut err)
+            .get_opt_key("slashingProtectionDb")
+            .parse_from_str("Expected slashing protection db file path")
+            .unwrap_or_else(|| {
+                let mut path = db.clone().into_os_string();
+                path.push("_slashing_protection");
+                path.into()
+            });
+
+        let slashing_protection_import = p
+            .chain(&mut err)
+            .get_opt_key("slashingProtectionImport")
+            .parse_from_str("Expected slashing protection interchange file path")
+            .end();
+
         let checkpoint_syncer = p
             .chain(&mut err)
             .get_key("checkpointSyncer")
@@ -116,6 +141,13 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
             .map(Duration::from_secs)
             .unwrap_or(Duration::from_secs(5));
 
+        let self_audit_interval = p
+            .chain(&mut err)
+            .get_opt_key("selfAuditInterval")
+            .parse_u64()
+            .map(Duration::from_secs)
+            .end();
+
         cfg_unwrap_all!(cwp, err: [origin_chain_name]);
 
         let reorg_period = p
@@ -140,11 +172,14 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
         err.into_result(Self {
             base,
             db,
+            slashing_protection_db,
+            slashing_protection_import,
             origin_chain,
             validator,
             checkpoint_syncer,
             reorg_period,
             interval,
+            self_audit_interval,
         })
     }
 }
@@ -224,6 +259,51 @@ fn parse_checkpoint_syncer(syncer: ValueParser) -> ConfigResult<CheckpointSyncer
                 user_secrets,
             })
         }
+        Some("fanOut") => {
+            let syncers = syncer
+                .get_key("syncers")
+                .and_then(ValueParser::into_array_iter)
+                .take_config_err(&mut err)
+                .map(|itr| {
+                    itr.filter_map(|s| parse_checkpoint_syncer(s).take_config_err(&mut err))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            if syncers.is_empty() {
+                Err::<(), _>(eyre!("Expected at least one checkpoint syncer"))
+                    .take_err(&mut err, || &syncer.cwp + "syncers");
+            }
+            if syncers
+                .iter()
+                .any(|s| matches!(s, CheckpointSyncerConf::FanOut { .. }))
+            {
+                Err::<(), _>(eyre!("Fan-out checkpoint syncers cannot be nested"))
+                    .take_err(&mut err, || &syncer.cwp + "syncers");
+            }
+
+            // Every checkpoint syncer must be written to unless a quorum is set
+            let write_policy = match syncer
+                .chain(&mut err)
+                .get_opt_key("writeQuorum")
+                .parse_u64()
+                .end()
+            {
+                Some(quorum) if quorum == 0 || quorum as usize > syncers.len() => {
+                    Err::<(), _>(eyre!(
+                        "Expected a write quorum between 1 and the number of checkpoint syncers"
+                    ))
+                    .take_err(&mut err, || &syncer.cwp + "write_quorum");
+                    FanOutWritePolicy::All
+                }
+                Some(quorum) => FanOutWritePolicy::Quorum(quorum as usize),
+                None => FanOutWritePolicy::All,
+            };
+
+            err.into_result(CheckpointSyncerConf::FanOut {
+                syncers,
+                write_policy,
+            })
+        }
         Some(_) => {
             Err(eyre!("Unknown checkpoint syncer type")).into_config_result(|| &syncer.cwp + "type")
         }
@@ -0,0 +1,326 @@
+//! Slashing protection for the checkpoints signed by the validator.
+//!
+//! Every checkpoint is recorded in a database kept apart from the validator's
+//! main database before it is signed, and a checkpoint is refused if a
+//! different one was already signed for the same origin domain, merkle tree
+//! hook and index. This guards against signing conflicting checkpoints after the main database
+//! is wiped or misconfigured. The database is locked while the validator runs,
+//! so two validators on the same host can't share it.
+//!
+//! The signed checkpoints can be exported and imported, e.g. to move the
+//! validator key to another host, in the following JSON interchange format:
+//!
+//! ```json
+//! {
+//!   "interchange_format_version": 1,
+//!   "validator": "0x...",
+//!   "signed_checkpoints": [
+//!     {
+//!       "checkpoint": {
+//!         "merkle_tree_hook_address": "0x...",
+//!         "mailbox_domain": 1,
+//!         "root": "0x...",
+//!         "index": 42,
+//!         "message_id": "0x..."
+//!       },
+//!       "signed_at": 1700000000
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! - `validator` is the address of the validator key the checkpoints were
+//!   signed with. Importing checkpoints of another validator is refused.
+//! - `signed_checkpoints` holds a single checkpoint per `mailbox_domain`,
+//!   merkle tree hook and index. `signed_at` is the unix timestamp (in seconds) of the signature.
+//!
+//! Importing fails without importing anything if any checkpoint conflicts with
+//! one that was already signed. Checkpoints that were already signed are
+//! skipped.
+
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use hyperlane_base::db::{DbError, HyperlaneRocksDB, SignedCheckpointRecord};
+use hyperlane_core::{CheckpointWithMessageId, H160};
+
+/// The version of the interchange format written by this validator
+pub(crate) const INTERCHANGE_FORMAT_VERSION: u32 = 1;
+
+/// The checkpoints signed by a validator, in the interchange format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SlashingProtectionInterchange {
+    pub interchange_format_version: u32,
+    pub validator: H160,
+    pub signed_checkpoints: Vec<SignedCheckpointRecord>,
+}
+
+/// Records the checkpoints signed by the validator and refuses to sign
+/// conflicting ones
+#[derive(Debug, Clone)]
+pub(crate) struct SlashingProtection {
+    db: HyperlaneRocksDB,
+    validator: H160,
+    /// Makes checking and recording a checkpoint atomic across the submitters
+    lock: Arc<Mutex<()>>,
+}
+
+impl SlashingProtection {
+    pub(crate) fn new(db: HyperlaneRocksDB, validator: H160) -> Self {
+        Self {
+            db,
+            validator,
+            lock: Default::default(),
+        }
+    }
+
+    /// Records that `checkpoint` is about to be signed. Fails if a different
+    /// checkpoint was already signed for the same origin domain, merkle tree
+    /// hook and index.
+    pub(crate) fn check_and_record(
+        &self,
+        checkpoint: &CheckpointWithMessageId,
+    ) -> Result<(), SlashingProtectionError> {
+        let _guard = self.lock.lock().unwrap_or_else(|err| err.into_inner());
+        match self.db.retrieve_signed_checkpoint_record(
+            checkpoint.mailbox_domain,
+            &checkpoint.merkle_tree_hook_address,
+            checkpoint.index,
+        )? {
+            Some(record) if record.checkpoint == *checkpoint => Ok(()),
+            Some(record) => Err(SlashingProtectionError::Conflict {
+                signed: Box::new(record.checkpoint),
+                attempted: Box::new(*checkpoint),
+            }),
+            None => {
+                self.db
+                    .store_signed_checkpoint_record(&SignedCheckpointRecord {
+                        checkpoint: *checkpoint,
+                        signed_at: chrono::Utc::now().timestamp() as u64,
+                    })?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Exports every checkpoint signed by the validator
+    pub(crate) fn export(&self) -> Result<SlashingProtectionInterchange, SlashingProtectionError> {
+        let mut signed_checkpoints = self.db.retrieve_signed_checkpoint_records()?;
+        signed_checkpoints.sort_by_key(|record| {
+            (
+                record.checkpoint.mailbox_domain,
+                record.checkpoint.merkle_tree_hook_address,
+                record.checkpoint.index,
+            )
+        });
+        Ok(SlashingProtectionInterchange {
+            interchange_format_version: INTERCHANGE_FORMAT_VERSION,
+            validator: self.validator,
+            signed_checkpoints,
+        })
+    }
+
+    /// Imports the checkpoints of `interchange` that weren't signed yet,
+    /// returning how many were imported
+    pub(crate) fn import(
+        &self,
+        interchange: &SlashingProtectionInterchange,
+    ) -> Result<usize, SlashingProtectionError> {
+        if interchange.interchange_format_version != INTERCHANGE_FORMAT_VERSION {
+            return Err(SlashingProtectionError::UnsupportedVersion(
+                interchange.interchange_format_version,
+            ));
+        }
+        if interchange.validator != self.validator {
+            return Err(SlashingProtectionError::WrongValidator {
+                expected: self.validator,
+                found: interchange.validator,
+            });
+        }
+
+        let _guard = self.lock.lock().unwrap_or_else(|err| err.into_inner());
+        // Check every checkpoint before importing any, so that a conflicting
+        // interchange leaves the database untouched
+        let mut new_records = HashMap::new();
+        for record in &interchange.signed_checkpoints {
+            let checkpoint = &record.checkpoint;
+            let key = (
+                checkpoint.mailbox_domain,
+                checkpoint.merkle_tree_hook_address,
+                checkpoint.index,
+            );
+            let existing = match new_records.get(&key) {
+                Some(new_record) => Some(new_record.checkpoint),
+                None => self
+                    .db
+                    .retrieve_signed_checkpoint_record(key.0, &key.1, key.2)?
+                    .map(|existing| existing.checkpoint),
+            };
+            match existing {
+                Some(existing) if existing == *checkpoint => {}
+                Some(existing) => {
+                    return Err(SlashingProtectionError::Conflict {
+                        signed: Box::new(existing),
+                        attempted: Box::new(*checkpoint),
+                    })
+                }
+                None => {
+                    new_records.insert(key, record);
+                }
+            }
+        }
+        for record in new_records.values() {
+            self.db.store_signed_checkpoint_record(record)?;
+        }
+        Ok(new_records.len())
+    }
+
+    /// Imports the interchange file at `path`
+    pub(crate) fn import_file(&self, path: &Path) -> Result<()> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Opening slashing protection interchange {path:?}"))?;
+        let interchange: SlashingProtectionInterchange =
+            serde_json::from_reader(std::io::BufReader::new(file))
+                .with_context(|| format!("Parsing slashing protection interchange {path:?}"))?;
+        let imported = self.import(&interchange)?;
+        let skipped = interchange.signed_checkpoints.len() - imported;
+        info!(
+            ?path,
+            imported,
+            skipped,
+            "Imported slashing protection interchange, skipping the checkpoints already signed"
+        );
+        Ok(())
+    }
+}
+
+/// Errors of the slashing protection
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum SlashingProtectionError {
+    /// A different checkpoint was already signed for the same index
+    #[error("Checkpoint {attempted:?} conflicts with the already signed {signed:?}")]
+    Conflict {
+        signed: Box<CheckpointWithMessageId>,
+        attempted: Box<CheckpointWithMessageId>,
+    },
+    /// The interchange is of another validator
+    #[error("Slashing protection interchange is for validator {found:?}, expected {expected:?}")]
+    WrongValidator { expected: H160, found: H160 },
+    /// The interchange format version isn't supported
+    #[error("Unsupported slashing protection interchange format version {0}")]
+    UnsupportedVersion(u32),
+    /// The slashing protection database failed
+    #[error(transparent)]
+    Db(#[from] DbError),
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_base::db::DB;
+    use hyperlane_core::{test_utils::dummy_domain, Checkpoint, H256};
+
+    use super::*;
+
+    fn checkpoint(index: u32, message_id: H256) -> CheckpointWithMessageId {
+        CheckpointWithMessageId {
+            checkpoint: Checkpoint {
+                merkle_tree_hook_address: H256::repeat_byte(1),
+                mailbox_domain: 1,
+                root: H256::repeat_byte(index as u8),
+                index,
+            },
+            message_id,
+        }
+    }
+
+    fn slashing_protection(dir: &tempfile::TempDir) -> SlashingProtection {
+        let db = DB::from_path(dir.path()).unwrap();
+        SlashingProtection::new(
+            HyperlaneRocksDB::new(&dummy_domain(1, "origin"), db),
+            H160::repeat_byte(9),
+        )
+    }
+
+    #[test]
+    fn test_refuses_conflicting_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let protection = slashing_protection(&dir);
+
+        let signed = checkpoint(5, H256::repeat_byte(2));
+        protection.check_and_record(&signed).unwrap();
+        // Signing the same checkpoint again is harmless
+        protection.check_and_record(&signed).unwrap();
+        assert!(matches!(
+            protection.check_and_record(&checkpoint(5, H256::repeat_byte(3))),
+            Err(SlashingProtectionError::Conflict { .. })
+        ));
+
+        let mut other_hook = checkpoint(5, H256::repeat_byte(3));
+        other_hook.checkpoint.merkle_tree_hook_address = H256::repeat_byte(4);
+        protection.check_and_record(&other_hook).unwrap();
+
+        // The same hook address on another origin is another merkle tree
+        let mut other_origin = checkpoint(5, H256::repeat_byte(3));
+        other_origin.checkpoint.mailbox_domain = 2;
+        protection.check_and_record(&other_origin).unwrap();
+        assert!(matches!(
+            protection.check_and_record(&checkpoint(5, H256::repeat_byte(3))),
+            Err(SlashingProtectionError::Conflict { .. })
+        ));
+    }
+
+    #[test]
+    fn test_export_import() {
+        let (from_dir, to_dir) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (from, to) = (slashing_protection(&from_dir), slashing_protection(&to_dir));
+        for index in [3, 1, 2] {
+            from.check_and_record(&checkpoint(index, H256::repeat_byte(2)))
+                .unwrap();
+        }
+        to.check_and_record(&checkpoint(1, H256::repeat_byte(2)))
+            .unwrap();
+
+        let interchange = from.export().unwrap();
+        let indexes = interchange
+            .signed_checkpoints
+            .iter()
+            .map(|record| record.checkpoint.index)
+            .collect::<Vec<_>>();
+        assert_eq!(indexes, vec![1, 2, 3]);
+        let json = serde_json::to_string(&interchange).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SlashingProtectionInterchange>(&json).unwrap(),
+            interchange
+        );
+
+        assert_eq!(to.import(&interchange).unwrap(), 2);
+        assert!(matches!(
+            to.check_and_record(&checkpoint(3, H256::repeat_byte(3))),
+            Err(SlashingProtectionError::Conflict { .. })
+        ));
+
+        // A conflicting interchange is rejected as a whole
+        let mut conflicting = interchange.clone();
+        conflicting.signed_checkpoints[2].checkpoint.message_id = H256::repeat_byte(3);
+        conflicting.signed_checkpoints.push(SignedCheckpointRecord {
+            checkpoint: checkpoint(4, H256::repeat_byte(2)),
+            signed_at: 0,
+        });
+        assert!(to.import(&conflicting).is_err());
+        assert_eq!(to.export().unwrap().signed_checkpoints.len(), 3);
+
+        let mut other_validator = interchange;
+        other_validator.validator = H160::repeat_byte(8);
+        assert!(matches!(
+            to.import(&other_validator),
+            Err(SlashingProtectionError::WrongValidator { .. })
+        ));
+    }
+}
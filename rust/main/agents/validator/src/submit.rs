@@ -13,9 +13,14 @@ use hyperlane_core::{
     accumulator::incremental::IncrementalMerkle, Checkpoint, CheckpointWithMessageId,
     HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneSignerExt,
 };
-use hyperlane_core::{ChainResult, MerkleTreeHook, ReorgEvent, ReorgPeriod};
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, MerkleTreeHook, ReorgEvent, ReorgPeriod,
+};
 use hyperlane_ethereum::SingletonSignerHandle;
 
+use crate::health::{ServiceHealth, ValidatorService};
+use crate::slashing_protection::{SlashingProtection, SlashingProtectionError};
+
 #[derive(Clone)]
 pub(crate) struct ValidatorSubmitter {
     interval: Duration,
@@ -23,17 +28,22 @@ pub(crate) struct ValidatorSubmitter {
     signer: SingletonSignerHandle,
     merkle_tree_hook: Arc<dyn MerkleTreeHook>,
     checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    slashing_protection: SlashingProtection,
+    service_health: ServiceHealth,
     db: Arc<dyn HyperlaneDb>,
     metrics: ValidatorSubmitterMetrics,
 }
 
 impl ValidatorSubmitter {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         interval: Duration,
         reorg_period: ReorgPeriod,
         merkle_tree_hook: Arc<dyn MerkleTreeHook>,
         signer: SingletonSignerHandle,
         checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+        slashing_protection: SlashingProtection,
+        service_health: ServiceHealth,
         db: Arc<dyn HyperlaneDb>,
         metrics: ValidatorSubmitterMetrics,
     ) -> Self {
@@ -43,6 +53,8 @@ impl ValidatorSubmitter {
             merkle_tree_hook,
             signer,
             checkpoint_syncer,
+            slashing_protection,
+            service_health,
             db,
             metrics,
         }
@@ -130,6 +142,8 @@ impl ValidatorSubmitter {
             self.metrics
                 .latest_checkpoint_processed
                 .set(latest_checkpoint.index as i64);
+            self.service_health
+                .record_progress(ValidatorService::Submitter);
 
             sleep(self.interval).await;
         }
@@ -253,17 +267,28 @@ impl ValidatorSubmitter {
         &self,
         checkpoint: CheckpointWithMessageId,
     ) -> ChainResult<()> {
+        // Checkpoints are written again to the storage locations that failed to
+        // be written to before
         let existing = self
             .checkpoint_syncer
-            .fetch_checkpoint(checkpoint.index)
+            .fetch_complete_checkpoint(checkpoint.index)
             .await?;
         if existing.is_some() {
             debug!(index = checkpoint.index, "Checkpoint already submitted");
             return Ok(());
         }
+        match self.slashing_protection.check_and_record(&checkpoint) {
+            Ok(()) => {}
+            // Signing a conflicting checkpoint may get the validator slashed, so
+            // stop instead of retrying
+            Err(err @ SlashingProtectionError::Conflict { .. }) => {
+                panic!("Refusing to sign checkpoint: {err}")
+            }
+            Err(err) => return Err(ChainCommunicationError::from_other(err)),
+        }
         let signed_checkpoint = self.signer.sign(checkpoint).await?;
         self.checkpoint_syncer
-            .write_checkpoint(&signed_checkpoint)
+            .write_missing_checkpoint(&signed_checkpoint)
             .await?;
         debug!(index = checkpoint.index, "Signed and submitted checkpoint");
 
@@ -340,7 +365,10 @@ mod test {
     use async_trait::async_trait;
     use eyre::Result;
     use hyperlane_base::{
-        db::{DbResult, HyperlaneDb, InterchainGasExpenditureData, InterchainGasPaymentData},
+        db::{
+            DbResult, DeadLetteredMessage, HyperlaneDb, InterchainGasExpenditureData,
+            InterchainGasPaymentData,
+        },
         AgentMetadata,
     };
     use hyperlane_core::{
@@ -464,6 +492,15 @@ mod test {
             ) -> DbResult<Option<u64>>;
             fn store_highest_seen_message_nonce_number(&self, nonce: &u32) -> DbResult<()>;
             fn retrieve_highest_seen_message_nonce_number(&self) -> DbResult<Option<u32>>;
+            fn store_dead_lettered_message_by_id(
+                &self,
+                message_id: &H256,
+                entry: &DeadLetteredMessage,
+            ) -> DbResult<()>;
+            fn retrieve_dead_lettered_message_by_id(
+                &self,
+                message_id: &H256,
+            ) -> DbResult<Option<DeadLetteredMessage>>;
 
         }
     }
@@ -527,6 +564,14 @@ mod test {
         SingletonSignerHandle::new(H160::from_low_u64_be(0), mpsc::unbounded_channel().0)
     }
 
+    fn dummy_slashing_protection(dir: &tempfile::TempDir) -> SlashingProtection {
+        let db = hyperlane_base::db::DB::from_path(dir.path()).unwrap();
+        SlashingProtection::new(
+            hyperlane_base::db::HyperlaneRocksDB::new(&dummy_domain(0, "dummy_domain"), db),
+            H160::from_low_u64_be(0),
+        )
+    }
+
     fn reorg_event_is_correct(
         reorg_event: &ReorgEvent,
         expected_local_merkle_tree: &IncrementalMerkle,
@@ -621,12 +666,15 @@ mod test {
             });
 
         // instantiate the validator submitter
+        let slashing_protection_dir = tempfile::tempdir().unwrap();
         let validator_submitter = ValidatorSubmitter::new(
             Duration::from_secs(1),
             ReorgPeriod::from_blocks(expected_reorg_period),
             Arc::new(mock_merkle_tree_hook),
             dummy_singleton_handle(),
             Arc::new(mock_checkpoint_syncer),
+            dummy_slashing_protection(&slashing_protection_dir),
+            ServiceHealth::default(),
             Arc::new(db),
             dummy_metrics(),
         );
@@ -648,4 +696,48 @@ mod test {
             )
             .await;
     }
+
+    #[tokio::test]
+    #[should_panic(expected = "Refusing to sign checkpoint")]
+    async fn conflicting_checkpoint_is_not_signed() {
+        let mut mock_checkpoint_syncer = MockCheckpointSyncer::new();
+        mock_checkpoint_syncer
+            .expect_fetch_checkpoint()
+            .returning(|_| Ok(None));
+
+        let slashing_protection_dir = tempfile::tempdir().unwrap();
+        let slashing_protection = dummy_slashing_protection(&slashing_protection_dir);
+        let signed = CheckpointWithMessageId {
+            checkpoint: Checkpoint {
+                root: H256::random(),
+                index: 7,
+                merkle_tree_hook_address: H256::from_low_u64_be(0),
+                mailbox_domain: 0,
+            },
+            message_id: H256::random(),
+        };
+        // e.g. signed by the validator before its main db was wiped
+        slashing_protection.check_and_record(&signed).unwrap();
+
+        let validator_submitter = ValidatorSubmitter::new(
+            Duration::from_secs(1),
+            ReorgPeriod::from_blocks(1),
+            Arc::new(MockMerkleTreeHook::new()),
+            dummy_singleton_handle(),
+            Arc::new(mock_checkpoint_syncer),
+            slashing_protection,
+            ServiceHealth::default(),
+            Arc::new(MockDb::new()),
+            dummy_metrics(),
+        );
+
+        let conflicting = CheckpointWithMessageId {
+            message_id: H256::random(),
+            ..signed
+        };
+        // the signer handle isn't running, so signing would fail instead of panicking
+        let _ = validator_submitter
+            .sign_and_submit_checkpoint(conflicting)
+            .await;
+    }
 }
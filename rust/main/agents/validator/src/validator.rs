@@ -6,6 +6,7 @@ use derive_more::AsRef;
 use eyre::Result;
 
 use futures_util::future::try_join_all;
+use prometheus::IntGauge;
 use tokio::{task::JoinHandle, time::sleep};
 use tracing::{error, info, info_span, instrument::Instrumented, warn, Instrument};
 
@@ -25,7 +26,10 @@ use hyperlane_core::{
 use hyperlane_ethereum::{SingletonSigner, SingletonSignerHandle};
 
 use crate::{
+    audit::{CheckpointAuditor, CheckpointAuditorMetrics},
+    health::{ServiceHealth, ValidatorService},
     settings::ValidatorSettings,
+    slashing_protection::SlashingProtection,
     submit::{ValidatorSubmitter, ValidatorSubmitterMetrics},
 };
 
@@ -38,6 +42,8 @@ pub struct Validator {
     core: HyperlaneAgentCore,
     db: HyperlaneRocksDB,
     merkle_tree_hook_sync: Arc<SequencedDataContractSync<MerkleTreeInsertion>>,
+    /// The block height indexed by the merkle tree hook sync
+    merkle_tree_hook_indexed_height: IntGauge,
     mailbox: Arc<dyn Mailbox>,
     merkle_tree_hook: Arc<dyn MerkleTreeHook>,
     validator_announce: Arc<dyn ValidatorAnnounce>,
@@ -46,7 +52,10 @@ pub struct Validator {
     signer_instance: Option<Box<SingletonSigner>>,
     reorg_period: ReorgPeriod,
     interval: Duration,
+    self_audit_interval: Option<Duration>,
     checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    slashing_protection: SlashingProtection,
+    service_health: ServiceHealth,
     core_metrics: Arc<CoreMetrics>,
     agent_metrics: AgentMetrics,
     chain_metrics: ChainMetrics,
@@ -76,6 +85,17 @@ impl BaseAgent for Validator {
         // Intentionally using hyperlane_ethereum for the validator's signer
         let (signer_instance, signer) = SingletonSigner::new(settings.validator.build().await?);
 
+        let slashing_protection = SlashingProtection::new(
+            HyperlaneRocksDB::new(
+                &settings.origin_chain,
+                DB::from_path(&settings.slashing_protection_db)?,
+            ),
+            signer.eth_address(),
+        );
+        if let Some(path) = &settings.slashing_protection_import {
+            slashing_protection.import_file(path)?;
+        }
+
         let core = settings.build_hyperlane_core(metrics.clone());
         let checkpoint_syncer = settings
             .checkpoint_syncer
@@ -103,6 +123,9 @@ impl BaseAgent for Validator {
 
         let contract_sync_metrics = Arc::new(ContractSyncMetrics::new(&metrics));
 
+        let merkle_tree_hook_indexed_height = contract_sync_metrics
+            .indexed_height
+            .with_label_values(&["merkle_tree_hook", settings.origin_chain.name()]);
         let merkle_tree_hook_sync = settings
             .sequenced_contract_sync::<MerkleTreeInsertion, _>(
                 &settings.origin_chain,
@@ -121,12 +144,16 @@ impl BaseAgent for Validator {
             mailbox: mailbox.into(),
             merkle_tree_hook: merkle_tree_hook.into(),
             merkle_tree_hook_sync,
+            merkle_tree_hook_indexed_height,
             validator_announce: validator_announce.into(),
             signer,
             signer_instance: Some(Box::new(signer_instance)),
             reorg_period: settings.reorg_period,
             interval: settings.interval,
+            self_audit_interval: settings.self_audit_interval,
             checkpoint_syncer,
+            slashing_protection,
+            service_health: ServiceHealth::default(),
             agent_metrics,
             chain_metrics,
             core_metrics: metrics,
@@ -139,8 +166,12 @@ impl BaseAgent for Validator {
         let mut tasks = vec![];
 
         // run server
-        let custom_routes =
-            validator_server::routes(self.origin_chain.clone(), self.core.metrics.clone());
+        let custom_routes = validator_server::routes(
+            self.origin_chain.clone(),
+            self.core.metrics.clone(),
+            self.slashing_protection.clone(),
+            self.service_health.clone(),
+        );
         let server = self
             .core
             .settings
@@ -198,6 +229,9 @@ impl BaseAgent for Validator {
                     for checkpoint_sync_task in self.run_checkpoint_submitters().await {
                         tasks.push(checkpoint_sync_task);
                     }
+                    if let Some(self_audit_interval) = self.self_audit_interval {
+                        tasks.push(self.run_checkpoint_auditor(self_audit_interval));
+                    }
                     break;
                 }
                 _ => {
@@ -228,13 +262,24 @@ impl Validator {
                     self.origin_chain
                 )
             });
-        tokio::spawn(async move {
-            contract_sync
-                .clone()
-                .sync("merkle_tree_hook", cursor.into())
-                .await;
-        })
-        .instrument(info_span!("MerkleTreeHookSyncer"))
+        let service_health = self.service_health.clone();
+        let indexed_height = self.merkle_tree_hook_indexed_height.clone();
+        let interval = self.interval;
+        self.service_health
+            .spawn(ValidatorService::Indexer, async move {
+                // The sync makes progress whenever it indexes further blocks
+                tokio::join!(
+                    contract_sync
+                        .clone()
+                        .sync("merkle_tree_hook", cursor.into()),
+                    service_health.record_gauge_progress(
+                        ValidatorService::Indexer,
+                        indexed_height,
+                        interval
+                    ),
+                );
+            })
+            .instrument(info_span!("MerkleTreeHookSyncer"))
     }
 
     async fn run_checkpoint_submitters(&self) -> Vec<Instrumented<JoinHandle<()>>> {
@@ -244,6 +289,8 @@ impl Validator {
             self.merkle_tree_hook.clone(),
             self.signer.clone(),
             self.checkpoint_syncer.clone(),
+            self.slashing_protection.clone(),
+            self.service_health.clone(),
             Arc::new(self.db.clone()) as Arc<dyn HyperlaneDb>,
             ValidatorSubmitterMetrics::new(&self.core.metrics, &self.origin_chain),
         );
@@ -263,22 +310,39 @@ impl Validator {
 
         let mut tasks = vec![];
         tasks.push(
-            tokio::spawn(async move {
-                backfill_submitter
-                    .backfill_checkpoint_submitter(backfill_target)
-                    .await
-            })
-            .instrument(info_span!("BackfillCheckpointSubmitter")),
+            self.service_health
+                .spawn(ValidatorService::BackfillSubmitter, async move {
+                    backfill_submitter
+                        .backfill_checkpoint_submitter(backfill_target)
+                        .await
+                })
+                .instrument(info_span!("BackfillCheckpointSubmitter")),
         );
 
         tasks.push(
-            tokio::spawn(async move { submitter.checkpoint_submitter(tip_tree).await })
+            self.service_health
+                .spawn(ValidatorService::Submitter, async move {
+                    submitter.checkpoint_submitter(tip_tree).await
+                })
                 .instrument(info_span!("TipCheckpointSubmitter")),
         );
 
         tasks
     }
 
+    fn run_checkpoint_auditor(&self, interval: Duration) -> Instrumented<JoinHandle<()>> {
+        let auditor = CheckpointAuditor::new(
+            interval,
+            self.signer.eth_address(),
+            self.merkle_tree_hook.address(),
+            self.merkle_tree_hook.domain().id(),
+            self.checkpoint_syncer.clone(),
+            self.db.clone(),
+            CheckpointAuditorMetrics::new(&self.core.metrics, &self.origin_chain),
+        );
+        tokio::spawn(async move { auditor.run().await }).instrument(info_span!("CheckpointAuditor"))
+    }
+
     fn log_on_announce_failure(result: ChainResult<TxOutcome>, chain_signer: &String) {
         match result {
             Ok(outcome) => {
@@ -318,19 +382,23 @@ impl Validator {
 
     async fn announce(&self) -> Result<()> {
         let address = self.signer.eth_address();
-        let announcement_location = self.checkpoint_syncer.announcement_location();
-
-        // Sign and post the validator announcement
-        let announcement = Announcement {
-            validator: address,
-            mailbox_address: self.mailbox.address(),
-            mailbox_domain: self.mailbox.domain().id(),
-            storage_location: announcement_location.clone(),
-        };
-        let signed_announcement = self.signer.sign(announcement.clone()).await?;
-        self.checkpoint_syncer
-            .write_announcement(&signed_announcement)
-            .await?;
+
+        // Sign and post an announcement for every storage location, which is
+        // more than one for fan-out checkpoint syncers
+        let mut signed_announcements = vec![];
+        for storage_location in self.checkpoint_syncer.announcement_locations() {
+            let announcement = Announcement {
+                validator: address,
+                mailbox_address: self.mailbox.address(),
+                mailbox_domain: self.mailbox.domain().id(),
+                storage_location,
+            };
+            let signed_announcement = self.signer.sign(announcement).await?;
+            self.checkpoint_syncer
+                .write_announcement(&signed_announcement)
+                .await?;
+            signed_announcements.push(signed_announcement);
+        }
 
         // Ensure that the validator has announced themselves before we enter
         // the main validator submit loop. This is to avoid a situation in
@@ -345,17 +413,21 @@ impl Validator {
                 .await?
                 .first()
             {
-                if locations.contains(&announcement_location) {
+                let unannounced = signed_announcements
+                    .iter()
+                    .filter(|a| !locations.contains(&a.value.storage_location))
+                    .collect::<Vec<_>>();
+                if unannounced.is_empty() {
                     info!(
                         ?locations,
-                        ?announcement_location,
-                        "Validator has announced signature storage location"
+                        "Validator has announced signature storage locations"
                     );
                     break;
                 }
                 info!(
                     announced_locations=?locations,
-                    "Validator has not announced signature storage location"
+                    unannounced_locations=?unannounced.iter().map(|a| &a.value.storage_location).collect::<Vec<_>>(),
+                    "Validator has not announced signature storage locations"
                 );
 
                 if let Some(chain_signer) = self.core.settings.chains[self.origin_chain.name()]
@@ -363,20 +435,22 @@ impl Validator {
                     .await?
                 {
                     let chain_signer = chain_signer.address_string();
-                    info!(eth_validator_address=?announcement.validator, ?chain_signer, "Attempting self announce");
-                    let balance_delta = self
-                        .validator_announce
-                        .announce_tokens_needed(signed_announcement.clone())
-                        .await
-                        .unwrap_or_default();
-                    if balance_delta > U256::zero() {
-                        warn!(
-                            tokens_needed=%balance_delta,
-                            eth_validator_address=?announcement.validator,
-                            ?chain_signer,
-                            "Please send tokens to your chain signer address to announce",
-                        );
-                    } else {
+                    for signed_announcement in unannounced {
+                        info!(eth_validator_address=?signed_announcement.value.validator, storage_location=?signed_announcement.value.storage_location, ?chain_signer, "Attempting self announce");
+                        let balance_delta = self
+                            .validator_announce
+                            .announce_tokens_needed(signed_announcement.clone())
+                            .await
+                            .unwrap_or_default();
+                        if balance_delta > U256::zero() {
+                            warn!(
+                                tokens_needed=%balance_delta,
+                                eth_validator_address=?signed_announcement.value.validator,
+                                ?chain_signer,
+                                "Please send tokens to your chain signer address to announce",
+                            );
+                            break;
+                        }
                         let result = self
                             .validator_announce
                             .announce(signed_announcement.clone())
@@ -63,6 +63,7 @@ fn provider(address: &str) -> WasmGrpcProvider {
             OperationBatchConfig {
                 batch_contract_address: None,
                 max_batch_size: 1,
+                max_batch_gas: None,
             },
             NativeToken {
                 decimals: 6,
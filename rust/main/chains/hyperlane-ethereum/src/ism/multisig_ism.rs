@@ -10,12 +10,13 @@ use tracing::instrument;
 
 use hyperlane_core::{
     ChainResult, ContractLocator, HyperlaneAbi, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
-    HyperlaneMessage, HyperlaneProvider, MultisigIsm, RawHyperlaneMessage, H256,
+    HyperlaneMessage, HyperlaneProvider, MultisigIsm, RawHyperlaneMessage, WeightedValidator, H256,
 };
 
 use crate::interfaces::i_multisig_ism::{
     IMultisigIsm as EthereumMultisigIsmInternal, IMULTISIGISM_ABI,
 };
+use crate::interfaces::i_static_weighted_multisig_ism::IStaticWeightedMultisigIsm as EthereumWeightedMultisigIsmInternal;
 use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider};
 
 impl<M> std::fmt::Display for EthereumMultisigIsmInternal<M>
@@ -51,6 +52,8 @@ where
     M: Middleware,
 {
     contract: Arc<EthereumMultisigIsmInternal<M>>,
+    /// The same contract, queried through the interface of weighted multisig ISMs
+    weighted_contract: Arc<EthereumWeightedMultisigIsmInternal<M>>,
     domain: HyperlaneDomain,
 }
 
@@ -62,7 +65,14 @@ where
     /// chain
     pub fn new(provider: Arc<M>, locator: &ContractLocator) -> Self {
         Self {
-            contract: Arc::new(EthereumMultisigIsmInternal::new(locator.address, provider)),
+            contract: Arc::new(EthereumMultisigIsmInternal::new(
+                locator.address,
+                provider.clone(),
+            )),
+            weighted_contract: Arc::new(EthereumWeightedMultisigIsmInternal::new(
+                locator.address,
+                provider,
+            )),
             domain: locator.domain.clone(),
         }
     }
@@ -112,6 +122,28 @@ where
         let validators: Vec<H256> = validator_addresses.iter().map(|&x| H256::from(x)).collect();
         Ok((validators, threshold))
     }
+
+    /// Only supported by weighted multisig ISMs
+    #[instrument(err)]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn validators_and_threshold_weight(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<WeightedValidator>, u128)> {
+        let (validator_infos, threshold_weight) = self
+            .weighted_contract
+            .validators_and_threshold_weight(RawHyperlaneMessage::from(message).to_vec().into())
+            .call()
+            .await?;
+        let validators = validator_infos
+            .into_iter()
+            .map(|info| WeightedValidator {
+                validator: info.signing_address.into(),
+                weight: info.weight,
+            })
+            .collect();
+        Ok((validators, threshold_weight))
+    }
 }
 
 pub struct EthereumMultisigIsmAbi;
@@ -0,0 +1,445 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use ethers::prelude::{Address, Signature};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip712::Eip712;
+use ethers::utils::{hex, keccak256};
+use ethers_signers::Signer;
+use reqwest::{Client, StatusCode, Url};
+use tokio::time::sleep;
+use tracing::{instrument, warn};
+
+/// Delay before the first retry of a failed signing request, doubled for
+/// every following retry
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+/// Caps the retry delay at `RETRY_BASE_DELAY * 2^MAX_BACKOFF_EXPONENT`
+const MAX_BACKOFF_EXPONENT: u32 = 5;
+
+/// A signer that signs with a key held by an external signing service
+/// implementing the web3signer eth1 API, so that the key never lives in the
+/// agent process.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    client: Client,
+    /// The `/api/v1/eth1/sign/{identifier}` endpoint of the key
+    sign_url: Url,
+    address: Address,
+    chain_id: u64,
+    /// How many times a request that failed because of the connection or the
+    /// service is retried
+    max_retries: u32,
+}
+
+impl RemoteSigner {
+    /// Create a remote signer for the key of `address`, which the signing
+    /// service at `url` knows as `identifier`. Timeouts and TLS are
+    /// configured on the `client`.
+    pub fn new(
+        client: Client,
+        url: &Url,
+        identifier: &str,
+        address: Address,
+        max_retries: u32,
+    ) -> Result<Self, RemoteSignerError> {
+        let sign_url = url
+            .join(&format!("api/v1/eth1/sign/{identifier}"))
+            .map_err(|err| RemoteSignerError::InvalidUrl(err.to_string()))?;
+        Ok(Self {
+            client,
+            sign_url,
+            address,
+            chain_id: 1,
+            max_retries,
+        })
+    }
+
+    /// The identity to authenticate to the signing service with, from the PEM
+    /// client certificate and its PEM private key
+    pub fn client_identity(
+        cert_pem: &[u8],
+        key_pem: &[u8],
+    ) -> Result<reqwest::Identity, RemoteSignerError> {
+        // The certificate file may not end with a newline, which would merge
+        // its last line with the first line of the key
+        let mut pem = Vec::with_capacity(cert_pem.len() + key_pem.len() + 1);
+        pem.extend_from_slice(cert_pem);
+        pem.push(b'\n');
+        pem.extend_from_slice(key_pem);
+        Ok(reqwest::Identity::from_pem(&pem)?)
+    }
+
+    /// Has the service sign the keccak256 hash of `data`, and checks that the
+    /// signature is from the expected key
+    #[instrument(skip(self, data), fields(url = %self.sign_url))]
+    async fn sign_data(&self, data: &[u8]) -> Result<Signature, RemoteSignerError> {
+        let body = serde_json::json!({ "data": format!("0x{}", hex::encode(data)) });
+        let mut attempt = 0;
+        let response = loop {
+            let result = self
+                .client
+                .post(self.sign_url.clone())
+                .json(&body)
+                .send()
+                .await;
+            let retriable = match &result {
+                Ok(response) => response.status().is_server_error(),
+                Err(err) => err.is_timeout() || err.is_connect(),
+            };
+            if !retriable || attempt >= self.max_retries {
+                break result?;
+            }
+            let delay = RETRY_BASE_DELAY * 2u32.pow(attempt.min(MAX_BACKOFF_EXPONENT));
+            warn!(attempt, ?delay, result = ?result.map(|r| r.status()), "Remote signing request failed, retrying");
+            sleep(delay).await;
+            attempt += 1;
+        };
+
+        let status = response.status();
+        let text = response.text().await?;
+        if status != StatusCode::OK {
+            return Err(RemoteSignerError::Status(status, text));
+        }
+        // Web3signer answers with the hex signature as plain text, but tolerate
+        // it being a JSON string
+        let text = text.trim().trim_matches('"');
+        let bytes = hex::decode(text.trim_start_matches("0x"))
+            .map_err(|_| RemoteSignerError::InvalidSignature(text.to_owned()))?;
+        let mut signature = Signature::try_from(bytes.as_slice())
+            .map_err(|_| RemoteSignerError::InvalidSignature(text.to_owned()))?;
+        if signature.v < 27 {
+            signature.v += 27;
+        }
+
+        let recovered = signature
+            .recover(ethers::types::H256::from(keccak256(data)))
+            .map_err(|_| RemoteSignerError::InvalidSignature(text.to_owned()))?;
+        if recovered != self.address {
+            return Err(RemoteSignerError::UnexpectedSigner {
+                expected: self.address,
+                recovered,
+            });
+        }
+        Ok(signature)
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    type Error = RemoteSignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        // EIP-191 personal message, as signed by the other signers
+        let message = message.as_ref();
+        let mut data = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+        data.extend_from_slice(message);
+        self.sign_data(&data).await
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        let mut tx = tx.clone();
+        let chain_id = tx.chain_id().map(|id| id.as_u64()).unwrap_or(self.chain_id);
+        tx.set_chain_id(chain_id);
+        let mut signature = self.sign_data(&tx.rlp()).await?;
+        // EIP-155 replay protection
+        signature.v = (signature.v - 27) + 35 + chain_id * 2;
+        Ok(signature)
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        _payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        Err(RemoteSignerError::Unsupported("typed data"))
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+/// Error types for the remote signer
+#[derive(Debug, thiserror::Error)]
+pub enum RemoteSignerError {
+    /// The url of the signing service is invalid
+    #[error("Invalid remote signer url: {0}")]
+    InvalidUrl(String),
+    /// The request to the signing service failed
+    #[error("Remote signer request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    /// The signing service refused to sign
+    #[error("Remote signer responded with status {0}: {1}")]
+    Status(StatusCode, String),
+    /// The signing service responded with something that isn't a signature
+    #[error("Invalid signature from remote signer: {0}")]
+    InvalidSignature(String),
+    /// The signing service signed with another key than expected
+    #[error("Remote signer signed with {recovered:?} instead of {expected:?}")]
+    UnexpectedSigner {
+        /// The address the signer was configured with
+        expected: Address,
+        /// The address recovered from the signature
+        recovered: Address,
+    },
+    /// The remote signer can't sign this kind of payload
+    #[error("Remote signer does not support signing {0}")]
+    Unsupported(&'static str),
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    use ethers::signers::LocalWallet;
+    use ethers::types::transaction::eip2930::AccessList;
+    use ethers::types::{
+        Eip1559TransactionRequest, Eip2930TransactionRequest, TransactionRequest, H256,
+    };
+
+    use super::*;
+
+    /// Reads a sign request from `stream`, and responds with a server error if
+    /// `fail` or else with the signature of `wallet`
+    fn serve_sign_request(
+        stream: impl Read + Write,
+        wallet: &LocalWallet,
+        fail: bool,
+    ) -> std::io::Result<()> {
+        let mut reader = BufReader::new(stream);
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line)?;
+            let line = line.trim_end().to_ascii_lowercase();
+            if line.is_empty() {
+                break;
+            }
+            if let Some(len) = line.strip_prefix("content-length:") {
+                content_length = len.trim().parse().unwrap();
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+
+        let (status, response) = if fail {
+            ("500 Internal Server Error", String::new())
+        } else {
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            let data =
+                hex::decode(body["data"].as_str().unwrap().trim_start_matches("0x")).unwrap();
+            let signature = wallet.sign_hash(H256::from(keccak256(data)));
+            ("200 OK", format!("0x{}", hex::encode(signature.to_vec())))
+        };
+        let stream = reader.get_mut();
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+            response.len()
+        )?;
+        stream.flush()
+    }
+
+    /// Serves web3signer sign requests with `wallet`, after responding with a
+    /// server error to the first `failures` requests
+    fn mock_signing_service(wallet: LocalWallet, failures: u32) -> (Url, Arc<AtomicU32>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        let requests = Arc::new(AtomicU32::new(0));
+        let counter = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let fail = counter.fetch_add(1, Ordering::SeqCst) < failures;
+                serve_sign_request(stream.unwrap(), &wallet, fail).unwrap();
+            }
+        });
+        (url, requests)
+    }
+
+    /// A certificate for `name`, which can issue other certificates if `is_ca`
+    fn certificate(name: &str, is_ca: bool) -> rcgen::Certificate {
+        let mut params = rcgen::CertificateParams::new(vec![name.to_owned()]);
+        params
+            .distinguished_name
+            .push(rcgen::DnType::CommonName, name);
+        if is_ca {
+            params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+        }
+        rcgen::Certificate::from_params(params).unwrap()
+    }
+
+    /// Serves web3signer sign requests with `wallet` over TLS on `localhost`,
+    /// only to clients presenting a certificate issued by `ca`
+    fn mock_tls_signing_service(wallet: LocalWallet, ca: &rcgen::Certificate) -> Url {
+        let server = certificate("localhost", false);
+        let mut client_roots = rustls::RootCertStore::empty();
+        client_roots
+            .add(&rustls::Certificate(ca.serialize_der().unwrap()))
+            .unwrap();
+        let config = rustls::ServerConfig::builder()
+            .with_safe_defaults()
+            .with_client_cert_verifier(
+                rustls::server::AllowAnyAuthenticatedClient::new(client_roots).boxed(),
+            )
+            .with_single_cert(
+                vec![rustls::Certificate(
+                    server.serialize_der_with_signer(ca).unwrap(),
+                )],
+                rustls::PrivateKey(server.serialize_private_key_der()),
+            )
+            .unwrap();
+        let config = Arc::new(config);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let connection = rustls::ServerConnection::new(config.clone()).unwrap();
+                let stream = rustls::StreamOwned::new(connection, stream.unwrap());
+                // Fails when the client doesn't authenticate
+                let _ = serve_sign_request(stream, &wallet, false);
+            }
+        });
+        Url::parse(&format!("https://localhost:{port}/")).unwrap()
+    }
+
+    fn wallet(key: &str) -> LocalWallet {
+        key.repeat(64).parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_signs_like_local_wallet() {
+        let wallet = wallet("1");
+        let (url, requests) = mock_signing_service(wallet.clone(), 2);
+        let signer =
+            RemoteSigner::new(Client::new(), &url, "validator", wallet.address(), 2).unwrap();
+
+        let message = H256::repeat_byte(7);
+        let signature = signer.sign_message(message).await.unwrap();
+        assert_eq!(signature, wallet.sign_message(message).await.unwrap());
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        let tx = TypedTransaction::Legacy(Default::default());
+        let signer = signer.with_chain_id(5u64);
+        assert_eq!(
+            signer.sign_transaction(&tx).await.unwrap(),
+            wallet
+                .with_chain_id(5u64)
+                .sign_transaction(&tx)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_signs_typed_transactions_like_local_wallet() {
+        let wallet = wallet("1");
+        let (url, _) = mock_signing_service(wallet.clone(), 0);
+        let signer = RemoteSigner::new(Client::new(), &url, "validator", wallet.address(), 0)
+            .unwrap()
+            .with_chain_id(5u64);
+        let wallet = wallet.with_chain_id(5u64);
+
+        let txs = [
+            TypedTransaction::Eip2930(Eip2930TransactionRequest::new(
+                TransactionRequest::new()
+                    .to(Address::repeat_byte(2))
+                    .value(100)
+                    .nonce(3)
+                    .gas(21_000)
+                    .gas_price(10),
+                AccessList::default(),
+            )),
+            TypedTransaction::Eip1559(
+                Eip1559TransactionRequest::new()
+                    .to(Address::repeat_byte(2))
+                    .value(100)
+                    .nonce(3)
+                    .gas(21_000)
+                    .max_fee_per_gas(10)
+                    .max_priority_fee_per_gas(2),
+            ),
+        ];
+        for tx in txs {
+            let signature = signer.sign_transaction(&tx).await.unwrap();
+            assert_eq!(signature, wallet.sign_transaction(&tx).await.unwrap());
+            // The signature is over the type prefixed hash
+            assert_eq!(signature.recover(tx.sighash()).unwrap(), wallet.address());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_signs_over_tls_with_client_certificate() {
+        let ca = certificate("signer-ca", true);
+        let wallet = wallet("1");
+        let url = mock_tls_signing_service(wallet.clone(), &ca);
+        let ca_cert =
+            reqwest::Certificate::from_pem(ca.serialize_pem().unwrap().as_bytes()).unwrap();
+
+        let client_cert = certificate("validator", false);
+        // Certificate files don't always end with a newline
+        let cert_pem = client_cert.serialize_pem_with_signer(&ca).unwrap();
+        let identity = RemoteSigner::client_identity(
+            cert_pem.trim_end().as_bytes(),
+            client_cert.serialize_private_key_pem().as_bytes(),
+        )
+        .unwrap();
+        let client = Client::builder()
+            .use_rustls_tls()
+            .add_root_certificate(ca_cert.clone())
+            .identity(identity)
+            .build()
+            .unwrap();
+        let signer = RemoteSigner::new(client, &url, "validator", wallet.address(), 0).unwrap();
+        let message = H256::repeat_byte(7);
+        assert_eq!(
+            signer.sign_message(message).await.unwrap(),
+            wallet.sign_message(message).await.unwrap()
+        );
+
+        // Clients without a certificate are refused
+        let client = Client::builder()
+            .use_rustls_tls()
+            .add_root_certificate(ca_cert)
+            .build()
+            .unwrap();
+        let signer = RemoteSigner::new(client, &url, "validator", wallet.address(), 0).unwrap();
+        assert!(matches!(
+            signer.sign_message(message).await,
+            Err(RemoteSignerError::Request(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_failures_and_other_keys() {
+        let (url, _) = mock_signing_service(wallet("1"), 2);
+        let signer =
+            RemoteSigner::new(Client::new(), &url, "validator", wallet("2").address(), 1).unwrap();
+
+        assert!(matches!(
+            signer.sign_message("hello").await,
+            Err(RemoteSignerError::Status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                _
+            ))
+        ));
+        assert!(matches!(
+            signer.sign_message("hello").await,
+            Err(RemoteSignerError::UnexpectedSigner { .. })
+        ));
+    }
+}
@@ -1,7 +1,8 @@
 use async_trait::async_trait;
 use hyperlane_core::{
     ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
-    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, MultisigIsm, RawHyperlaneMessage, H256,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, MultisigIsm, RawHyperlaneMessage,
+    WeightedValidator, H256,
 };
 use hyperlane_sealevel_multisig_ism_message_id::instruction::ValidatorsAndThreshold;
 use serializable_account_meta::SimulationReturnData;
@@ -13,8 +14,9 @@ use solana_sdk::{
 
 use crate::{ConnectionConf, SealevelProvider, SealevelRpcClient};
 
-use multisig_ism::interface::{
-    MultisigIsmInstruction, VALIDATORS_AND_THRESHOLD_ACCOUNT_METAS_PDA_SEEDS,
+use multisig_ism::{
+    interface::{MultisigIsmInstruction, VALIDATORS_AND_THRESHOLD_ACCOUNT_METAS_PDA_SEEDS},
+    weighted::ValidatorsAndThresholdWeight,
 };
 
 /// A reference to a MultisigIsm contract on some Sealevel chain
@@ -106,6 +108,57 @@ impl MultisigIsm for SealevelMultisigIsm {
 
         Ok((validators, validators_and_threshold.threshold))
     }
+
+    /// Returns the weighted validators and threshold weight needed to verify
+    /// message. Only supported by weighted multisig ISMs.
+    async fn validators_and_threshold_weight(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<WeightedValidator>, u128)> {
+        let message_bytes = RawHyperlaneMessage::from(message).to_vec();
+
+        let account_metas = self
+            .get_validators_and_threshold_account_metas(message_bytes.clone())
+            .await?;
+
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            &MultisigIsmInstruction::ValidatorsAndThresholdWeight(message_bytes)
+                .encode()
+                .map_err(ChainCommunicationError::from_other)?[..],
+            account_metas,
+        );
+
+        let validators_and_threshold_weight = self
+            .rpc()
+            .simulate_instruction::<SimulationReturnData<ValidatorsAndThresholdWeight>>(
+                self.payer
+                    .as_ref()
+                    .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?,
+                instruction,
+            )
+            .await?
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str(
+                    "No return data was returned from the weighted multisig ism",
+                )
+            })?
+            .return_data;
+
+        let validators = validators_and_threshold_weight
+            .validators
+            .into_iter()
+            .map(|v| WeightedValidator {
+                validator: v.validator.into(),
+                weight: v.weight.into(),
+            })
+            .collect();
+
+        Ok((
+            validators,
+            validators_and_threshold_weight.threshold_weight.into(),
+        ))
+    }
 }
 
 impl SealevelMultisigIsm {
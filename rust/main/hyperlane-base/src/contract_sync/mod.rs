@@ -110,6 +110,34 @@ where
         }
     }
 
+    /// Fetch the logs emitted by a transaction and store them, regardless of
+    /// the position of the cursor. Like the logs found by the cursor, the
+    /// transaction id is broadcast to the other indexing tasks.
+    #[instrument(fields(domain=self.domain().name()), skip(self))]
+    pub async fn index_tx(&self, label: &'static str, tx_id: H512) -> Result<Vec<T>> {
+        let stored_logs_metric = self
+            .metrics
+            .stored_events
+            .with_label_values(&[label, self.domain.as_ref()]);
+        let logs = self.indexer.fetch_logs_by_tx_hash(tx_id).await?;
+        let logs = self.dedupe_and_store_logs(logs, &stored_logs_metric).await;
+        info!(
+            num_logs = logs.len(),
+            ?tx_id,
+            sequences = ?logs.iter().map(|(log, _)| log.sequence).collect::<Vec<_>>(),
+            "Force indexed log(s) of tx id"
+        );
+        if let Some(tx) = self.broadcast_sender.as_ref() {
+            if let Err(err) = tx.send(tx_id).await {
+                trace!(?err, "Error sending txid to receiver");
+            }
+        }
+        Ok(logs
+            .into_iter()
+            .map(|(log, _)| log.inner().clone())
+            .collect())
+    }
+
     #[instrument(fields(domain=self.domain().name()), skip(self, recv, stored_logs_metric))]
     async fn fetch_logs_from_receiver(
         &self,
@@ -272,6 +300,10 @@ pub trait ContractSyncer<T>: Send + Sync {
 
     /// If this syncer is also a broadcaster, return the channel to receive txids
     fn get_broadcaster(&self) -> Option<BroadcastMpscSender<H512>>;
+
+    /// Fetches and stores the events emitted by a transaction, regardless of
+    /// the cursor position, and returns them
+    async fn index_tx(&self, label: &'static str, tx_id: H512) -> Result<Vec<T>>;
 }
 
 #[derive(new)]
@@ -333,6 +365,10 @@ where
     fn get_broadcaster(&self) -> Option<BroadcastMpscSender<H512>> {
         ContractSync::get_broadcaster(self)
     }
+
+    async fn index_tx(&self, label: &'static str, tx_id: H512) -> Result<Vec<T>> {
+        ContractSync::index_tx(self, label, tx_id).await
+    }
 }
 
 /// Log store for sequence aware cursors
@@ -376,4 +412,8 @@ where
     fn get_broadcaster(&self) -> Option<BroadcastMpscSender<H512>> {
         ContractSync::get_broadcaster(self)
     }
+
+    async fn index_tx(&self, label: &'static str, tx_id: H512) -> Result<Vec<T>> {
+        ContractSync::index_tx(self, label, tx_id).await
+    }
 }
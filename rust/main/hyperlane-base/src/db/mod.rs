@@ -5,7 +5,11 @@ use hyperlane_core::{
 };
 pub use rocks::*;
 
-pub use self::storage_types::{InterchainGasExpenditureData, InterchainGasPaymentData};
+pub use self::storage_types::{
+    DeadLetteredMessage, InterchainGasExpenditureData, InterchainGasPaymentData,
+    ProfitabilityLedgerEntry, QueuedMessageSnapshot, RateLimitBucket, SignedCheckpointRecord,
+    SubmittedTx,
+};
 
 mod error;
 mod rocks;
@@ -159,4 +163,17 @@ pub trait HyperlaneDb: Send + Sync {
 
     /// Retrieve the nonce of the highest processed message we're aware of
     fn retrieve_highest_seen_message_nonce_number(&self) -> DbResult<Option<u32>>;
+
+    /// Store a dead-lettered message by its message id
+    fn store_dead_lettered_message_by_id(
+        &self,
+        message_id: &H256,
+        entry: &DeadLetteredMessage,
+    ) -> DbResult<()>;
+
+    /// Retrieve a dead-lettered message by its message id
+    fn retrieve_dead_lettered_message_by_id(
+        &self,
+        message_id: &H256,
+    ) -> DbResult<Option<DeadLetteredMessage>>;
 }
@@ -11,7 +11,10 @@ use hyperlane_core::{
 
 use super::{DbError, TypedDB, DB};
 use crate::db::{
-    storage_types::{InterchainGasExpenditureData, InterchainGasPaymentData},
+    storage_types::{
+        DeadLetteredMessage, InterchainGasExpenditureData, InterchainGasPaymentData,
+        ProfitabilityLedgerEntry, QueuedMessageSnapshot, RateLimitBucket, SignedCheckpointRecord,
+    },
     HyperlaneDb,
 };
 
@@ -36,6 +39,14 @@ const MERKLE_LEAF_INDEX_BY_MESSAGE_ID: &str = "merkle_leaf_index_by_message_id_"
 const MERKLE_TREE_INSERTION_BLOCK_NUMBER_BY_LEAF_INDEX: &str =
     "merkle_tree_insertion_block_number_by_leaf_index_";
 const LATEST_INDEXED_GAS_PAYMENT_BLOCK: &str = "latest_indexed_gas_payment_block";
+const DEAD_LETTERED_MESSAGE_BY_MESSAGE_ID: &str = "dead_lettered_message_by_message_id_";
+const SUBMITTER_PAUSED: &str = "submitter_paused_";
+const RATE_LIMIT_BUCKET: &str = "rate_limit_bucket_";
+const PROFITABILITY_LEDGER_ENTRY: &str = "profitability_ledger_entry_";
+const QUEUED_MESSAGE_SNAPSHOT_BY_MESSAGE_ID: &str = "queued_message_snapshot_by_message_id_";
+const SIGNED_CHECKPOINT_BY_DOMAIN_HOOK_AND_INDEX: &str =
+    "signed_checkpoint_by_domain_hook_and_index_";
+const LATEST_AUDITED_CHECKPOINT_INDEX_BY_HOOK: &str = "latest_audited_checkpoint_index_by_hook_";
 
 /// Rocks DB result type
 pub type DbResult<T> = std::result::Result<T, DbError>;
@@ -279,6 +290,166 @@ impl HyperlaneRocksDB {
             .unwrap_or_default()
             .complete(message_id))
     }
+
+    /// Retrieve all messages currently in the dead-letter queue
+    pub fn retrieve_dead_lettered_messages(&self) -> DbResult<Vec<DeadLetteredMessage>> {
+        self.retrieve_all_decodable(DEAD_LETTERED_MESSAGE_BY_MESSAGE_ID)
+    }
+
+    /// Remove a message from the dead-letter queue
+    pub fn remove_dead_lettered_message_by_id(&self, message_id: &H256) -> DbResult<()> {
+        self.delete_value_by_key(DEAD_LETTERED_MESSAGE_BY_MESSAGE_ID, message_id)
+    }
+
+    /// Store whether submitting operations to this domain is paused
+    pub fn store_submitter_paused(&self, paused: &bool) -> DbResult<()> {
+        // There's no unit struct Encode/Decode impl, so just use `bool` and always use the `Default::default()` key
+        self.store_value_by_key(SUBMITTER_PAUSED, &bool::default(), paused)
+    }
+
+    /// Retrieve whether submitting operations to this domain is paused
+    pub fn retrieve_submitter_paused(&self) -> DbResult<Option<bool>> {
+        // There's no unit struct Encode/Decode impl, so just use `bool` and always use the `Default::default()` key
+        self.retrieve_value_by_key(SUBMITTER_PAUSED, &bool::default())
+    }
+
+    /// Store the state of a rate limit bucket
+    pub fn store_rate_limit_bucket(&self, key: &H256, bucket: &RateLimitBucket) -> DbResult<()> {
+        self.store_value_by_key(RATE_LIMIT_BUCKET, key, bucket)
+    }
+
+    /// Retrieve the state of a rate limit bucket
+    pub fn retrieve_rate_limit_bucket(&self, key: &H256) -> DbResult<Option<RateLimitBucket>> {
+        self.retrieve_value_by_key(RATE_LIMIT_BUCKET, key)
+    }
+
+    /// Retrieve the state of every rate limit bucket, along with its key
+    pub fn retrieve_rate_limit_buckets(&self) -> DbResult<Vec<(H256, RateLimitBucket)>> {
+        self.retrieve_all_keyed_decodable(RATE_LIMIT_BUCKET)
+    }
+
+    /// Delete a rate limit bucket
+    pub fn delete_rate_limit_bucket(&self, key: &H256) -> DbResult<()> {
+        self.delete_value_by_key(RATE_LIMIT_BUCKET, key)
+    }
+
+    /// Store an entry of the profitability ledger
+    pub fn store_profitability_ledger_entry(
+        &self,
+        key: &H256,
+        entry: &ProfitabilityLedgerEntry,
+    ) -> DbResult<()> {
+        self.store_value_by_key(PROFITABILITY_LEDGER_ENTRY, key, entry)
+    }
+
+    /// Retrieve an entry of the profitability ledger
+    pub fn retrieve_profitability_ledger_entry(
+        &self,
+        key: &H256,
+    ) -> DbResult<Option<ProfitabilityLedgerEntry>> {
+        self.retrieve_value_by_key(PROFITABILITY_LEDGER_ENTRY, key)
+    }
+
+    /// Delete an entry of the profitability ledger
+    pub fn delete_profitability_ledger_entry(&self, key: &H256) -> DbResult<()> {
+        self.delete_value_by_key(PROFITABILITY_LEDGER_ENTRY, key)
+    }
+
+    /// Retrieve every entry of the profitability ledger
+    pub fn retrieve_profitability_ledger_entries(&self) -> DbResult<Vec<ProfitabilityLedgerEntry>> {
+        self.retrieve_all_decodable(PROFITABILITY_LEDGER_ENTRY)
+    }
+
+    /// Store the state of a message in the submission queues
+    pub fn store_queued_message_snapshot_by_id(
+        &self,
+        message_id: &H256,
+        snapshot: &QueuedMessageSnapshot,
+    ) -> DbResult<()> {
+        self.store_value_by_key(QUEUED_MESSAGE_SNAPSHOT_BY_MESSAGE_ID, message_id, snapshot)
+    }
+
+    /// Retrieve the state of every message persisted from the submission queues
+    pub fn retrieve_queued_message_snapshots(&self) -> DbResult<Vec<QueuedMessageSnapshot>> {
+        self.retrieve_all_decodable(QUEUED_MESSAGE_SNAPSHOT_BY_MESSAGE_ID)
+    }
+
+    /// Remove the persisted state of a message in the submission queues
+    pub fn remove_queued_message_snapshot_by_id(&self, message_id: &H256) -> DbResult<()> {
+        self.delete_value_by_key(QUEUED_MESSAGE_SNAPSHOT_BY_MESSAGE_ID, message_id)
+    }
+
+    /// Store a checkpoint signed by the validator
+    pub fn store_signed_checkpoint_record(&self, record: &SignedCheckpointRecord) -> DbResult<()> {
+        let checkpoint = &record.checkpoint;
+        self.store_encodable(
+            SIGNED_CHECKPOINT_BY_DOMAIN_HOOK_AND_INDEX,
+            signed_checkpoint_key(
+                checkpoint.mailbox_domain,
+                &checkpoint.merkle_tree_hook_address,
+                checkpoint.index,
+            ),
+            record,
+        )
+    }
+
+    /// Retrieve the checkpoint signed by the validator for the given origin
+    /// domain, merkle tree hook and index
+    pub fn retrieve_signed_checkpoint_record(
+        &self,
+        mailbox_domain: u32,
+        merkle_tree_hook_address: &H256,
+        index: u32,
+    ) -> DbResult<Option<SignedCheckpointRecord>> {
+        self.retrieve_decodable(
+            SIGNED_CHECKPOINT_BY_DOMAIN_HOOK_AND_INDEX,
+            signed_checkpoint_key(mailbox_domain, merkle_tree_hook_address, index),
+        )
+    }
+
+    /// Retrieve every checkpoint signed by the validator
+    pub fn retrieve_signed_checkpoint_records(&self) -> DbResult<Vec<SignedCheckpointRecord>> {
+        self.retrieve_all_decodable(SIGNED_CHECKPOINT_BY_DOMAIN_HOOK_AND_INDEX)
+    }
+
+    /// Store the index of the latest checkpoint published for the given merkle
+    /// tree hook that the validator audited
+    pub fn store_latest_audited_checkpoint_index(
+        &self,
+        merkle_tree_hook_address: &H256,
+        index: &u32,
+    ) -> DbResult<()> {
+        self.store_value_by_key(
+            LATEST_AUDITED_CHECKPOINT_INDEX_BY_HOOK,
+            merkle_tree_hook_address,
+            index,
+        )
+    }
+
+    /// Retrieve the index of the latest checkpoint published for the given
+    /// merkle tree hook that the validator audited
+    pub fn retrieve_latest_audited_checkpoint_index(
+        &self,
+        merkle_tree_hook_address: &H256,
+    ) -> DbResult<Option<u32>> {
+        self.retrieve_value_by_key(
+            LATEST_AUDITED_CHECKPOINT_INDEX_BY_HOOK,
+            merkle_tree_hook_address,
+        )
+    }
+}
+
+/// Signed checkpoints are keyed by merkle tree hook as well as index, so that a
+/// redeployed hook doesn't conflict with the checkpoints of the previous one
+fn signed_checkpoint_key(
+    mailbox_domain: u32,
+    merkle_tree_hook_address: &H256,
+    index: u32,
+) -> Vec<u8> {
+    let mut key = mailbox_domain.to_be_bytes().to_vec();
+    key.extend(merkle_tree_hook_address.as_bytes());
+    key.extend(index.to_be_bytes());
+    key
 }
 
 #[async_trait]
@@ -656,6 +827,23 @@ impl HyperlaneDb for HyperlaneRocksDB {
         // There's no unit struct Encode/Decode impl, so just use `bool` and always use the `Default::default()` key
         self.retrieve_value_by_key(HIGHEST_SEEN_MESSAGE_NONCE, &bool::default())
     }
+
+    /// Store a dead-lettered message by its message id
+    fn store_dead_lettered_message_by_id(
+        &self,
+        message_id: &H256,
+        entry: &DeadLetteredMessage,
+    ) -> DbResult<()> {
+        self.store_value_by_key(DEAD_LETTERED_MESSAGE_BY_MESSAGE_ID, message_id, entry)
+    }
+
+    /// Retrieve a dead-lettered message by its message id
+    fn retrieve_dead_lettered_message_by_id(
+        &self,
+        message_id: &H256,
+    ) -> DbResult<Option<DeadLetteredMessage>> {
+        self.retrieve_value_by_key(DEAD_LETTERED_MESSAGE_BY_MESSAGE_ID, message_id)
+    }
 }
 
 impl HyperlaneRocksDB {
@@ -675,4 +863,8 @@ impl HyperlaneRocksDB {
     ) -> DbResult<Option<V>> {
         self.retrieve_decodable(prefix, key.to_vec())
     }
+
+    fn delete_value_by_key<K: Encode>(&self, prefix: impl AsRef<[u8]>, key: &K) -> DbResult<()> {
+        self.delete_value(prefix, key.to_vec())
+    }
 }
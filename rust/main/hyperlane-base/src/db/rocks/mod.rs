@@ -74,4 +74,33 @@ impl DB {
     pub fn retrieve(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
         Ok(self.0.get(key)?)
     }
+
+    /// Delete a value from the DB
+    pub fn delete(&self, key: &[u8]) -> Result<()> {
+        Ok(self.0.delete(key)?)
+    }
+
+    /// Retrieve all values whose key starts with `prefix`
+    pub fn retrieve_by_prefix(&self, prefix: &[u8]) -> Result<Vec<Vec<u8>>> {
+        Ok(self
+            .retrieve_key_values_by_prefix(prefix)?
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect())
+    }
+
+    /// Retrieve all keys and values whose key starts with `prefix`
+    pub fn retrieve_key_values_by_prefix(&self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        let mut key_values = vec![];
+        for item in self.0.prefix_iterator(prefix) {
+            let (key, value) = item?;
+            // No prefix extractor is configured, so the iterator keeps going past
+            // the prefixed range and has to be stopped manually
+            if !key.starts_with(prefix) {
+                break;
+            }
+            key_values.push((key.to_vec(), value.to_vec()));
+        }
+        Ok(key_values)
+    }
 }
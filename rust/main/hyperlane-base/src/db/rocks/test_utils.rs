@@ -32,10 +32,10 @@ where
 mod test {
     use hyperlane_core::{
         HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage, Indexed, LogMeta,
-        RawHyperlaneMessage, H256, H512, U256,
+        RawHyperlaneMessage, ReprepareReason, H256, H512, U256,
     };
 
-    use crate::db::HyperlaneRocksDB;
+    use crate::db::{DeadLetteredMessage, HyperlaneDb, HyperlaneRocksDB};
 
     use super::*;
 
@@ -77,4 +77,43 @@ mod test {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn db_stores_lists_and_removes_dead_lettered_messages() {
+        run_test_db(|db| async move {
+            let domain = HyperlaneDomain::new_test_domain("db_dead_lettered_messages");
+            let db = HyperlaneRocksDB::new(&domain, db);
+
+            let entries = (0..3)
+                .map(|nonce| DeadLetteredMessage {
+                    message_id: H256::random(),
+                    origin: domain.id(),
+                    destination: 12,
+                    nonce,
+                    num_retries: 50,
+                    reason: ReprepareReason::ErrorEstimatingGas,
+                    dead_lettered_at: 1_700_000_000,
+                    dropped_at: None,
+                })
+                .collect::<Vec<_>>();
+            for entry in &entries {
+                db.store_dead_lettered_message_by_id(&entry.message_id, entry)
+                    .unwrap();
+            }
+
+            let mut listed = db.retrieve_dead_lettered_messages().unwrap();
+            listed.sort_by_key(|e| e.nonce);
+            assert_eq!(listed, entries);
+
+            db.remove_dead_lettered_message_by_id(&entries[1].message_id)
+                .unwrap();
+            assert_eq!(
+                db.retrieve_dead_lettered_message_by_id(&entries[1].message_id)
+                    .unwrap(),
+                None
+            );
+            assert_eq!(db.retrieve_dead_lettered_messages().unwrap().len(), 2);
+        })
+        .await;
+    }
 }
@@ -67,6 +67,40 @@ impl TypedDB {
             .map_err(Into::into)
     }
 
+    /// Delete the value stored at the given prefix and key
+    pub fn delete_value(&self, prefix: impl AsRef<[u8]>, key: impl AsRef<[u8]>) -> Result<()> {
+        self.db
+            .delete(&self.prefixed_key(prefix.as_ref(), key.as_ref()))
+    }
+
+    /// Retrieve all decodable values stored under the given prefix
+    pub fn retrieve_all_decodable<V: Decode>(&self, prefix: impl AsRef<[u8]>) -> Result<Vec<V>> {
+        self.db
+            .retrieve_by_prefix(&self.prefixed_key(prefix.as_ref(), &[]))?
+            .into_iter()
+            .map(|v| V::read_from(&mut v.as_slice()).map_err(Into::into))
+            .collect()
+    }
+
+    /// Retrieve all decodable values stored under the given prefix, along with
+    /// their decoded keys
+    pub fn retrieve_all_keyed_decodable<K: Decode, V: Decode>(
+        &self,
+        prefix: impl AsRef<[u8]>,
+    ) -> Result<Vec<(K, V)>> {
+        let prefixed = self.prefixed_key(prefix.as_ref(), &[]);
+        self.db
+            .retrieve_key_values_by_prefix(&prefixed)?
+            .into_iter()
+            .map(|(k, v)| -> Result<(K, V)> {
+                Ok((
+                    K::read_from(&mut &k[prefixed.len()..])?,
+                    V::read_from(&mut v.as_slice())?,
+                ))
+            })
+            .collect()
+    }
+
     /// Store encodable kv pair
     pub fn store_keyed_encodable<K: Encode, V: Encode>(
         &self,
@@ -1,9 +1,11 @@
 use std::io::{Read, Write};
 
 use hyperlane_core::{
-    Decode, Encode, HyperlaneProtocolError, InterchainGasExpenditure, InterchainGasPayment, H256,
-    U256,
+    CheckpointWithMessageId, Decode, Encode, HyperlaneProtocolError, InterchainGasExpenditure,
+    InterchainGasPayment, OperationLane, PendingOperationStatus, ReprepareReason, SubmitterQueue,
+    H256, H512, U256,
 };
+use serde::{Deserialize, Serialize};
 
 /// Subset of `InterchainGasPayment` excluding the message id which is stored in
 /// the key.
@@ -24,6 +26,112 @@ pub struct InterchainGasExpenditureData {
     pub gas_used: U256,
 }
 
+/// A message that was moved out of the submission queues after exhausting its
+/// retries, kept around so it can be inspected, requeued or dropped. Dropped
+/// messages keep their entry, so that they are never relayed again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeadLetteredMessage {
+    /// The id of the message
+    pub message_id: H256,
+    /// The origin domain of the message
+    pub origin: u32,
+    /// The destination domain of the message
+    pub destination: u32,
+    /// The nonce of the message on the origin mailbox
+    pub nonce: u32,
+    /// How many times the message had been retried when it was dead-lettered
+    pub num_retries: u32,
+    /// The reprepare reason of the last failed attempt
+    pub reason: ReprepareReason,
+    /// Unix timestamp (in seconds) at which the message was dead-lettered
+    pub dead_lettered_at: u64,
+    /// Unix timestamp (in seconds) at which the message was dropped, if it was
+    #[serde(default)]
+    pub dropped_at: Option<u64>,
+}
+
+/// The state of a relayer rate limit token bucket
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitBucket {
+    /// How many messages can currently be relayed without waiting
+    pub tokens: f64,
+    /// Unix timestamp (in milliseconds) at which `tokens` was last updated
+    pub updated_at: u64,
+}
+
+/// What was paid and spent relaying the messages of one app context between
+/// an origin and a destination during one hour
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfitabilityLedgerEntry {
+    /// The app context of the messages, or `Unknown`
+    pub app_context: String,
+    /// The origin domain of the messages
+    pub origin: u32,
+    /// The destination domain of the messages
+    pub destination: u32,
+    /// Unix timestamp (in seconds) at which the hour starts
+    pub period_start: u64,
+    /// Gas payments of the messages that were delivered, in the smallest unit
+    /// of the origin's native token
+    pub payments: U256,
+    /// Tokens spent on gas, in the smallest unit of the destination's native
+    /// token
+    pub gas_spent: U256,
+    /// Gas used by the submitted transactions
+    pub gas_used: U256,
+    /// How many messages were delivered
+    pub messages_delivered: u64,
+}
+
+/// The state of a message in the submission queues of the relayer, persisted
+/// on shutdown so the message can be restored in the same queue on startup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedMessageSnapshot {
+    /// The id of the message
+    pub message_id: H256,
+    /// The queue the message was in
+    pub queue: SubmitterQueue,
+    /// The status of the message in its queue
+    pub status: PendingOperationStatus,
+    /// The app context of the message
+    pub app_context: Option<String>,
+    /// The lane the message is served from
+    pub lane: OperationLane,
+    /// Whether the message was requested to be relayed ahead of the others
+    pub expedited: bool,
+    /// How many times the message has been retried
+    pub num_retries: u32,
+    /// Seconds until the message should next be attempted, if it is waiting
+    pub next_attempt_in_secs: Option<u64>,
+    /// The metadata and gas limit the message was prepared with, if it was
+    pub submission_data: Option<(Vec<u8>, U256)>,
+    /// The transaction that submitted the message, if it awaits confirmation
+    pub submitted_tx: Option<SubmittedTx>,
+    /// Unix timestamp (in seconds) at which the snapshot was taken
+    pub snapshot_at: u64,
+}
+
+/// The transaction that submitted a queued message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubmittedTx {
+    /// The hash of the transaction
+    pub tx_hash: H512,
+    /// Whether the transaction was executed or reverted
+    pub executed: bool,
+    /// The share of the gas used by the transaction attributed to the message
+    pub gas_used: U256,
+}
+
+/// A checkpoint signed by a validator, kept so that no conflicting checkpoint
+/// is ever signed for the same index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedCheckpointRecord {
+    /// The checkpoint that was signed
+    pub checkpoint: CheckpointWithMessageId,
+    /// Unix timestamp (in seconds) at which the checkpoint was signed
+    pub signed_at: u64,
+}
+
 impl Default for InterchainGasPaymentData {
     fn default() -> Self {
         Self {
@@ -126,3 +234,140 @@ impl Decode for InterchainGasExpenditureData {
         })
     }
 }
+
+impl Encode for DeadLetteredMessage {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        // Serialize to JSON and write to the writer, to avoid having to implement the encoding manually
+        let serialized = serde_json::to_vec(self)
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "Failed to serialize"))?;
+        writer.write_all(&serialized)?;
+        Ok(serialized.len())
+    }
+}
+
+impl Decode for DeadLetteredMessage {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        // Deserialize from JSON and read from the reader, to avoid having to implement the encoding / decoding manually
+        serde_json::from_reader(reader).map_err(|err| {
+            HyperlaneProtocolError::IoError(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("Failed to deserialize. Error: {}", err),
+            ))
+        })
+    }
+}
+
+impl Encode for RateLimitBucket {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        let serialized = serde_json::to_vec(self)
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "Failed to serialize"))?;
+        writer.write_all(&serialized)?;
+        Ok(serialized.len())
+    }
+}
+
+impl Decode for RateLimitBucket {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        serde_json::from_reader(reader).map_err(|err| {
+            HyperlaneProtocolError::IoError(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("Failed to deserialize. Error: {}", err),
+            ))
+        })
+    }
+}
+
+impl Encode for ProfitabilityLedgerEntry {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        let serialized = serde_json::to_vec(self)
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "Failed to serialize"))?;
+        writer.write_all(&serialized)?;
+        Ok(serialized.len())
+    }
+}
+
+impl Decode for ProfitabilityLedgerEntry {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        serde_json::from_reader(reader).map_err(|err| {
+            HyperlaneProtocolError::IoError(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("Failed to deserialize. Error: {}", err),
+            ))
+        })
+    }
+}
+
+impl Encode for QueuedMessageSnapshot {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        let serialized = serde_json::to_vec(self)
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "Failed to serialize"))?;
+        writer.write_all(&serialized)?;
+        Ok(serialized.len())
+    }
+}
+
+impl Decode for QueuedMessageSnapshot {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        serde_json::from_reader(reader).map_err(|err| {
+            HyperlaneProtocolError::IoError(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("Failed to deserialize. Error: {}", err),
+            ))
+        })
+    }
+}
+
+impl Encode for SignedCheckpointRecord {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        let serialized = serde_json::to_vec(self)
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "Failed to serialize"))?;
+        writer.write_all(&serialized)?;
+        Ok(serialized.len())
+    }
+}
+
+impl Decode for SignedCheckpointRecord {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        serde_json::from_reader(reader).map_err(|err| {
+            HyperlaneProtocolError::IoError(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("Failed to deserialize. Error: {}", err),
+            ))
+        })
+    }
+}
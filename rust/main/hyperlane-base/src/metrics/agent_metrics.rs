@@ -141,6 +141,10 @@ pub struct AgentMetricsConf {
     #[cfg_attr(feature = "serde", serde(default))]
     pub address: Option<String>,
 
+    /// The accounts of the signer pool to track
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub signer_pool_addresses: Vec<String>,
+
     /// Information about the chain this metric is for
     pub domain: HyperlaneDomain,
 
@@ -177,9 +181,17 @@ impl MetricsUpdater {
     }
 
     async fn update_agent_metrics(&self) {
-        let Some(wallet_addr) = self.conf.address.clone() else {
-            return;
-        };
+        let wallet_addrs = self
+            .conf
+            .address
+            .iter()
+            .chain(&self.conf.signer_pool_addresses);
+        for wallet_addr in wallet_addrs {
+            self.update_wallet_balance(wallet_addr.clone()).await;
+        }
+    }
+
+    async fn update_wallet_balance(&self, wallet_addr: String) {
         let wallet_name = self.conf.name.clone();
         let Some(wallet_balance_metric) = self.agent_metrics.wallet_balance.clone() else {
             return;
@@ -39,11 +39,20 @@ pub struct CoreMetrics {
     span_events: IntCounterVec,
     last_known_message_nonce: IntGaugeVec,
     submitter_queue_length: IntGaugeVec,
+    submitter_paused: IntGaugeVec,
+    route_gas_payments: GaugeVec,
+    route_gas_spent: GaugeVec,
 
     operations_processed_count: IntCounterVec,
     messages_processed_count: IntCounterVec,
+    messages_dry_run_count: IntCounterVec,
 
     latest_checkpoint: IntGaugeVec,
+    checkpoint_audit_failures: IntCounterVec,
+
+    checkpoint_cache_lookups: IntCounterVec,
+
+    metadata_build_duration_seconds: HistogramVec,
 
     /// Set of metrics that tightly wrap the JsonRpcClient for use with the
     /// quorum provider.
@@ -137,6 +146,36 @@ impl CoreMetrics {
             registry
         )?;
 
+        let submitter_paused = register_int_gauge_vec_with_registry!(
+            opts!(
+                namespaced!("submitter_paused"),
+                "Whether submission to a remote is paused (1) or not (0)",
+                const_labels_ref
+            ),
+            &["remote"],
+            registry
+        )?;
+
+        let route_gas_payments = register_gauge_vec_with_registry!(
+            opts!(
+                namespaced!("route_gas_payments"),
+                "Gas payments received for delivered messages within a time window, in the smallest unit of the origin's native token",
+                const_labels_ref
+            ),
+            &["app_context", "origin", "remote", "window"],
+            registry
+        )?;
+
+        let route_gas_spent = register_gauge_vec_with_registry!(
+            opts!(
+                namespaced!("route_gas_spent"),
+                "Gas spent relaying messages within a time window, in the smallest unit of the remote's native token",
+                const_labels_ref
+            ),
+            &["app_context", "origin", "remote", "window"],
+            registry
+        )?;
+
         let latest_checkpoint = register_int_gauge_vec_with_registry!(
             opts!(
                 namespaced!("latest_checkpoint"),
@@ -167,6 +206,47 @@ impl CoreMetrics {
             registry
         )?;
 
+        let messages_dry_run_count = register_int_counter_vec_with_registry!(
+            opts!(
+                namespaced!("messages_dry_run_count"),
+                "Number of messages that were prepared but not submitted because of dry run mode",
+                const_labels_ref
+            ),
+            &["origin", "remote"],
+            registry
+        )?;
+
+        let checkpoint_audit_failures = register_int_counter_vec_with_registry!(
+            opts!(
+                namespaced!("checkpoint_audit_failures"),
+                "Number of published checkpoints that failed the validator self-audit",
+                const_labels_ref
+            ),
+            &["origin", "storage_location", "failure"],
+            registry
+        )?;
+
+        let checkpoint_cache_lookups = register_int_counter_vec_with_registry!(
+            opts!(
+                namespaced!("checkpoint_cache_lookups"),
+                "Number of lookups in the cache of checkpoints fetched from validators",
+                const_labels_ref
+            ),
+            &["kind", "result"],
+            registry
+        )?;
+
+        let metadata_build_duration_seconds = register_histogram_vec_with_registry!(
+            histogram_opts!(
+                namespaced!("metadata_build_duration_seconds"),
+                "Time spent building the metadata of an ISM, including that of its sub-modules",
+                vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0],
+                const_labels.clone()
+            ),
+            &["origin", "remote", "module_type", "result"],
+            registry
+        )?;
+
         Ok(Self {
             agent_name: for_agent.into(),
             registry,
@@ -179,11 +259,20 @@ impl CoreMetrics {
             last_known_message_nonce,
 
             submitter_queue_length,
+            submitter_paused,
+            route_gas_payments,
+            route_gas_spent,
 
             operations_processed_count,
             messages_processed_count,
+            messages_dry_run_count,
 
             latest_checkpoint,
+            checkpoint_audit_failures,
+
+            checkpoint_cache_lookups,
+
+            metadata_build_duration_seconds,
 
             json_rpc_client_metrics: OnceLock::new(),
             provider_metrics: OnceLock::new(),
@@ -315,10 +404,21 @@ impl CoreMetrics {
     /// - `validator_observed`: When the validator has observed the checkpoint
     ///   on the mailbox contract.
     /// - `validator_processed`: When the validator has written this checkpoint.
+    /// - `validator_audited`: When the validator has verified the checkpoint it
+    ///   published.
     pub fn latest_checkpoint(&self) -> IntGaugeVec {
         self.latest_checkpoint.clone()
     }
 
+    /// Published checkpoints that failed the validator self-audit.
+    ///
+    /// Labels:
+    /// - `origin`: Origin chain of the checkpoints.
+    /// - `failure`: Either `missing`, `wrong_signer` or `mismatch`.
+    pub fn checkpoint_audit_failures(&self) -> IntCounterVec {
+        self.checkpoint_audit_failures.clone()
+    }
+
     /// Measure of the queue lengths in Submitter instances
     ///
     /// Labels:
@@ -328,6 +428,38 @@ impl CoreMetrics {
         self.submitter_queue_length.clone()
     }
 
+    /// Whether the Submitter of a remote is paused (1) or not (0)
+    ///
+    /// Labels:
+    /// - `remote`: Remote chain the submitter is for.
+    pub fn submitter_paused(&self) -> IntGaugeVec {
+        self.submitter_paused.clone()
+    }
+
+    /// Gas payments received for messages that were delivered within a time
+    /// window, in the smallest unit of the origin's native token.
+    ///
+    /// Labels:
+    /// - `app_context`: App context of the messages, or `Unknown`.
+    /// - `origin`: Chain the messages came from.
+    /// - `remote`: Chain the messages were delivered to.
+    /// - `window`: The time window, e.g. `24h`.
+    pub fn route_gas_payments(&self) -> GaugeVec {
+        self.route_gas_payments.clone()
+    }
+
+    /// Gas spent relaying messages within a time window, in the smallest unit
+    /// of the remote's native token.
+    ///
+    /// Labels:
+    /// - `app_context`: App context of the messages, or `Unknown`.
+    /// - `origin`: Chain the messages came from.
+    /// - `remote`: Chain the messages were delivered to.
+    /// - `window`: The time window, e.g. `24h`.
+    pub fn route_gas_spent(&self) -> GaugeVec {
+        self.route_gas_spent.clone()
+    }
+
     /// The number of operations successfully submitted by this process during
     /// its lifetime.
     ///
@@ -372,6 +504,39 @@ impl CoreMetrics {
         self.messages_processed_count.clone()
     }
 
+    /// The number of messages that were fully prepared but, because the
+    /// relayer runs in dry run mode, were only logged instead of submitted.
+    ///
+    /// Labels:
+    /// - `origin`: Chain the message came from.
+    /// - `remote`: Chain the message would have been delivered to.
+    pub fn messages_dry_run_count(&self) -> IntCounterVec {
+        self.messages_dry_run_count.clone()
+    }
+
+    /// Lookups in the cache of checkpoints fetched from the checkpoint
+    /// syncers of validators.
+    ///
+    /// Labels:
+    /// - `kind`: What was looked up, either `checkpoint` or `latest_index`.
+    /// - `result`: Either `hit` or `miss`. Joining a fetch that is already in
+    ///   flight counts as a hit.
+    pub fn checkpoint_cache_lookups(&self) -> IntCounterVec {
+        self.checkpoint_cache_lookups.clone()
+    }
+
+    /// Time spent building the metadata of an ISM for a message, including
+    /// the metadata of its sub-modules.
+    ///
+    /// Labels:
+    /// - `origin`: Chain the message came from.
+    /// - `remote`: Chain the message is delivered to.
+    /// - `module_type`: Type of the ISM, e.g. `MessageIdMultisig`.
+    /// - `result`: One of `built`, `no_metadata`, `failed` or `timed_out`.
+    pub fn metadata_build_duration_seconds(&self) -> HistogramVec {
+        self.metadata_build_duration_seconds.clone()
+    }
+
     /// Measure of span durations provided by tracing.
     ///
     /// Labels:
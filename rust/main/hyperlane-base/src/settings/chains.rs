@@ -3,7 +3,7 @@ use ethers::prelude::Selector;
 use h_cosmos::CosmosProvider;
 use std::{collections::HashMap, sync::Arc};
 
-use eyre::{eyre, Context, Result};
+use eyre::{bail, eyre, Context, Result};
 
 use ethers_prometheus::middleware::{ChainInfo, ContractInfo, PrometheusMiddlewareConf};
 use hyperlane_core::{
@@ -48,6 +48,10 @@ pub struct ChainConf {
     pub domain: HyperlaneDomain,
     /// Signer configuration for this chain
     pub signer: Option<SignerConf>,
+    /// Additional signers that submit transactions to this chain in parallel
+    /// with `signer`, each with its own nonce. Only supported on Ethereum
+    /// chains.
+    pub signer_pool: Vec<SignerConf>,
     /// The reorg period of the chain, i.e. the number of blocks until finality
     pub reorg_period: ReorgPeriod,
     /// Addresses of contracts on the chain
@@ -254,6 +258,26 @@ impl ChainConf {
         .context(ctx)
     }
 
+    /// Try to build a mailbox for each signer of the signer pool
+    pub async fn build_signer_pool_mailboxes(
+        &self,
+        metrics: &CoreMetrics,
+    ) -> Result<Vec<Box<dyn Mailbox>>> {
+        if !self.signer_pool.is_empty()
+            && !matches!(self.connection, ChainConnectionConf::Ethereum(_))
+        {
+            bail!(
+                "Signer pools are only supported on Ethereum chains, but {} has one",
+                self.domain
+            );
+        }
+        let mut mailboxes = Vec::with_capacity(self.signer_pool.len());
+        for signer in &self.signer_pool {
+            mailboxes.push(self.with_signer(signer).build_mailbox(metrics).await?);
+        }
+        Ok(mailboxes)
+    }
+
     /// Try to convert the chain setting into a Merkle Tree Hook contract
     pub async fn build_merkle_tree_hook(
         &self,
@@ -714,6 +738,15 @@ impl ChainConf {
         .context(ctx)
     }
 
+    /// This chain config with `signer` as its only signer
+    fn with_signer(&self, signer: &SignerConf) -> Self {
+        Self {
+            signer: Some(signer.clone()),
+            signer_pool: vec![],
+            ..self.clone()
+        }
+    }
+
     async fn signer<S: BuildableWithSignerConf>(&self) -> Result<Option<S>> {
         if let Some(conf) = &self.signer {
             Ok(Some(conf.build::<S>().await?))
@@ -762,8 +795,15 @@ impl ChainConf {
     /// Try to build an agent metrics configuration from the chain config
     pub async fn agent_metrics_conf(&self, agent_name: String) -> Result<AgentMetricsConf> {
         let chain_signer_address = self.chain_signer().await?.map(|s| s.address_string());
+        let mut signer_pool_addresses = Vec::with_capacity(self.signer_pool.len());
+        for signer in &self.signer_pool {
+            if let Some(chain_signer) = self.with_signer(signer).chain_signer().await? {
+                signer_pool_addresses.push(chain_signer.address_string());
+            }
+        }
         Ok(AgentMetricsConf {
             address: chain_signer_address,
+            signer_pool_addresses,
             domain: self.domain.clone(),
             name: agent_name,
         })
@@ -1,12 +1,12 @@
 use crate::{
-    CheckpointSyncer, GcsStorageClientBuilder, LocalStorage, S3Storage, GCS_SERVICE_ACCOUNT_KEY,
-    GCS_USER_SECRET,
+    CheckpointSyncer, FanOutCheckpointSyncer, FanOutWritePolicy, GcsStorageClientBuilder,
+    LocalStorage, S3Storage, GCS_SERVICE_ACCOUNT_KEY, GCS_USER_SECRET,
 };
 use core::str::FromStr;
 use eyre::{eyre, Context, Report, Result};
 use prometheus::IntGauge;
 use rusoto_core::Region;
-use std::{env, path::PathBuf};
+use std::{env, path::PathBuf, sync::Arc};
 use tracing::error;
 use ya_gcp::{AuthFlow, ServiceAccountAuth};
 
@@ -39,6 +39,14 @@ pub enum CheckpointSyncerConf {
         /// `gcloud auth application-default login`
         user_secrets: Option<String>,
     },
+    /// A checkpoint syncer that writes to several checkpoint syncers, each of
+    /// which is announced
+    FanOut {
+        /// The checkpoint syncers, in order of preference when reading
+        syncers: Vec<CheckpointSyncerConf>,
+        /// How many of the checkpoint syncers a write must succeed on
+        write_policy: FanOutWritePolicy,
+    },
 }
 
 impl FromStr for CheckpointSyncerConf {
@@ -135,6 +143,30 @@ impl CheckpointSyncerConf {
     async fn build(
         &self,
         latest_index_gauge: Option<IntGauge>,
+    ) -> Result<Box<dyn CheckpointSyncer>, Report> {
+        let CheckpointSyncerConf::FanOut {
+            syncers,
+            write_policy,
+        } = self
+        else {
+            return self.build_backend(latest_index_gauge).await;
+        };
+        let mut built: Vec<Arc<dyn CheckpointSyncer>> = Vec::with_capacity(syncers.len());
+        for syncer in syncers {
+            built.push(
+                syncer
+                    .build_backend(latest_index_gauge.clone())
+                    .await?
+                    .into(),
+            );
+        }
+        Ok(Box::new(FanOutCheckpointSyncer::new(built, *write_policy)?))
+    }
+
+    /// Build the checkpoint syncer of a single storage backend
+    async fn build_backend(
+        &self,
+        latest_index_gauge: Option<IntGauge>,
     ) -> Result<Box<dyn CheckpointSyncer>, Report> {
         Ok(match self {
             CheckpointSyncerConf::LocalStorage { path } => {
@@ -171,6 +203,9 @@ impl CheckpointSyncerConf {
                         .await?,
                 )
             }
+            CheckpointSyncerConf::FanOut { .. } => {
+                return Err(eyre!("Fan-out checkpoint syncers cannot be nested"))
+            }
         })
     }
 }
@@ -7,6 +7,7 @@
 use std::{
     collections::{HashMap, HashSet},
     default::Default,
+    time::Duration,
 };
 
 use convert_case::{Case, Casing};
@@ -19,12 +20,13 @@ use url::Url;
 use h_cosmos::RawCosmosAmount;
 use hyperlane_core::{
     cfg_unwrap_all, config::*, HyperlaneDomain, HyperlaneDomainProtocol,
-    HyperlaneDomainTechnicalStack, IndexMode, ReorgPeriod,
+    HyperlaneDomainTechnicalStack, IndexMode, ReorgPeriod, H160,
 };
 
 use crate::settings::{
     chains::IndexSettings, parser::connection_parser::build_connection_conf, trace::TracingConfig,
-    ChainConf, CoreContractAddresses, Settings, SignerConf,
+    ChainConf, CoreContractAddresses, Settings, SignerConf, DEFAULT_REMOTE_SIGNER_MAX_RETRIES,
+    DEFAULT_REMOTE_SIGNER_TIMEOUT,
 };
 
 pub use super::envs::*;
@@ -134,6 +136,17 @@ fn parse_chain(
         .and_then(parse_signer)
         .end();
 
+    let signer_pool = chain
+        .chain(&mut err)
+        .get_opt_key("signerPool")
+        .into_array_iter()
+        .map(|signers| {
+            signers
+                .filter_map(|signer| parse_signer(signer).take_config_err(&mut err))
+                .collect()
+        })
+        .unwrap_or_default();
+
     let reorg_period = chain
         .chain(&mut err)
         .get_opt_key("blocks")
@@ -204,6 +217,12 @@ fn parse_chain(
         .parse_u32()
         .unwrap_or(1);
 
+    let max_batch_gas = chain
+        .chain(&mut err)
+        .get_opt_key("maxBatchGas")
+        .parse_u256()
+        .end();
+
     cfg_unwrap_all!(&chain.cwp, err: [domain]);
     let connection = build_connection_conf(
         domain.domain_protocol(),
@@ -214,6 +233,7 @@ fn parse_chain(
         OperationBatchConfig {
             batch_contract_address,
             max_batch_size,
+            max_batch_gas,
         },
     );
 
@@ -221,6 +241,7 @@ fn parse_chain(
     err.into_result(ChainConf {
         domain,
         signer,
+        signer_pool,
         reorg_period,
         addresses: CoreContractAddresses {
             mailbox,
@@ -346,12 +367,74 @@ fn parse_signer(signer: ValueParser) -> ConfigResult<SignerConf> {
                 account_address_type,
             })
         }};
+        (remote) => {{
+            let url = signer
+                .chain(&mut err)
+                .get_key("url")
+                .parse_from_str::<Url>("Expected remote signer url")
+                .end();
+            let address = signer
+                .chain(&mut err)
+                .get_key("address")
+                .parse_from_str::<H160>("Expected signer address")
+                .end();
+            let identifier = signer
+                .chain(&mut err)
+                .get_opt_key("identifier")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            let client_cert = signer
+                .chain(&mut err)
+                .get_opt_key("clientCert")
+                .parse_from_str("Expected client certificate file path")
+                .end();
+            let client_key = signer
+                .chain(&mut err)
+                .get_opt_key("clientKey")
+                .parse_from_str("Expected client key file path")
+                .end();
+            if client_cert.is_some() != client_key.is_some() {
+                Err::<(), _>(eyre!(
+                    "Expected both a client certificate and key, or neither"
+                ))
+                .take_err(&mut err, || &signer.cwp + "client_cert");
+            }
+            let ca_cert = signer
+                .chain(&mut err)
+                .get_opt_key("caCert")
+                .parse_from_str("Expected CA certificate file path")
+                .end();
+            let timeout = signer
+                .chain(&mut err)
+                .get_opt_key("timeout")
+                .parse_u64()
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_REMOTE_SIGNER_TIMEOUT);
+            let max_retries = signer
+                .chain(&mut err)
+                .get_opt_key("maxRetries")
+                .parse_u32()
+                .unwrap_or(DEFAULT_REMOTE_SIGNER_MAX_RETRIES);
+            cfg_unwrap_all!(&signer.cwp, err: [url, address]);
+            err.into_result(SignerConf::Remote {
+                url,
+                address,
+                identifier,
+                client_cert,
+                client_key,
+                ca_cert,
+                timeout,
+                max_retries,
+            })
+        }};
     }
 
     match signer_type {
         Some("hexKey") => parse_signer!(hexKey),
         Some("aws") => parse_signer!(aws),
         Some("cosmosKey") => parse_signer!(cosmosKey),
+        Some("remote") => parse_signer!(remote),
         Some(t) => {
             Err(eyre!("Unknown signer type `{t}`")).into_config_result(|| &signer.cwp + "type")
         }
@@ -1,17 +1,25 @@
+use std::{path::PathBuf, time::Duration};
+
 use async_trait::async_trait;
 use ed25519_dalek::SecretKey;
 use ethers::prelude::{AwsSigner, LocalWallet};
 use ethers::utils::hex::ToHex;
 use eyre::{bail, Context, Report};
-use hyperlane_core::{AccountAddressType, H256};
+use hyperlane_core::{AccountAddressType, H160, H256};
 use hyperlane_sealevel::Keypair;
 use rusoto_core::Region;
 use rusoto_kms::KmsClient;
 use tracing::instrument;
+use url::Url;
 
 use super::aws_credentials::AwsChainCredentialsProvider;
 use crate::types::utils;
 
+/// Default timeout of the requests to a remote signer
+pub const DEFAULT_REMOTE_SIGNER_TIMEOUT: Duration = Duration::from_secs(10);
+/// Default number of times a failed request to a remote signer is retried
+pub const DEFAULT_REMOTE_SIGNER_MAX_RETRIES: u32 = 3;
+
 /// Signer types
 #[derive(Default, Debug, Clone)]
 pub enum SignerConf {
@@ -37,6 +45,27 @@ pub enum SignerConf {
         /// Account address type for cosmos address
         account_address_type: AccountAddressType,
     },
+    /// A key held by an external signing service implementing the web3signer
+    /// eth1 API
+    Remote {
+        /// Base url of the signing service
+        url: Url,
+        /// Address of the key
+        address: H160,
+        /// Identifier of the key in the signing service, defaults to its address
+        identifier: Option<String>,
+        /// Path to the PEM certificate to authenticate to the signing service with
+        client_cert: Option<PathBuf>,
+        /// Path to the PEM private key of the client certificate
+        client_key: Option<PathBuf>,
+        /// Path to the PEM certificate of the CA of the signing service, if it
+        /// isn't trusted by the system
+        ca_cert: Option<PathBuf>,
+        /// Timeout of each request to the signing service
+        timeout: Duration,
+        /// How many times a failed request to the signing service is retried
+        max_retries: u32,
+    },
     /// Assume node will sign on RPC calls
     #[default]
     Node,
@@ -85,6 +114,43 @@ impl BuildableWithSignerConf for hyperlane_ethereum::Signers {
                 let signer = AwsSigner::new(client, id, 0).await?;
                 hyperlane_ethereum::Signers::Aws(signer)
             }
+            SignerConf::Remote {
+                url,
+                address,
+                identifier,
+                client_cert,
+                client_key,
+                ca_cert,
+                timeout,
+                max_retries,
+            } => {
+                let mut client = reqwest::Client::builder()
+                    .use_rustls_tls()
+                    .timeout(*timeout);
+                if let Some(ca_cert) = ca_cert {
+                    let pem = std::fs::read(ca_cert).context("Reading remote signer CA cert")?;
+                    client = client.add_root_certificate(
+                        reqwest::Certificate::from_pem(&pem)
+                            .context("Invalid remote signer CA cert")?,
+                    );
+                }
+                if let (Some(cert), Some(key)) = (client_cert, client_key) {
+                    let cert = std::fs::read(cert).context("Reading remote signer client cert")?;
+                    let key = std::fs::read(key).context("Reading remote signer client key")?;
+                    client = client.identity(
+                        hyperlane_ethereum::RemoteSigner::client_identity(&cert, &key)
+                            .context("Invalid remote signer client cert or key")?,
+                    );
+                }
+                let identifier = identifier.clone().unwrap_or_else(|| format!("{address:?}"));
+                hyperlane_ethereum::Signers::Remote(hyperlane_ethereum::RemoteSigner::new(
+                    client.build()?,
+                    url,
+                    &identifier,
+                    ethers::types::Address::from(address.0),
+                    *max_retries,
+                )?)
+            }
             SignerConf::CosmosKey { .. } => {
                 bail!("cosmosKey signer is not supported by Ethereum")
             }
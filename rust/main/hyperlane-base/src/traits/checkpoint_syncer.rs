@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
 use eyre::Result;
@@ -28,12 +28,40 @@ pub trait CheckpointSyncer: Debug + Send + Sync {
         &self,
         signed_checkpoint: &SignedCheckpointWithMessageId,
     ) -> Result<()>;
+    /// Fetch the signed checkpoint at this index if every storage location of
+    /// this syncer has it, so that a location that couldn't be written to
+    /// gets the checkpoint once it is written again
+    async fn fetch_complete_checkpoint(
+        &self,
+        index: u32,
+    ) -> Result<Option<SignedCheckpointWithMessageId>> {
+        self.fetch_checkpoint(index).await
+    }
+    /// Write the signed checkpoint to the storage locations of this syncer
+    /// that don't have a checkpoint at its index yet
+    async fn write_missing_checkpoint(
+        &self,
+        signed_checkpoint: &SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        self.write_checkpoint(signed_checkpoint).await
+    }
     /// Write the agent metadata to this syncer
     async fn write_metadata(&self, metadata: &AgentMetadata) -> Result<()>;
     /// Write the signed announcement to this syncer
     async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()>;
     /// Return the announcement storage location for this syncer
     fn announcement_location(&self) -> String;
+    /// Return every storage location this syncer writes to, which should all
+    /// be announced
+    fn announcement_locations(&self) -> Vec<String> {
+        vec![self.announcement_location()]
+    }
+    /// The checkpoint syncers of the storage locations of this syncer, so that
+    /// each of them can be read from on its own. Empty if this syncer has a
+    /// single storage location.
+    fn backends(&self) -> Vec<Arc<dyn CheckpointSyncer>> {
+        vec![]
+    }
     /// If a bigger than expected reorg was detected on the validated chain, this flag can be set to inform
     /// the validator agent to stop publishing checkpoints. Once any remediation is done, this flag can be reset
     /// to resume operation.
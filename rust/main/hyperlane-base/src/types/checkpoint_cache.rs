@@ -0,0 +1,451 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use hyperlane_core::{SignedCheckpointWithMessageId, H160};
+use prometheus::IntCounterVec;
+use tokio::sync::OnceCell;
+use tracing::debug;
+
+use crate::{CheckpointSyncer, CoreMetrics};
+
+/// The default number of signed checkpoints that are cached
+pub const DEFAULT_CHECKPOINT_CACHE_CAPACITY: usize = 50_000;
+/// The default time for which a checkpoint that couldn't be fetched is assumed
+/// to still be missing
+pub const DEFAULT_CHECKPOINT_CACHE_NEGATIVE_TTL: Duration = Duration::from_secs(10);
+/// The default time for which the latest index of a validator is cached
+pub const DEFAULT_CHECKPOINT_CACHE_LATEST_INDEX_TTL: Duration = Duration::from_secs(5);
+
+/// Validators may use the same key on several origins, so their checkpoints
+/// and latest indices are scoped to the origin domain
+type ValidatorKey = (u32, H160);
+type CheckpointKey = (u32, H160, u32);
+type InFlightFetch = Arc<OnceCell<Option<SignedCheckpointWithMessageId>>>;
+
+/// Caches the checkpoints fetched from the checkpoint syncers of validators,
+/// keyed by origin domain, validator and index, so messages whose metadata is
+/// built around the same time don't download the same checkpoints again.
+///
+/// Signed checkpoints never change, so they are kept until they are evicted to
+/// make room for newer ones. Checkpoints that couldn't be fetched may still be
+/// signed later, so they are only cached for a short time. Concurrent lookups
+/// of the same checkpoint share a single fetch.
+#[derive(Debug)]
+pub struct CheckpointCache {
+    capacity: usize,
+    negative_ttl: Duration,
+    latest_index_ttl: Duration,
+    state: Mutex<CacheState>,
+    lookups: IntCounterVec,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    checkpoints: HashMap<CheckpointKey, CachedCheckpoint>,
+    /// Cached keys, oldest first
+    insertion_order: VecDeque<CheckpointKey>,
+    in_flight: HashMap<CheckpointKey, InFlightFetch>,
+    latest_indices: HashMap<ValidatorKey, (Instant, Option<u32>)>,
+}
+
+#[derive(Debug)]
+struct CachedCheckpoint {
+    checkpoint: Option<SignedCheckpointWithMessageId>,
+    /// Only set for checkpoints that couldn't be fetched
+    expires_at: Option<Instant>,
+}
+
+impl CachedCheckpoint {
+    fn is_fresh(&self) -> bool {
+        self.expires_at
+            .map_or(true, |expires_at| Instant::now() < expires_at)
+    }
+}
+
+impl CheckpointCache {
+    /// Create a cache that holds at most `capacity` checkpoints
+    pub fn new(
+        capacity: usize,
+        negative_ttl: Duration,
+        latest_index_ttl: Duration,
+        metrics: &CoreMetrics,
+    ) -> Self {
+        Self {
+            capacity,
+            negative_ttl,
+            latest_index_ttl,
+            state: Default::default(),
+            lookups: metrics.checkpoint_cache_lookups(),
+        }
+    }
+
+    /// Create a cache with the default capacity and TTLs
+    pub fn with_defaults(metrics: &CoreMetrics) -> Self {
+        Self::new(
+            DEFAULT_CHECKPOINT_CACHE_CAPACITY,
+            DEFAULT_CHECKPOINT_CACHE_NEGATIVE_TTL,
+            DEFAULT_CHECKPOINT_CACHE_LATEST_INDEX_TTL,
+            metrics,
+        )
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn record_lookup(&self, kind: &str, hit: bool) {
+        self.lookups
+            .with_label_values(&[kind, if hit { "hit" } else { "miss" }])
+            .inc();
+    }
+
+    /// The latest index of the validator on the origin, read from `syncer`
+    /// unless it was read recently. Errors are treated like a missing index.
+    pub async fn latest_index(
+        &self,
+        origin: u32,
+        validator: H160,
+        syncer: &dyn CheckpointSyncer,
+    ) -> Option<u32> {
+        let key = (origin, validator);
+        let cached = self
+            .state()
+            .latest_indices
+            .get(&key)
+            .filter(|(read_at, _)| read_at.elapsed() < self.latest_index_ttl)
+            .map(|(_, index)| *index);
+        if let Some(index) = cached {
+            self.record_lookup("latest_index", true);
+            return index;
+        }
+        self.record_lookup("latest_index", false);
+
+        let index = match syncer.latest_index().await {
+            Ok(index) => index,
+            Err(err) => {
+                debug!(
+                    origin,
+                    ?validator,
+                    ?err,
+                    "Failed to get latest index from validator"
+                );
+                None
+            }
+        };
+        self.state()
+            .latest_indices
+            .insert(key, (Instant::now(), index));
+        index
+    }
+
+    /// The checkpoint the validator signed at `index` on the origin, fetched
+    /// from `syncer` unless it is cached. Errors are treated like a missing
+    /// checkpoint.
+    pub async fn fetch_checkpoint(
+        &self,
+        origin: u32,
+        validator: H160,
+        index: u32,
+        syncer: &dyn CheckpointSyncer,
+    ) -> Option<SignedCheckpointWithMessageId> {
+        let key = (origin, validator, index);
+        let fetch = {
+            let mut state = self.state();
+            if let Some(cached) = state.checkpoints.get(&key).filter(|c| c.is_fresh()) {
+                self.record_lookup("checkpoint", true);
+                return cached.checkpoint.clone();
+            }
+            // Join the fetch that is already in flight, if there is one
+            let in_flight = state.in_flight.get(&key).cloned();
+            self.record_lookup("checkpoint", in_flight.is_some());
+            in_flight.unwrap_or_else(|| state.in_flight.entry(key).or_default().clone())
+        };
+
+        let checkpoint = fetch
+            .get_or_init(|| async {
+                match syncer.fetch_checkpoint(index).await {
+                    Ok(checkpoint) => checkpoint,
+                    Err(err) => {
+                        debug!(
+                            origin,
+                            ?validator,
+                            index,
+                            ?err,
+                            "Failed to fetch checkpoint"
+                        );
+                        None
+                    }
+                }
+            })
+            .await
+            .clone();
+
+        let mut state = self.state();
+        // Only the first of the callers sharing the fetch finds it in flight
+        if state
+            .in_flight
+            .get(&key)
+            .map_or(false, |f| Arc::ptr_eq(f, &fetch))
+        {
+            state.in_flight.remove(&key);
+            self.insert(&mut state, key, checkpoint.clone());
+        }
+        checkpoint
+    }
+
+    fn insert(
+        &self,
+        state: &mut CacheState,
+        key: CheckpointKey,
+        checkpoint: Option<SignedCheckpointWithMessageId>,
+    ) {
+        let expires_at = checkpoint
+            .is_none()
+            .then(|| Instant::now() + self.negative_ttl);
+        let cached = CachedCheckpoint {
+            checkpoint,
+            expires_at,
+        };
+        // Keys keep their position when a stale entry is refreshed, so every
+        // cached key is in `insertion_order` exactly once
+        if state.checkpoints.insert(key, cached).is_none() {
+            state.insertion_order.push_back(key);
+        }
+        while state.checkpoints.len() > self.capacity {
+            let Some(oldest) = state.insertion_order.pop_front() else {
+                break;
+            };
+            state.checkpoints.remove(&oldest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use async_trait::async_trait;
+    use eyre::Result;
+    use hyperlane_core::{
+        Checkpoint, CheckpointWithMessageId, ReorgEvent, Signature, SignedAnnouncement, H256, U256,
+    };
+    use prometheus::Registry;
+
+    use super::*;
+    use crate::AgentMetadata;
+
+    const ORIGIN: u32 = 1;
+
+    /// Serves checkpoints up to `latest` and counts the fetches
+    #[derive(Debug, Default)]
+    struct CountingSyncer {
+        latest: u32,
+        fetches: AtomicU32,
+    }
+
+    fn signed_checkpoint(index: u32) -> SignedCheckpointWithMessageId {
+        SignedCheckpointWithMessageId {
+            value: CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: H256::zero(),
+                    mailbox_domain: 1,
+                    root: H256::zero(),
+                    index,
+                },
+                message_id: H256::zero(),
+            },
+            signature: Signature {
+                r: U256::zero(),
+                s: U256::zero(),
+                v: 0,
+            },
+        }
+    }
+
+    #[async_trait]
+    impl CheckpointSyncer for CountingSyncer {
+        async fn latest_index(&self) -> Result<Option<u32>> {
+            Ok(Some(self.latest))
+        }
+        async fn write_latest_index(&self, _index: u32) -> Result<()> {
+            unimplemented!()
+        }
+        async fn fetch_checkpoint(
+            &self,
+            index: u32,
+        ) -> Result<Option<SignedCheckpointWithMessageId>> {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+            // Give concurrent lookups a chance to join the fetch
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok((index <= self.latest).then(|| signed_checkpoint(index)))
+        }
+        async fn write_checkpoint(
+            &self,
+            _checkpoint: &SignedCheckpointWithMessageId,
+        ) -> Result<()> {
+            unimplemented!()
+        }
+        async fn write_metadata(&self, _metadata: &AgentMetadata) -> Result<()> {
+            unimplemented!()
+        }
+        async fn write_announcement(&self, _announcement: &SignedAnnouncement) -> Result<()> {
+            unimplemented!()
+        }
+        fn announcement_location(&self) -> String {
+            unimplemented!()
+        }
+        async fn write_reorg_status(&self, _reorg_event: &ReorgEvent) -> Result<()> {
+            unimplemented!()
+        }
+        async fn reorg_status(&self) -> Result<Option<ReorgEvent>> {
+            unimplemented!()
+        }
+    }
+
+    fn cache(capacity: usize, negative_ttl: Duration) -> (CheckpointCache, CoreMetrics) {
+        let metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+        let cache = CheckpointCache::new(capacity, negative_ttl, Duration::from_secs(60), &metrics);
+        (cache, metrics)
+    }
+
+    #[tokio::test]
+    async fn test_caches_checkpoints_and_shares_fetches() {
+        let (cache, metrics) = cache(10, Duration::from_secs(60));
+        let syncer = CountingSyncer {
+            latest: 5,
+            ..Default::default()
+        };
+        let validator = H160::random();
+
+        let (first, second) = tokio::join!(
+            cache.fetch_checkpoint(ORIGIN, validator, 3, &syncer),
+            cache.fetch_checkpoint(ORIGIN, validator, 3, &syncer)
+        );
+        assert_eq!(first, Some(signed_checkpoint(3)));
+        assert_eq!(second, first);
+        assert_eq!(
+            cache.fetch_checkpoint(ORIGIN, validator, 3, &syncer).await,
+            first
+        );
+        assert_eq!(syncer.fetches.load(Ordering::SeqCst), 1);
+
+        // Missing checkpoints are cached too
+        assert_eq!(
+            cache.fetch_checkpoint(ORIGIN, validator, 6, &syncer).await,
+            None
+        );
+        assert_eq!(
+            cache.fetch_checkpoint(ORIGIN, validator, 6, &syncer).await,
+            None
+        );
+        assert_eq!(syncer.fetches.load(Ordering::SeqCst), 2);
+
+        let lookups = metrics.checkpoint_cache_lookups();
+        assert_eq!(lookups.with_label_values(&["checkpoint", "hit"]).get(), 3);
+        assert_eq!(lookups.with_label_values(&["checkpoint", "miss"]).get(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_missing_checkpoints_expire() {
+        let (cache, _metrics) = cache(10, Duration::ZERO);
+        let syncer = CountingSyncer::default();
+        let validator = H160::random();
+
+        assert_eq!(
+            cache.fetch_checkpoint(ORIGIN, validator, 1, &syncer).await,
+            None
+        );
+        assert_eq!(
+            cache.fetch_checkpoint(ORIGIN, validator, 1, &syncer).await,
+            None
+        );
+        assert_eq!(syncer.fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_evicts_oldest_checkpoints() {
+        let (cache, _metrics) = cache(2, Duration::from_secs(60));
+        let syncer = CountingSyncer {
+            latest: 10,
+            ..Default::default()
+        };
+        let validator = H160::random();
+
+        for index in 0..3 {
+            cache
+                .fetch_checkpoint(ORIGIN, validator, index, &syncer)
+                .await;
+        }
+        assert_eq!(cache.state().checkpoints.len(), 2);
+        cache.fetch_checkpoint(ORIGIN, validator, 2, &syncer).await;
+        assert_eq!(syncer.fetches.load(Ordering::SeqCst), 3);
+        cache.fetch_checkpoint(ORIGIN, validator, 0, &syncer).await;
+        assert_eq!(syncer.fetches.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_caches_latest_index() {
+        let (cache, _metrics) = cache(10, Duration::from_secs(60));
+        let validator = H160::random();
+        let syncer = CountingSyncer {
+            latest: 5,
+            ..Default::default()
+        };
+        assert_eq!(
+            cache.latest_index(ORIGIN, validator, &syncer).await,
+            Some(5)
+        );
+
+        let advanced = CountingSyncer {
+            latest: 6,
+            ..Default::default()
+        };
+        assert_eq!(
+            cache.latest_index(ORIGIN, validator, &advanced).await,
+            Some(5)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scopes_validators_to_their_origin() {
+        let (cache, _metrics) = cache(10, Duration::from_secs(60));
+        // The same validator key, signing for two origins
+        let validator = H160::random();
+        let origin_syncer = CountingSyncer {
+            latest: 5,
+            ..Default::default()
+        };
+        let other_origin_syncer = CountingSyncer {
+            latest: 8,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            cache.latest_index(ORIGIN, validator, &origin_syncer).await,
+            Some(5)
+        );
+        assert_eq!(
+            cache
+                .latest_index(ORIGIN + 1, validator, &other_origin_syncer)
+                .await,
+            Some(8)
+        );
+
+        assert_eq!(
+            cache
+                .fetch_checkpoint(ORIGIN, validator, 7, &origin_syncer)
+                .await,
+            None
+        );
+        assert_eq!(
+            cache
+                .fetch_checkpoint(ORIGIN + 1, validator, 7, &other_origin_syncer)
+                .await,
+            Some(signed_checkpoint(7))
+        );
+        assert_eq!(other_origin_syncer.fetches.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,232 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use eyre::Result;
+use hyperlane_core::{ReorgEvent, SignedAnnouncement, SignedCheckpointWithMessageId};
+use tokio::sync::OnceCell;
+use tracing::{debug, warn};
+
+use crate::settings::CheckpointSyncerConf;
+use crate::traits::CheckpointSyncer;
+use crate::AgentMetadata;
+
+/// A checkpoint syncer that reads from a preferred checkpoint syncer, and only
+/// falls back to the other storage locations of a validator when reading from
+/// it fails or it doesn't have the data, e.g. because a write to it failed. The fallback checkpoint syncers are built the first time they
+/// are needed, so that they cost nothing while the preferred one is healthy.
+#[derive(Debug)]
+pub struct FallbackCheckpointSyncer {
+    preferred: Arc<dyn CheckpointSyncer>,
+    /// The configs of the fallback checkpoint syncers, in order of preference
+    fallback_configs: Vec<CheckpointSyncerConf>,
+    fallbacks: OnceCell<Vec<Arc<dyn CheckpointSyncer>>>,
+}
+
+impl FallbackCheckpointSyncer {
+    /// Create a new fallback checkpoint syncer
+    pub fn new(
+        preferred: Arc<dyn CheckpointSyncer>,
+        fallback_configs: Vec<CheckpointSyncerConf>,
+    ) -> Self {
+        Self {
+            preferred,
+            fallback_configs,
+            fallbacks: OnceCell::new(),
+        }
+    }
+
+    /// The fallback checkpoint syncers that could be built, which are only
+    /// built on the first call
+    async fn fallbacks(&self) -> &[Arc<dyn CheckpointSyncer>] {
+        self.fallbacks
+            .get_or_init(|| async {
+                let mut fallbacks = Vec::with_capacity(self.fallback_configs.len());
+                for config in &self.fallback_configs {
+                    match config.build_and_validate(None).await {
+                        Ok(syncer) => fallbacks.push(syncer.into()),
+                        Err(err) => {
+                            debug!(
+                                ?err,
+                                ?config,
+                                "Error when loading fallback checkpoint syncer"
+                            )
+                        }
+                    }
+                }
+                fallbacks
+            })
+            .await
+    }
+
+    /// Reads `what` from the preferred checkpoint syncer, or from the first
+    /// fallback that has it if the preferred one fails or doesn't have it.
+    /// The error of the preferred checkpoint syncer is only returned if none
+    /// of them could be read from.
+    async fn read<'a, T, F, Fut>(&'a self, what: &str, read: F) -> Result<Option<T>>
+    where
+        F: Fn(&'a Arc<dyn CheckpointSyncer>) -> Fut,
+        Fut: std::future::Future<Output = Result<Option<T>>>,
+    {
+        let location = self.preferred.announcement_location();
+        let preferred_err = match read(&self.preferred).await {
+            Ok(Some(value)) => return Ok(Some(value)),
+            Ok(None) => {
+                debug!(%location, "No {what} in checkpoint syncer, falling back to the other storage locations");
+                None
+            }
+            Err(err) => {
+                warn!(%location, ?err, "Failed to read {what} from checkpoint syncer, falling back to the other storage locations");
+                Some(err)
+            }
+        };
+        let mut any_ok = preferred_err.is_none();
+        for syncer in self.fallbacks().await {
+            match read(syncer).await {
+                Ok(Some(value)) => return Ok(Some(value)),
+                Ok(None) => any_ok = true,
+                Err(err) => {
+                    warn!(location = %syncer.announcement_location(), ?err, "Failed to read {what} from fallback checkpoint syncer");
+                }
+            }
+        }
+        match preferred_err {
+            Some(err) if !any_ok => Err(err),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Writes only go to the preferred checkpoint syncer, since this is meant for
+/// reading the checkpoints of other validators
+#[async_trait]
+impl CheckpointSyncer for FallbackCheckpointSyncer {
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        self.read("latest index", |s| s.latest_index()).await
+    }
+
+    async fn write_latest_index(&self, index: u32) -> Result<()> {
+        self.preferred.write_latest_index(index).await
+    }
+
+    async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        self.read("checkpoint", |s| s.fetch_checkpoint(index)).await
+    }
+
+    async fn write_checkpoint(
+        &self,
+        signed_checkpoint: &SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        self.preferred.write_checkpoint(signed_checkpoint).await
+    }
+
+    async fn write_metadata(&self, metadata: &AgentMetadata) -> Result<()> {
+        self.preferred.write_metadata(metadata).await
+    }
+
+    async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
+        self.preferred.write_announcement(signed_announcement).await
+    }
+
+    fn announcement_location(&self) -> String {
+        self.preferred.announcement_location()
+    }
+
+    async fn write_reorg_status(&self, reorg_event: &ReorgEvent) -> Result<()> {
+        self.preferred.write_reorg_status(reorg_event).await
+    }
+
+    async fn reorg_status(&self) -> Result<Option<ReorgEvent>> {
+        self.read("reorg status", |s| s.reorg_status()).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use hyperlane_core::{Checkpoint, CheckpointWithMessageId, Signature, H256, U256};
+
+    use super::*;
+    use crate::LocalStorage;
+
+    fn signed_checkpoint(index: u32) -> SignedCheckpointWithMessageId {
+        SignedCheckpointWithMessageId {
+            value: CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: H256::zero(),
+                    mailbox_domain: 1,
+                    root: H256::zero(),
+                    index,
+                },
+                message_id: H256::zero(),
+            },
+            signature: Signature {
+                r: U256::zero(),
+                s: U256::zero(),
+                v: 0,
+            },
+        }
+    }
+
+    fn local_storage_conf(dir: &tempfile::TempDir) -> CheckpointSyncerConf {
+        CheckpointSyncerConf::LocalStorage {
+            path: dir.path().join("checkpoints"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallbacks_are_only_built_when_the_preferred_syncer_fails() {
+        let preferred_dir = tempfile::tempdir().unwrap();
+        let fallback_dir = tempfile::tempdir().unwrap();
+        let preferred: Arc<dyn CheckpointSyncer> =
+            Arc::new(LocalStorage::new(preferred_dir.path().join("checkpoints"), None).unwrap());
+        let fallback = LocalStorage::new(fallback_dir.path().join("checkpoints"), None).unwrap();
+        preferred.write_latest_index(3).await.unwrap();
+        fallback.write_latest_index(5).await.unwrap();
+
+        let syncer = FallbackCheckpointSyncer::new(
+            preferred,
+            vec![
+                // Can't be built, so it is skipped
+                CheckpointSyncerConf::LocalStorage {
+                    path: PathBuf::from("/dev/null/checkpoints"),
+                },
+                local_storage_conf(&fallback_dir),
+            ],
+        );
+        assert_eq!(syncer.latest_index().await.unwrap(), Some(3));
+        assert!(syncer.fallbacks.get().is_none());
+
+        // The preferred checkpoint syncer becomes unreadable
+        std::fs::write(
+            preferred_dir.path().join("checkpoints").join("index.json"),
+            "not an index",
+        )
+        .unwrap();
+        assert_eq!(syncer.latest_index().await.unwrap(), Some(5));
+        assert_eq!(syncer.fallbacks.get().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_when_the_preferred_syncer_is_missing_data() {
+        let preferred_dir = tempfile::tempdir().unwrap();
+        let fallback_dir = tempfile::tempdir().unwrap();
+        let preferred: Arc<dyn CheckpointSyncer> =
+            Arc::new(LocalStorage::new(preferred_dir.path().join("checkpoints"), None).unwrap());
+        let fallback = LocalStorage::new(fallback_dir.path().join("checkpoints"), None).unwrap();
+        fallback.write_latest_index(5).await.unwrap();
+        fallback
+            .write_checkpoint(&signed_checkpoint(5))
+            .await
+            .unwrap();
+
+        let syncer =
+            FallbackCheckpointSyncer::new(preferred, vec![local_storage_conf(&fallback_dir)]);
+        assert_eq!(syncer.latest_index().await.unwrap(), Some(5));
+        assert_eq!(
+            syncer.fetch_checkpoint(5).await.unwrap(),
+            Some(signed_checkpoint(5))
+        );
+        assert_eq!(syncer.fetch_checkpoint(6).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,388 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use eyre::{eyre, Result};
+use futures::future::join_all;
+use hyperlane_core::{ReorgEvent, SignedAnnouncement, SignedCheckpointWithMessageId};
+use tracing::warn;
+
+use crate::traits::CheckpointSyncer;
+use crate::AgentMetadata;
+
+/// How many of the checkpoint syncers of a `FanOutCheckpointSyncer` a write
+/// must succeed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FanOutWritePolicy {
+    /// Every checkpoint syncer must be written to
+    #[default]
+    All,
+    /// At least this many checkpoint syncers must be written to
+    Quorum(usize),
+}
+
+/// A checkpoint syncer that writes to several checkpoint syncers, so that
+/// the outage of one storage backend doesn't stop checkpoints from being
+/// published, and reads from the first of them that has the data.
+#[derive(Debug, Clone)]
+pub struct FanOutCheckpointSyncer {
+    /// The checkpoint syncers, in order of preference when reading
+    syncers: Vec<Arc<dyn CheckpointSyncer>>,
+    write_policy: FanOutWritePolicy,
+}
+
+impl FanOutCheckpointSyncer {
+    /// Create a new fan-out checkpoint syncer. Fails if there are no
+    /// checkpoint syncers, or fewer than the write quorum.
+    pub fn new(
+        syncers: Vec<Arc<dyn CheckpointSyncer>>,
+        write_policy: FanOutWritePolicy,
+    ) -> Result<Self> {
+        if syncers.is_empty() {
+            return Err(eyre!(
+                "A fan-out checkpoint syncer needs at least one syncer"
+            ));
+        }
+        if let FanOutWritePolicy::Quorum(quorum) = write_policy {
+            if quorum == 0 || quorum > syncers.len() {
+                return Err(eyre!(
+                    "Invalid write quorum of {quorum} for {} checkpoint syncers",
+                    syncers.len()
+                ));
+            }
+        }
+        Ok(Self {
+            syncers,
+            write_policy,
+        })
+    }
+
+    fn required_writes(&self) -> usize {
+        match self.write_policy {
+            FanOutWritePolicy::All => self.syncers.len(),
+            FanOutWritePolicy::Quorum(quorum) => quorum,
+        }
+    }
+
+    /// Checks the results of writing `what` to every checkpoint syncer against
+    /// the write policy
+    fn check_writes(&self, what: &str, results: Vec<Result<()>>) -> Result<()> {
+        let mut succeeded = 0;
+        for (syncer, result) in self.syncers.iter().zip(results) {
+            match result {
+                Ok(()) => succeeded += 1,
+                Err(err) => {
+                    warn!(location = %syncer.announcement_location(), ?err, "Failed to write {what} to checkpoint syncer");
+                }
+            }
+        }
+        let required = self.required_writes();
+        if succeeded < required {
+            return Err(eyre!(
+                "Wrote {what} to {succeeded} checkpoint syncers, {required} required"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CheckpointSyncer for FanOutCheckpointSyncer {
+    /// The highest index among the checkpoint syncers that could be read
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        let results = join_all(self.syncers.iter().map(|s| s.latest_index())).await;
+        let mut latest_index = None;
+        let mut last_err = None;
+        let mut any_ok = false;
+        for (syncer, result) in self.syncers.iter().zip(results) {
+            match result {
+                Ok(index) => {
+                    any_ok = true;
+                    latest_index = latest_index.max(index);
+                }
+                Err(err) => {
+                    warn!(location = %syncer.announcement_location(), ?err, "Failed to read latest index from checkpoint syncer");
+                    last_err = Some(err);
+                }
+            }
+        }
+        match last_err {
+            Some(err) if !any_ok => Err(err),
+            _ => Ok(latest_index),
+        }
+    }
+
+    async fn write_latest_index(&self, index: u32) -> Result<()> {
+        let results = join_all(self.syncers.iter().map(|s| s.write_latest_index(index))).await;
+        self.check_writes("latest index", results)
+    }
+
+    /// Updates the latest index of each checkpoint syncer that is behind, so
+    /// that a syncer that was unavailable catches up
+    async fn update_latest_index(&self, index: u32) -> Result<()> {
+        let results = join_all(self.syncers.iter().map(|s| s.update_latest_index(index))).await;
+        self.check_writes("latest index", results)
+    }
+
+    async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        let mut last_err = None;
+        let mut any_ok = false;
+        for syncer in &self.syncers {
+            match syncer.fetch_checkpoint(index).await {
+                Ok(Some(checkpoint)) => return Ok(Some(checkpoint)),
+                Ok(None) => any_ok = true,
+                Err(err) => {
+                    warn!(location = %syncer.announcement_location(), ?err, index, "Failed to fetch checkpoint from checkpoint syncer");
+                    last_err = Some(err);
+                }
+            }
+        }
+        match last_err {
+            Some(err) if !any_ok => Err(err),
+            _ => Ok(None),
+        }
+    }
+
+    async fn write_checkpoint(
+        &self,
+        signed_checkpoint: &SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        let results = join_all(
+            self.syncers
+                .iter()
+                .map(|s| s.write_checkpoint(signed_checkpoint)),
+        )
+        .await;
+        self.check_writes("checkpoint", results)
+    }
+
+    /// Checkpoint syncers that can't be read are assumed to be missing the
+    /// checkpoint
+    async fn fetch_complete_checkpoint(
+        &self,
+        index: u32,
+    ) -> Result<Option<SignedCheckpointWithMessageId>> {
+        let results = join_all(self.syncers.iter().map(|s| s.fetch_checkpoint(index))).await;
+        let mut complete = None;
+        for (syncer, result) in self.syncers.iter().zip(results) {
+            match result {
+                Ok(Some(checkpoint)) => complete = complete.or(Some(checkpoint)),
+                Ok(None) => return Ok(None),
+                Err(err) => {
+                    warn!(location = %syncer.announcement_location(), ?err, index, "Failed to fetch checkpoint from checkpoint syncer");
+                    return Ok(None);
+                }
+            }
+        }
+        Ok(complete)
+    }
+
+    /// Checkpoint syncers that already have the checkpoint count as written
+    /// to, and those that can't be read are written to anyway
+    async fn write_missing_checkpoint(
+        &self,
+        signed_checkpoint: &SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        let index = signed_checkpoint.value.index;
+        let results = join_all(self.syncers.iter().map(|s| async move {
+            match s.fetch_checkpoint(index).await {
+                Ok(Some(_)) => Ok(()),
+                _ => s.write_checkpoint(signed_checkpoint).await,
+            }
+        }))
+        .await;
+        self.check_writes("checkpoint", results)
+    }
+
+    async fn write_metadata(&self, metadata: &AgentMetadata) -> Result<()> {
+        let results = join_all(self.syncers.iter().map(|s| s.write_metadata(metadata))).await;
+        self.check_writes("metadata", results)
+    }
+
+    /// Writes the announcement to the checkpoint syncer it announces, or to
+    /// all of them if it announces none of them
+    async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
+        let location = &signed_announcement.value.storage_location;
+        if let Some(syncer) = self
+            .syncers
+            .iter()
+            .find(|s| &s.announcement_location() == location)
+        {
+            return syncer.write_announcement(signed_announcement).await;
+        }
+        let results = join_all(
+            self.syncers
+                .iter()
+                .map(|s| s.write_announcement(signed_announcement)),
+        )
+        .await;
+        self.check_writes("announcement", results)
+    }
+
+    fn announcement_location(&self) -> String {
+        self.syncers[0].announcement_location()
+    }
+
+    fn announcement_locations(&self) -> Vec<String> {
+        self.syncers
+            .iter()
+            .flat_map(|s| s.announcement_locations())
+            .collect()
+    }
+
+    fn backends(&self) -> Vec<Arc<dyn CheckpointSyncer>> {
+        self.syncers.clone()
+    }
+
+    /// Reorgs are always written to every checkpoint syncer that can be
+    /// written to, regardless of the write policy
+    async fn write_reorg_status(&self, reorg_event: &ReorgEvent) -> Result<()> {
+        let results = join_all(
+            self.syncers
+                .iter()
+                .map(|s| s.write_reorg_status(reorg_event)),
+        )
+        .await;
+        self.check_writes("reorg status", results)
+    }
+
+    /// A reorg flagged on any checkpoint syncer is reported, so that the
+    /// validator stops even if the flag was only written to some of them
+    async fn reorg_status(&self) -> Result<Option<ReorgEvent>> {
+        let results = join_all(self.syncers.iter().map(|s| s.reorg_status())).await;
+        let mut last_err = None;
+        let mut any_ok = false;
+        for (syncer, result) in self.syncers.iter().zip(results) {
+            match result {
+                Ok(Some(reorg_event)) => return Ok(Some(reorg_event)),
+                Ok(None) => any_ok = true,
+                Err(err) => {
+                    warn!(location = %syncer.announcement_location(), ?err, "Failed to read reorg status from checkpoint syncer");
+                    last_err = Some(err);
+                }
+            }
+        }
+        match last_err {
+            Some(err) if !any_ok => Err(err),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{Checkpoint, CheckpointWithMessageId, Signature, H256, U256};
+
+    use super::*;
+    use crate::LocalStorage;
+
+    fn signed_checkpoint(index: u32) -> SignedCheckpointWithMessageId {
+        SignedCheckpointWithMessageId {
+            value: CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: H256::zero(),
+                    mailbox_domain: 1,
+                    root: H256::zero(),
+                    index,
+                },
+                message_id: H256::zero(),
+            },
+            signature: Signature {
+                r: U256::zero(),
+                s: U256::zero(),
+                v: 0,
+            },
+        }
+    }
+
+    /// Local storages in temporary directories, the first of which can't be
+    /// written to because its directory was removed
+    fn local_storages(n: usize) -> (Vec<tempfile::TempDir>, Vec<Arc<dyn CheckpointSyncer>>) {
+        let dirs = (0..n)
+            .map(|_| tempfile::tempdir().unwrap())
+            .collect::<Vec<_>>();
+        let syncers = dirs
+            .iter()
+            .map(|dir| {
+                Arc::new(LocalStorage::new(dir.path().join("checkpoints"), None).unwrap())
+                    as Arc<dyn CheckpointSyncer>
+            })
+            .collect::<Vec<_>>();
+        std::fs::remove_dir_all(dirs[0].path().join("checkpoints")).unwrap();
+        (dirs, syncers)
+    }
+
+    #[tokio::test]
+    async fn test_write_policy() {
+        let (_dirs, syncers) = local_storages(3);
+
+        let all = FanOutCheckpointSyncer::new(syncers.clone(), FanOutWritePolicy::All).unwrap();
+        assert!(all.write_checkpoint(&signed_checkpoint(1)).await.is_err());
+
+        let quorum =
+            FanOutCheckpointSyncer::new(syncers.clone(), FanOutWritePolicy::Quorum(2)).unwrap();
+        quorum
+            .write_checkpoint(&signed_checkpoint(1))
+            .await
+            .unwrap();
+        quorum.write_latest_index(1).await.unwrap();
+        for syncer in &syncers[1..] {
+            assert_eq!(syncer.latest_index().await.unwrap(), Some(1));
+        }
+
+        assert!(FanOutCheckpointSyncer::new(syncers, FanOutWritePolicy::Quorum(4)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reads_fail_over() {
+        let (_dirs, syncers) = local_storages(3);
+        syncers[1].write_latest_index(5).await.unwrap();
+        syncers[2].write_latest_index(7).await.unwrap();
+        syncers[2]
+            .write_checkpoint(&signed_checkpoint(7))
+            .await
+            .unwrap();
+
+        let fan_out = FanOutCheckpointSyncer::new(syncers, FanOutWritePolicy::All).unwrap();
+        assert_eq!(fan_out.latest_index().await.unwrap(), Some(7));
+        assert_eq!(
+            fan_out.fetch_checkpoint(7).await.unwrap(),
+            Some(signed_checkpoint(7))
+        );
+        assert_eq!(fan_out.fetch_checkpoint(8).await.unwrap(), None);
+        assert_eq!(fan_out.announcement_locations().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_missing_checkpoints_are_written_once_syncers_recover() {
+        let (dirs, syncers) = local_storages(3);
+        let fan_out = FanOutCheckpointSyncer::new(syncers.clone(), FanOutWritePolicy::All).unwrap();
+
+        let checkpoint = signed_checkpoint(3);
+        assert!(fan_out.write_missing_checkpoint(&checkpoint).await.is_err());
+        assert_eq!(
+            fan_out.fetch_checkpoint(3).await.unwrap(),
+            Some(checkpoint.clone())
+        );
+        assert_eq!(fan_out.fetch_complete_checkpoint(3).await.unwrap(), None);
+
+        // Once the first syncer recovers, only it is written to
+        std::fs::create_dir_all(dirs[0].path().join("checkpoints")).unwrap();
+        let mut resigned = signed_checkpoint(3);
+        resigned.signature.v = 1;
+        fan_out.write_missing_checkpoint(&resigned).await.unwrap();
+        assert_eq!(
+            syncers[0].fetch_checkpoint(3).await.unwrap(),
+            Some(resigned)
+        );
+        for syncer in &syncers[1..] {
+            assert_eq!(
+                syncer.fetch_checkpoint(3).await.unwrap(),
+                Some(checkpoint.clone())
+            );
+        }
+        assert_eq!(
+            fan_out.fetch_complete_checkpoint(3).await.unwrap(),
+            Some(checkpoint)
+        );
+    }
+}
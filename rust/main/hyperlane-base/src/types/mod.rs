@@ -1,3 +1,6 @@
+mod checkpoint_cache;
+mod fallback_storage;
+mod fan_out_storage;
 mod gcs_storage;
 mod local_storage;
 mod multisig;
@@ -6,6 +9,9 @@ mod s3_storage;
 /// Reusable logic for working with storage backends.
 pub mod utils;
 
+pub use checkpoint_cache::*;
+pub use fallback_storage::*;
+pub use fan_out_storage::*;
 pub use gcs_storage::*;
 pub use local_storage::*;
 pub use multisig::*;
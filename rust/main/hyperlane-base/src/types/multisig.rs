@@ -6,10 +6,11 @@ use eyre::Result;
 use tracing::{debug, instrument};
 
 use hyperlane_core::{
-    HyperlaneDomain, MultisigSignedCheckpoint, SignedCheckpointWithMessageId, H160, H256,
+    HyperlaneDomain, MultisigSignedCheckpoint, SignedCheckpointWithMessageId, WeightedValidator,
+    H160, H256,
 };
 
-use crate::{CheckpointSyncer, CoreMetrics};
+use crate::{CheckpointCache, CheckpointSyncer, CoreMetrics};
 
 /// For a particular validator set, fetches signed checkpoints from multiple
 /// validators to create MultisigSignedCheckpoints.
@@ -19,45 +20,56 @@ pub struct MultisigCheckpointSyncer {
     checkpoint_syncers: HashMap<H160, Arc<dyn CheckpointSyncer>>,
     metrics: Arc<CoreMetrics>,
     app_context: Option<String>,
+    /// The domain the validators sign checkpoints of
+    origin_domain: u32,
+    /// Shared by all multisig checkpoint syncers of the agent
+    cache: Arc<CheckpointCache>,
 }
 
 impl MultisigCheckpointSyncer {
-    /// Gets the latest checkpoint index from each validator's checkpoint syncer.
-    /// Returns a vector of the latest indices, in an unspecified order, and does
-    /// not contain indices for validators that did not provide a latest index.
-    /// Also updates the validator latest checkpoint metrics.
-    pub async fn get_validator_latest_checkpoints_and_update_metrics(
+    /// Gets the latest checkpoint index from the checkpoint syncer of each
+    /// validator that has one. If a validator does not return a latest index,
+    /// None is recorded so this can be surfaced in the metrics.
+    pub async fn get_validator_latest_indices(
         &self,
         validators: &[H256],
-        origin: &HyperlaneDomain,
-        destination: &HyperlaneDomain,
-    ) -> Vec<u32> {
-        // Get the latest_index from each validator's checkpoint syncer.
-        // If a validator does not return a latest index, None is recorded so
-        // this can be surfaced in the metrics.
+    ) -> HashMap<H160, Option<u32>> {
         let mut latest_indices: HashMap<H160, Option<u32>> =
             HashMap::with_capacity(validators.len());
 
         for validator in validators {
             let address = H160::from(*validator);
             if let Some(checkpoint_syncer) = self.checkpoint_syncers.get(&address) {
-                // Gracefully handle errors getting the latest_index
-                match checkpoint_syncer.latest_index().await {
-                    Ok(Some(index)) => {
-                        debug!(?address, ?index, "Validator returned latest index");
-                        latest_indices.insert(H160::from(*validator), Some(index));
-                    }
-                    result => {
-                        debug!(
-                            ?address,
-                            ?result,
-                            "Failed to get latest index from validator"
-                        );
-                        latest_indices.insert(H160::from(*validator), None);
-                    }
-                }
+                // Errors getting the latest_index are handled gracefully by the cache
+                let index = self
+                    .cache
+                    .latest_index(self.origin_domain, address, checkpoint_syncer.as_ref())
+                    .await;
+                debug!(?address, ?index, "Got latest index from validator");
+                latest_indices.insert(address, index);
             }
         }
+        latest_indices
+    }
+
+    /// The storage location of each validator's checkpoint syncer
+    pub fn storage_locations(&self) -> HashMap<H160, String> {
+        self.checkpoint_syncers
+            .iter()
+            .map(|(validator, syncer)| (*validator, syncer.announcement_location()))
+            .collect()
+    }
+
+    /// Gets the latest checkpoint index from each validator's checkpoint syncer,
+    /// like `get_validator_latest_indices`, and updates the validator latest
+    /// checkpoint metrics.
+    pub async fn get_validator_latest_checkpoints_and_update_metrics(
+        &self,
+        validators: &[H256],
+        origin: &HyperlaneDomain,
+        destination: &HyperlaneDomain,
+    ) -> HashMap<H160, Option<u32>> {
+        let latest_indices = self.get_validator_latest_indices(validators).await;
 
         if let Some(app_context) = &self.app_context {
             self.metrics
@@ -71,16 +83,15 @@ impl MultisigCheckpointSyncer {
                 .await;
         }
 
-        // Filter out any validators that did not return a latest index
-        latest_indices.values().copied().flatten().collect()
+        latest_indices
     }
 
     /// Attempts to get the latest checkpoint with a quorum of signatures among
     /// validators.
     ///
     /// First iterates through the `latest_index` of each validator's checkpoint
-    /// syncer, looking for the highest index that >= `threshold` validators
-    /// have returned.
+    /// syncer, looking for the highest index that validators weighing at least
+    /// `threshold_weight` have returned.
     ///
     /// Attempts to find a quorum of signed checkpoints from that index,
     /// iterating backwards if unsuccessful, until the (optional) index is
@@ -90,15 +101,16 @@ impl MultisigCheckpointSyncer {
     #[instrument(err, skip(self))]
     pub async fn fetch_checkpoint_in_range(
         &self,
-        validators: &[H256],
-        threshold: usize,
+        validators: &[WeightedValidator],
+        threshold_weight: u128,
         minimum_index: u32,
         maximum_index: u32,
         origin: &HyperlaneDomain,
         destination: &HyperlaneDomain,
     ) -> Result<Option<MultisigSignedCheckpoint>> {
-        let mut latest_indices = self
-            .get_validator_latest_checkpoints_and_update_metrics(validators, origin, destination)
+        let addresses = validators.iter().map(|v| v.validator).collect::<Vec<_>>();
+        let latest_indices = self
+            .get_validator_latest_checkpoints_and_update_metrics(&addresses, origin, destination)
             .await;
 
         debug!(
@@ -106,15 +118,14 @@ impl MultisigCheckpointSyncer {
             "Fetched latest indices from checkpoint syncers"
         );
 
-        if latest_indices.is_empty() {
+        if latest_indices.values().all(Option::is_none) {
             debug!("No validators returned a latest index");
             return Ok(None);
         }
 
-        // Sort in descending order. The n'th index will represent
-        // the highest index for which we (supposedly) have (n+1) signed checkpoints
-        latest_indices.sort_by(|a, b| b.cmp(a));
-        if let Some(&highest_quorum_index) = latest_indices.get(threshold - 1) {
+        if let Some(highest_quorum_index) =
+            quorum_index(validators, threshold_weight, &latest_indices)
+        {
             // The highest viable checkpoint index is the minimum of the highest index
             // we (supposedly) have a quorum for, and the maximum index for which we can
             // generate a proof.
@@ -124,8 +135,9 @@ impl MultisigCheckpointSyncer {
                 return Ok(None);
             }
             for index in (minimum_index..=start_index).rev() {
-                if let Ok(Some(checkpoint)) =
-                    self.fetch_checkpoint(validators, threshold, index).await
+                if let Ok(Some(checkpoint)) = self
+                    .fetch_checkpoint(validators, threshold_weight, index)
+                    .await
                 {
                     return Ok(Some(checkpoint));
                 }
@@ -135,29 +147,44 @@ impl MultisigCheckpointSyncer {
         Ok(None)
     }
 
-    /// Fetches a MultisigSignedCheckpointWithMessageId if there is a quorum.
-    /// Validators must reflect the onchain ordering of the set
+    /// Fetches a MultisigSignedCheckpointWithMessageId if there is a quorum,
+    /// i.e. signatures from validators weighing at least `threshold_weight`.
+    /// Validators must reflect the onchain ordering of the set, which is the
+    /// order of the returned signatures.
     /// Returns Ok(None) if there is no quorum.
     #[instrument(err, skip(self))]
     pub async fn fetch_checkpoint(
         &self,
-        validators: &[H256],
-        threshold: usize,
+        validators: &[WeightedValidator],
+        threshold_weight: u128,
         index: u32,
     ) -> Result<Option<MultisigSignedCheckpoint>> {
-        // Keeps track of signed validator checkpoints for a particular root.
+        // Keeps track of signed validator checkpoints for a particular root, with
+        // the position of their validator in the set, and their total weight.
         // In practice, it's likely that validators will all sign the same root for a
         // particular index, but we'd like to be robust to this not being the case
-        let mut signed_checkpoints_per_root: HashMap<H256, Vec<SignedCheckpointWithMessageId>> =
-            HashMap::new();
+        let mut signed_checkpoints_per_root: HashMap<
+            H256,
+            (u128, Vec<(usize, SignedCheckpointWithMessageId)>),
+        > = HashMap::new();
 
-        for validator in validators.iter() {
+        // Query the heaviest validators first, so the first quorum found is the
+        // one with the fewest signatures. The sort is stable, so validators of
+        // equal weight, e.g. those of threshold-count multisig ISMs, are
+        // queried in the onchain order.
+        let mut validators_by_weight = validators.iter().enumerate().collect::<Vec<_>>();
+        validators_by_weight.sort_by(|(_, a), (_, b)| b.weight.cmp(&a.weight));
+
+        for (position, WeightedValidator { validator, weight }) in validators_by_weight {
             let addr = H160::from(*validator);
             if let Some(checkpoint_syncer) = self.checkpoint_syncers.get(&addr) {
                 // Gracefully ignore an error fetching the checkpoint from a validator's
                 // checkpoint syncer, which can happen if the validator has not
                 // signed the checkpoint at `index`.
-                if let Ok(Some(signed_checkpoint)) = checkpoint_syncer.fetch_checkpoint(index).await
+                if let Some(signed_checkpoint) = self
+                    .cache
+                    .fetch_checkpoint(self.origin_domain, addr, index, checkpoint_syncer.as_ref())
+                    .await
                 {
                     // If the signed checkpoint is for a different index, ignore it
                     if signed_checkpoint.value.index != index {
@@ -184,8 +211,10 @@ impl MultisigCheckpointSyncer {
 
                     // Push the signed checkpoint into the hashmap
                     let root = signed_checkpoint.value.root;
-                    let signed_checkpoints = signed_checkpoints_per_root.entry(root).or_default();
-                    signed_checkpoints.push(signed_checkpoint);
+                    let (signed_weight, signed_checkpoints) =
+                        signed_checkpoints_per_root.entry(root).or_default();
+                    signed_checkpoints.push((position, signed_checkpoint));
+                    *signed_weight = signed_weight.saturating_add(*weight);
 
                     // Count the number of signatures for this signed checkpoint
                     let signature_count = signed_checkpoints.len();
@@ -194,12 +223,19 @@ impl MultisigCheckpointSyncer {
                         index = index,
                         root = format!("{:#x}", root),
                         signature_count = signature_count,
+                        signed_weight = %signed_weight,
                         "Found signed checkpoint"
                     );
 
                     // If we've hit a quorum, create a MultisigSignedCheckpoint
-                    if signature_count >= threshold {
-                        let checkpoint: MultisigSignedCheckpoint = signed_checkpoints.try_into()?;
+                    if *signed_weight >= threshold_weight {
+                        // Signatures must be ordered like the validators onchain
+                        signed_checkpoints.sort_by_key(|(position, _)| *position);
+                        let checkpoint: MultisigSignedCheckpoint = (&mut signed_checkpoints
+                            .iter()
+                            .map(|(_, signed_checkpoint)| signed_checkpoint.clone())
+                            .collect::<Vec<_>>())
+                            .try_into()?;
                         debug!(checkpoint=?checkpoint, "Fetched multisig checkpoint");
                         return Ok(Some(checkpoint));
                     }
@@ -219,3 +255,71 @@ impl MultisigCheckpointSyncer {
         Ok(None)
     }
 }
+
+/// Returns the highest checkpoint index that validators weighing at least
+/// `threshold_weight` (supposedly) signed, given the latest index each
+/// validator signed.
+pub fn quorum_index(
+    validators: &[WeightedValidator],
+    threshold_weight: u128,
+    latest_indices: &HashMap<H160, Option<u32>>,
+) -> Option<u32> {
+    let mut signed = validators
+        .iter()
+        .filter_map(|v| {
+            let latest_index = latest_indices.get(&H160::from(v.validator)).copied();
+            latest_index.flatten().map(|index| (index, v.weight))
+        })
+        .collect::<Vec<_>>();
+    // Sort in descending order. The n'th index is (supposedly) signed by the
+    // first (n+1) validators
+    signed.sort_by(|(a, _), (b, _)| b.cmp(a));
+    let mut signed_weight = 0u128;
+    for (index, weight) in signed {
+        signed_weight = signed_weight.saturating_add(weight);
+        if signed_weight >= threshold_weight {
+            return Some(index);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn validator(i: u64, weight: u128) -> WeightedValidator {
+        WeightedValidator {
+            validator: H256::from_low_u64_be(i),
+            weight,
+        }
+    }
+
+    #[test]
+    fn test_quorum_index() {
+        let latest_indices = HashMap::from([
+            (H160::from_low_u64_be(1), Some(10)),
+            (H160::from_low_u64_be(2), Some(20)),
+            (H160::from_low_u64_be(3), None),
+            (H160::from_low_u64_be(4), Some(30)),
+        ]);
+
+        let unit_weights =
+            WeightedValidator::with_unit_weights(&[1, 2, 3, 4].map(H256::from_low_u64_be));
+        assert_eq!(quorum_index(&unit_weights, 1, &latest_indices), Some(30));
+        assert_eq!(quorum_index(&unit_weights, 2, &latest_indices), Some(20));
+        assert_eq!(quorum_index(&unit_weights, 3, &latest_indices), Some(10));
+        assert_eq!(quorum_index(&unit_weights, 4, &latest_indices), None);
+
+        // A heavy validator is enough to reach a quorum on its own
+        let weighted = [
+            validator(1, 10),
+            validator(2, 60),
+            validator(3, 20),
+            validator(4, 10),
+        ];
+        assert_eq!(quorum_index(&weighted, 60, &latest_indices), Some(20));
+        assert_eq!(quorum_index(&weighted, 80, &latest_indices), Some(10));
+        assert_eq!(quorum_index(&weighted, 90, &latest_indices), None);
+    }
+}
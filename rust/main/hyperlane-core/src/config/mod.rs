@@ -10,7 +10,7 @@ use eyre::Report;
 pub use str_or_int::{StrOrInt, StrOrIntParseError};
 pub use trait_ext::*;
 
-use crate::H256;
+use crate::{H256, U256};
 
 mod config_path;
 mod str_or_int;
@@ -29,6 +29,9 @@ pub struct OperationBatchConfig {
     pub batch_contract_address: Option<H256>,
     /// Batch size
     pub max_batch_size: u32,
+    /// Max estimated gas of the operations in a batch, which should leave room
+    /// in the block gas limit of the chain. Unlimited if not set.
+    pub max_batch_gas: Option<U256>,
 }
 
 /// A trait that allows for constructing `Self` from a raw config type.
@@ -41,6 +41,12 @@ pub enum ModuleType {
     Null,
     /// Ccip Read ISM (accepts offchain signature information)
     CcipRead,
+    /// Arbitrum L2 to L1 ISM (verifies messages bridged through Arbitrum)
+    ArbL2ToL1,
+    /// Weighted Merkle Proof ISM (a threshold weight of validator signatures)
+    WeightedMerkleRootMultisig,
+    /// Weighted Message ID ISM (a threshold weight of validator signatures)
+    WeightedMessageIdMultisig,
 }
 
 /// Interface for the InterchainSecurityModule chain contract. Allows abstraction over
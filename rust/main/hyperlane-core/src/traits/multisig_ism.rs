@@ -5,6 +5,29 @@ use auto_impl::auto_impl;
 
 use crate::{ChainResult, HyperlaneContract, HyperlaneMessage, H256};
 
+/// A validator of a multisig ISM and the weight of its signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeightedValidator {
+    /// The address of the validator
+    pub validator: H256,
+    /// The weight of the validator's signature
+    pub weight: u128,
+}
+
+impl WeightedValidator {
+    /// Gives each validator a weight of 1, like threshold-count multisig ISMs
+    /// implicitly do
+    pub fn with_unit_weights(validators: &[H256]) -> Vec<Self> {
+        validators
+            .iter()
+            .map(|&validator| Self {
+                validator,
+                weight: 1,
+            })
+            .collect()
+    }
+}
+
 /// Interface for the MultisigIsm chain contract. Allows abstraction over
 /// different chains
 #[async_trait]
@@ -15,4 +38,19 @@ pub trait MultisigIsm: HyperlaneContract + Send + Sync + Debug {
         &self,
         message: &HyperlaneMessage,
     ) -> ChainResult<(Vec<H256>, u8)>;
+
+    /// Returns the validators with the weight of their signatures, and the
+    /// total weight of signatures needed to verify message. Weighted multisig
+    /// ISMs must override this; for the others, each validator weighs 1 and
+    /// the threshold weight is the threshold.
+    async fn validators_and_threshold_weight(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<WeightedValidator>, u128)> {
+        let (validators, threshold) = self.validators_and_threshold(message).await?;
+        Ok((
+            WeightedValidator::with_unit_weights(&validators),
+            threshold.into(),
+        ))
+    }
 }
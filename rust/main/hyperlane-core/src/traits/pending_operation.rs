@@ -52,6 +52,19 @@ pub trait PendingOperation: Send + Sync + Debug + TryBatchAs<HyperlaneMessage> {
     /// operations when neither of them have a `next_attempt_after`
     fn priority(&self) -> u32;
 
+    /// The lane this operation is served from by queues that prioritize some
+    /// operations over others.
+    fn lane(&self) -> OperationLane {
+        OperationLane::Standard
+    }
+
+    /// Whether this operation was requested to be relayed ahead of the
+    /// others, e.g. through the relayer API. Expedited operations come first
+    /// among the ones that can be attempted right away.
+    fn is_expedited(&self) -> bool {
+        false
+    }
+
     /// The domain this originates from.
     fn origin_domain_id(&self) -> u32;
 
@@ -67,6 +80,9 @@ pub trait PendingOperation: Send + Sync + Debug + TryBatchAs<HyperlaneMessage> {
     /// The recipient address of this operation.
     fn recipient_address(&self) -> &H256;
 
+    /// The body of the message this operation relays.
+    fn body(&self) -> &[u8];
+
     /// Label to use for metrics granularity.
     fn app_context(&self) -> Option<String>;
 
@@ -160,6 +176,21 @@ pub trait PendingOperation: Send + Sync + Debug + TryBatchAs<HyperlaneMessage> {
     fn try_get_mailbox(&self) -> Option<Arc<dyn Mailbox>> {
         None
     }
+
+    /// Submit this operation through `mailbox` instead of the default one,
+    /// e.g. to send it from one of several signers of the destination
+    fn set_submission_mailbox(&mut self, _mailbox: Arc<dyn Mailbox>) {}
+
+    /// Persist the state of this operation and the queue it is in, so it can
+    /// be restored in that queue after a restart. Operations that aren't
+    /// persisted start over from the prepare queue.
+    fn persist_queue_snapshot(&self, _queue: SubmitterQueue) {}
+
+    /// The queue this operation was in when it was persisted, if it was
+    /// restored from a snapshot.
+    fn restored_queue(&self) -> Option<SubmitterQueue> {
+        None
+    }
 }
 
 #[derive(Debug, Display, Clone, Serialize, Deserialize, PartialEq)]
@@ -250,6 +281,9 @@ pub enum ReprepareReason {
     #[strum(to_string = "Delivery transaction reverted or reorged")]
     /// Delivery transaction reverted or reorged
     RevertedOrReorged,
+    #[strum(to_string = "Rate limit exceeded")]
+    /// The rate limit of the message sender or app was exceeded
+    RateLimited,
 }
 
 #[derive(Display, Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -269,6 +303,28 @@ pub enum ConfirmReason {
     ErrorRecordingProcessSuccess,
 }
 
+/// The queues of a submitter that operations move through
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SubmitterQueue {
+    /// Operations waiting to be prepared
+    Prepare,
+    /// Prepared operations waiting to be submitted
+    Submit,
+    /// Submitted operations waiting for their delivery to be confirmed
+    Confirm,
+}
+
+/// The lanes of a queue that serves some operations before others
+#[derive(Display, Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OperationLane {
+    /// Served before standard operations, e.g. because the operation paid
+    /// more than needed for gas
+    Premium,
+    /// Served in the order of the queue
+    #[default]
+    Standard,
+}
+
 /// Utility fn to calculate the total estimated cost of an operation batch
 pub fn total_estimated_cost(ops: &[Box<dyn PendingOperation>]) -> U256 {
     ops.iter()
@@ -334,15 +390,19 @@ impl Ord for QueueOperation {
             // No time means it should come before
             (None, Some(_)) => Less,
             (Some(_), None) => Greater,
-            (None, None) => {
-                if self.origin_domain_id() == other.origin_domain_id() {
-                    // Should execute in order of nonce for the same origin
-                    self.priority().cmp(&other.priority())
-                } else {
-                    // There is no priority between these messages, so arbitrarily use the id
-                    self.id().cmp(&other.id())
-                }
-            }
+            // Expedited operations come first, then the rest in order
+            (None, None) => other
+                .is_expedited()
+                .cmp(&self.is_expedited())
+                .then_with(|| {
+                    if self.origin_domain_id() == other.origin_domain_id() {
+                        // Should execute in order of nonce for the same origin
+                        self.priority().cmp(&other.priority())
+                    } else {
+                        // There is no priority between these messages, so arbitrarily use the id
+                        self.id().cmp(&other.id())
+                    }
+                }),
         }
     }
 }
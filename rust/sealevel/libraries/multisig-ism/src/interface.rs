@@ -8,6 +8,10 @@ use spl_type_length_value::discriminator::Discriminator;
 pub enum MultisigIsmInstruction {
     /// Gets the validators and threshold for the provided message.
     ValidatorsAndThreshold(Vec<u8>),
+    /// Gets the validators with their weights and the threshold weight for
+    /// the provided message. Only supported by weighted multisig ISMs.
+    /// Expects the same accounts as `ValidatorsAndThreshold`.
+    ValidatorsAndThresholdWeight(Vec<u8>),
     /// Gets the account metas required for an instruction to the
     /// `ValidatorsAndThreshold` program.
     /// Intended to be simulated by an off-chain client.
@@ -22,6 +26,12 @@ const VALIDATORS_AND_THRESHOLD_DISCRIMINATOR: [u8; Discriminator::LENGTH] =
     [82, 96, 5, 220, 241, 173, 13, 50];
 const VALIDATORS_AND_THRESHOLD_DISCRIMINATOR_SLICE: &[u8] = &VALIDATORS_AND_THRESHOLD_DISCRIMINATOR;
 
+/// First 8 bytes of `hash::hashv(&[b"hyperlane-multisig-ism:validators-and-threshold-weight"])`
+const VALIDATORS_AND_THRESHOLD_WEIGHT_DISCRIMINATOR: [u8; Discriminator::LENGTH] =
+    [237, 191, 221, 72, 49, 134, 153, 38];
+const VALIDATORS_AND_THRESHOLD_WEIGHT_DISCRIMINATOR_SLICE: &[u8] =
+    &VALIDATORS_AND_THRESHOLD_WEIGHT_DISCRIMINATOR;
+
 const VALIDATORS_AND_THRESHOLD_ACCOUNT_METAS_DISCRIMINATOR: [u8; Discriminator::LENGTH] =
     [113, 7, 132, 85, 239, 247, 157, 204];
 const VALIDATORS_AND_THRESHOLD_ACCOUNT_METAS_DISCRIMINATOR_SLICE: &[u8] =
@@ -45,6 +55,10 @@ impl MultisigIsmInstruction {
                 buf.extend_from_slice(VALIDATORS_AND_THRESHOLD_DISCRIMINATOR_SLICE);
                 buf.extend_from_slice(&message[..]);
             }
+            MultisigIsmInstruction::ValidatorsAndThresholdWeight(message) => {
+                buf.extend_from_slice(VALIDATORS_AND_THRESHOLD_WEIGHT_DISCRIMINATOR_SLICE);
+                buf.extend_from_slice(&message[..]);
+            }
             MultisigIsmInstruction::ValidatorsAndThresholdAccountMetas(message) => {
                 buf.extend_from_slice(VALIDATORS_AND_THRESHOLD_ACCOUNT_METAS_DISCRIMINATOR_SLICE);
                 buf.extend_from_slice(&message[..]);
@@ -64,6 +78,10 @@ impl MultisigIsmInstruction {
                 let message = rest.to_vec();
                 Ok(Self::ValidatorsAndThreshold(message))
             }
+            VALIDATORS_AND_THRESHOLD_WEIGHT_DISCRIMINATOR_SLICE => {
+                let message = rest.to_vec();
+                Ok(Self::ValidatorsAndThresholdWeight(message))
+            }
             VALIDATORS_AND_THRESHOLD_ACCOUNT_METAS_DISCRIMINATOR_SLICE => {
                 let message = rest.to_vec();
                 Ok(Self::ValidatorsAndThresholdAccountMetas(message))
@@ -86,6 +104,12 @@ mod test {
             VALIDATORS_AND_THRESHOLD_DISCRIMINATOR_SLICE,
         );
 
+        assert_eq!(
+            &hashv(&[b"hyperlane-multisig-ism:validators-and-threshold-weight"]).to_bytes()
+                [..Discriminator::LENGTH],
+            VALIDATORS_AND_THRESHOLD_WEIGHT_DISCRIMINATOR_SLICE,
+        );
+
         assert_eq!(
             &hashv(&[b"hyperlane-multisig-ism:validators-and-threshold-account-metas"]).to_bytes()
                 [..Discriminator::LENGTH],
@@ -107,6 +131,20 @@ mod test {
         assert_eq!(instruction, decoded);
     }
 
+    #[test]
+    fn test_encode_decode_validators_and_threshold_weight_instruction() {
+        let instruction = MultisigIsmInstruction::ValidatorsAndThresholdWeight(vec![1, 2, 3, 4, 5]);
+
+        let encoded = instruction.encode().unwrap();
+        assert_eq!(
+            &encoded[..Discriminator::LENGTH],
+            VALIDATORS_AND_THRESHOLD_WEIGHT_DISCRIMINATOR_SLICE,
+        );
+
+        let decoded = MultisigIsmInstruction::decode(&encoded).unwrap();
+        assert_eq!(instruction, decoded);
+    }
+
     #[test]
     fn test_encode_decode_validators_and_threshold_account_metas_instruction() {
         let instruction =
@@ -3,5 +3,7 @@ pub mod interface;
 pub mod multisig;
 #[cfg(feature = "test-data")]
 pub mod test_data;
+pub mod weighted;
 
 pub use crate::multisig::MultisigIsm;
+pub use crate::weighted::WeightedMultisigIsm;
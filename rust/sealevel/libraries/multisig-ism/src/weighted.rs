@@ -0,0 +1,182 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use ecdsa_signature::EcdsaSignature;
+use hyperlane_core::{Signable, H160};
+
+use crate::error::MultisigIsmError;
+
+/// A validator and the weight of its signature.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Eq, Default, Clone, Copy)]
+pub struct WeightedValidator {
+    pub validator: H160,
+    pub weight: u64,
+}
+
+/// A configuration of a weighted validator set and the total weight of
+/// signatures required to verify a message.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Eq, Default, Clone)]
+pub struct ValidatorsAndThresholdWeight {
+    pub validators: Vec<WeightedValidator>,
+    pub threshold_weight: u64,
+}
+
+/// A type for verifying that validators with a total weight of at least the
+/// threshold weight signed over a signable data type.
+pub struct WeightedMultisigIsm<T: Signable> {
+    signed_data: T,
+    signatures: Vec<EcdsaSignature>,
+    validators: Vec<WeightedValidator>,
+    threshold_weight: u64,
+}
+
+impl<T: Signable> WeightedMultisigIsm<T> {
+    pub fn new(
+        signed_data: T,
+        signatures: Vec<EcdsaSignature>,
+        validators: Vec<WeightedValidator>,
+        threshold_weight: u64,
+    ) -> Self {
+        Self {
+            signed_data,
+            signatures,
+            validators,
+            threshold_weight,
+        }
+    }
+
+    /// Returns Ok(()) if the validators that signed the signed data have a
+    /// total weight of at least the threshold weight.
+    /// Requires the signatures over the signed data to be ordered by the `this.validators`
+    /// ordering.
+    /// Returns an error if the threshold weight is not met or if any of the signatures
+    /// needed to meet it are invalid.
+    pub fn verify(&self) -> Result<(), MultisigIsmError> {
+        let signed_digest = self.signed_data.eth_signed_message_hash();
+        let signed_digest_bytes = signed_digest.as_bytes();
+
+        let validator_count = self.validators.len();
+        let mut validator_index = 0;
+        let mut signed_weight: u64 = 0;
+
+        // Assumes that signatures are ordered by validator
+        for signature in &self.signatures {
+            if signed_weight >= self.threshold_weight {
+                break;
+            }
+
+            let signer = signature
+                .secp256k1_recover_ethereum_address(signed_digest_bytes)
+                .map_err(|_| MultisigIsmError::InvalidSignature)?;
+
+            while validator_index < validator_count
+                && signer != self.validators[validator_index].validator
+            {
+                validator_index += 1;
+            }
+
+            if validator_index >= validator_count {
+                return Err(MultisigIsmError::ThresholdNotMet);
+            }
+
+            signed_weight = signed_weight.saturating_add(self.validators[validator_index].weight);
+            validator_index += 1;
+        }
+
+        if signed_weight < self.threshold_weight {
+            return Err(MultisigIsmError::ThresholdNotMet);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::str::FromStr;
+
+    use hyperlane_core::H256;
+
+    struct TestSignedPayload();
+
+    impl Signable for TestSignedPayload {
+        fn signing_hash(&self) -> H256 {
+            H256::from_str("0xf00000000000000000000000000000000000000000000000000000000000000f")
+                .unwrap()
+        }
+    }
+
+    // See the tests of `MultisigIsm` for how these signatures were generated.
+    fn validators_and_signatures() -> (Vec<H160>, Vec<EcdsaSignature>) {
+        let validator_0 = H160::from_str("0xfdB65576568b99A8a00a292577b8fc51abB115bD").unwrap();
+        let signature_0 = EcdsaSignature::from_bytes(
+            &hex::decode("4e561dcd350b7a271c7247843f7731a8a9810037c13784f5b3a9616788ca536976c5ff70b1865c4568e273a375851a5304dc7a1ac54f0783f3dde38d345313a91c").unwrap()[..]
+        ).unwrap();
+
+        let validator_1 = H160::from_str("0x5090cEd8BC5A7D3c2FbE2b2702eE4a8e7b227181").unwrap();
+        let signature_1 = EcdsaSignature::from_bytes(
+            &hex::decode("9d510e0d988e44cf05a4e29d7b1ecec6e3277a8be137164f89d6cf52325190f058101ef9aa57d118f9452a38c156efbdb1b69d4022ac2c35370c433ca5b61aeb1c").unwrap()[..]
+        ).unwrap();
+
+        (
+            vec![validator_0, validator_1],
+            vec![signature_0, signature_1],
+        )
+    }
+
+    fn weighted(validators: &[H160], weights: &[u64]) -> Vec<WeightedValidator> {
+        validators
+            .iter()
+            .zip(weights)
+            .map(|(&validator, &weight)| WeightedValidator { validator, weight })
+            .collect()
+    }
+
+    #[test]
+    fn test_weighted_multisig_ism_verify_success() {
+        let (validators, signatures) = validators_and_signatures();
+
+        // Both signatures are needed
+        let ism = WeightedMultisigIsm::new(
+            TestSignedPayload(),
+            signatures,
+            weighted(&validators, &[40, 60]),
+            100,
+        );
+        assert!(ism.verify().is_ok());
+
+        // The signature of the heavier validator is enough
+        let (_, signatures) = validators_and_signatures();
+        let ism = WeightedMultisigIsm::new(
+            TestSignedPayload(),
+            signatures.into_iter().skip(1).collect(),
+            weighted(&validators, &[40, 60]),
+            60,
+        );
+        assert!(ism.verify().is_ok());
+    }
+
+    #[test]
+    fn test_weighted_multisig_ism_verify_threshold_weight_not_met() {
+        let (validators, signatures) = validators_and_signatures();
+
+        let ism = WeightedMultisigIsm::new(
+            TestSignedPayload(),
+            signatures.into_iter().take(1).collect(),
+            weighted(&validators, &[40, 60]),
+            60,
+        );
+        assert_eq!(ism.verify().unwrap_err(), MultisigIsmError::ThresholdNotMet);
+
+        // Sigs out of order
+        let (_, mut signatures) = validators_and_signatures();
+        signatures.swap(0, 1);
+        let ism = WeightedMultisigIsm::new(
+            TestSignedPayload(),
+            signatures,
+            weighted(&validators, &[40, 60]),
+            100,
+        );
+        assert_eq!(ism.verify().unwrap_err(), MultisigIsmError::ThresholdNotMet);
+    }
+}
@@ -0,0 +1,19 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use account_utils::AccountData;
+use multisig_ism::weighted::ValidatorsAndThresholdWeight;
+
+/// The data of a "domain data" PDA account.
+/// One of these exists for each domain that's been enrolled.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, PartialEq)]
+pub struct DomainData {
+    pub bump_seed: u8,
+    pub validators_and_threshold_weight: ValidatorsAndThresholdWeight,
+}
+
+pub type DomainDataAccount = AccountData<DomainData>;
+
+// The access control data is the same as the one of `multisig-ism-message-id`.
+pub use hyperlane_sealevel_multisig_ism_message_id::accounts::{
+    AccessControlAccount, AccessControlData,
+};
@@ -0,0 +1,195 @@
+use account_utils::{DiscriminatorData, DiscriminatorEncode, PROGRAM_INSTRUCTION_DISCRIMINATOR};
+use borsh::{BorshDeserialize, BorshSerialize};
+use hyperlane_sealevel_multisig_ism_message_id::{error::Error, instruction::Domained};
+use multisig_ism::weighted::ValidatorsAndThresholdWeight;
+use solana_program::{
+    instruction::{AccountMeta, Instruction as SolanaInstruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+
+use std::collections::HashSet;
+
+use crate::{access_control_pda_seeds, domain_data_pda_seeds};
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub enum Instruction {
+    /// Initializes the program.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` The new owner and payer of the access control PDA.
+    /// 1. `[writable]` The access control PDA account.
+    /// 2. `[executable]` The system program account.
+    Initialize,
+    /// Input: domain ID, weighted validators, & threshold weight to set.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` The access control owner and payer of the domain PDA.
+    /// 1. `[]` The access control PDA account.
+    /// 2. `[writable]` The PDA relating to the provided domain.
+    /// 3. `[executable]` OPTIONAL - The system program account. Required if creating the domain PDA.
+    SetValidatorsAndThresholdWeight(Domained<ValidatorsAndThresholdWeight>),
+    /// Gets the owner from the access control data.
+    ///
+    /// Accounts:
+    /// 0. `[]` The access control PDA account.
+    GetOwner,
+    /// Sets the owner in the access control data.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` The current access control owner.
+    /// 1. `[]` The access control PDA account.
+    TransferOwnership(Option<Pubkey>),
+}
+
+impl DiscriminatorData for Instruction {
+    const DISCRIMINATOR: [u8; Self::DISCRIMINATOR_LENGTH] = PROGRAM_INSTRUCTION_DISCRIMINATOR;
+}
+
+impl TryFrom<&[u8]> for Instruction {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::try_from_slice(data).map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
+
+/// Validates the weighted validator set and threshold weight.
+/// Returns an error if the set is empty, any weight or the threshold weight is zero, the
+/// threshold weight exceeds the total weight of the validators, or if the validator set has
+/// any duplicates.
+pub fn validate(config: &ValidatorsAndThresholdWeight) -> Result<(), ProgramError> {
+    let validators_len = config.validators.len();
+
+    let mut total_weight: u64 = 0;
+    let mut set = HashSet::with_capacity(validators_len);
+    for validator in &config.validators {
+        // If the set has any duplicates or weightless validators, error.
+        if validator.weight == 0 || !set.insert(validator.validator) {
+            return Err(Error::InvalidValidatorsAndThreshold.into());
+        }
+        total_weight = total_weight
+            .checked_add(validator.weight)
+            .ok_or(Error::InvalidValidatorsAndThreshold)?;
+    }
+
+    // Ensure the threshold weight is non-zero and reachable.
+    if config.threshold_weight == 0 || config.threshold_weight > total_weight {
+        return Err(Error::InvalidValidatorsAndThreshold.into());
+    }
+
+    Ok(())
+}
+
+/// Creates an Initialize instruction.
+pub fn init_instruction(
+    program_id: Pubkey,
+    payer: Pubkey,
+) -> Result<SolanaInstruction, ProgramError> {
+    let (access_control_pda_key, _access_control_pda_bump) =
+        Pubkey::try_find_program_address(access_control_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+
+    // Accounts:
+    // 0. `[signer]` The new owner and payer of the access control PDA.
+    // 1. `[writable]` The access control PDA account.
+    // 2. `[executable]` The system program account.
+    let accounts = vec![
+        AccountMeta::new(payer, true),
+        AccountMeta::new(access_control_pda_key, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(SolanaInstruction {
+        program_id,
+        data: Instruction::Initialize.encode()?,
+        accounts,
+    })
+}
+
+/// Creates a SetValidatorsAndThresholdWeight instruction.
+pub fn set_validators_and_threshold_weight_instruction(
+    program_id: Pubkey,
+    owner_payer: Pubkey,
+    domain: u32,
+    validators_and_threshold_weight: ValidatorsAndThresholdWeight,
+) -> Result<SolanaInstruction, ProgramError> {
+    let (access_control_pda_key, _access_control_pda_bump) =
+        Pubkey::find_program_address(access_control_pda_seeds!(), &program_id);
+
+    let (domain_data_pda_key, _domain_data_pda_bump) =
+        Pubkey::find_program_address(domain_data_pda_seeds!(domain), &program_id);
+
+    let ixn = Instruction::SetValidatorsAndThresholdWeight(Domained {
+        domain,
+        data: validators_and_threshold_weight,
+    });
+
+    // Accounts:
+    // 0. `[signer]` The access control owner and payer of the domain PDA.
+    // 1. `[]` The access control PDA account.
+    // 2. `[writable]` The PDA relating to the provided domain.
+    // 3. `[executable]` OPTIONAL - The system program account. Required if creating the domain PDA.
+    let accounts = vec![
+        AccountMeta::new(owner_payer, true),
+        AccountMeta::new_readonly(access_control_pda_key, false),
+        AccountMeta::new(domain_data_pda_key, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(SolanaInstruction {
+        program_id,
+        data: ixn.encode()?,
+        accounts,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use hyperlane_core::H160;
+    use multisig_ism::weighted::WeightedValidator;
+
+    fn config(weights: &[u64], threshold_weight: u64) -> ValidatorsAndThresholdWeight {
+        ValidatorsAndThresholdWeight {
+            validators: weights
+                .iter()
+                .map(|&weight| WeightedValidator {
+                    validator: H160::random(),
+                    weight,
+                })
+                .collect(),
+            threshold_weight,
+        }
+    }
+
+    #[test]
+    fn test_validate_success() {
+        assert!(validate(&config(&[30, 70], 60)).is_ok());
+        // Threshold weight equals the total weight
+        assert!(validate(&config(&[30, 70], 100)).is_ok());
+    }
+
+    #[test]
+    fn test_validate_errors() {
+        let invalid: ProgramError = Error::InvalidValidatorsAndThreshold.into();
+
+        // Threshold weight 0 and validators empty
+        assert_eq!(validate(&config(&[], 0)).unwrap_err(), invalid);
+        // Threshold weight 0 and validators not empty
+        assert_eq!(validate(&config(&[30], 0)).unwrap_err(), invalid);
+        // Threshold weight exceeds the total weight
+        assert_eq!(validate(&config(&[30, 70], 101)).unwrap_err(), invalid);
+        // Weightless validator
+        assert_eq!(validate(&config(&[0, 70], 70)).unwrap_err(), invalid);
+        // Total weight overflows
+        assert_eq!(validate(&config(&[u64::MAX, 1], 1)).unwrap_err(), invalid);
+
+        // Validator set has duplicates
+        let mut duplicates = config(&[30, 70], 60);
+        duplicates.validators[1].validator = duplicates.validators[0].validator;
+        assert_eq!(validate(&duplicates).unwrap_err(), invalid);
+    }
+}
@@ -0,0 +1,12 @@
+//! A multisig Interchain Security Module like `multisig-ism-message-id`,
+//! except that each validator's signature carries a weight, and signatures
+//! from validators with a total weight of at least the threshold weight are
+//! required.
+//! No merkle proofs.
+
+#![deny(warnings)]
+#![deny(unsafe_code)]
+
+pub mod accounts;
+pub mod instruction;
+pub mod processor;
@@ -0,0 +1,645 @@
+//! Contains functional tests for things that cannot be done
+//! strictly in unit tests. This includes CPIs, like creating
+//! new PDA accounts.
+
+use account_utils::DiscriminatorEncode;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+};
+
+use ecdsa_signature::EcdsaSignature;
+use hyperlane_core::{Encode, HyperlaneMessage, ModuleType, H160, H256};
+use hyperlane_sealevel_interchain_security_module_interface::{
+    InterchainSecurityModuleInstruction, VerifyInstruction, VERIFY_ACCOUNT_METAS_PDA_SEEDS,
+};
+use hyperlane_sealevel_multisig_ism_message_id::{
+    error::Error as MultisigIsmError, metadata::MultisigIsmMessageIdMetadata,
+};
+use hyperlane_sealevel_weighted_multisig_ism_message_id::{
+    access_control_pda_seeds,
+    accounts::{AccessControlAccount, AccessControlData, DomainData, DomainDataAccount},
+    domain_data_pda_seeds,
+    instruction::{
+        init_instruction, set_validators_and_threshold_weight_instruction,
+        Instruction as WeightedMultisigIsmProgramInstruction,
+    },
+    processor::process_instruction,
+};
+use hyperlane_test_utils::{
+    assert_transaction_error, get_account_metas, new_funded_keypair,
+    process_instruction as process_test_instruction, simulate_instruction,
+};
+use multisig_ism::{
+    interface::{MultisigIsmInstruction, VALIDATORS_AND_THRESHOLD_ACCOUNT_METAS_PDA_SEEDS},
+    test_data::{get_multisig_ism_test_data, MultisigIsmTestData},
+    weighted::{ValidatorsAndThresholdWeight, WeightedValidator},
+};
+use serializable_account_meta::SimulationReturnData;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError, signature::Signer, signer::keypair::Keypair,
+    transaction::TransactionError,
+};
+
+const ONE_SOL_IN_LAMPORTS: u64 = 1000000000;
+
+pub fn weighted_multisig_ism_message_id_id() -> Pubkey {
+    pubkey!("2YjtZDiUoptoSsA5eVrDCcX6wxNK6YoEVW7y82x5Z2fw")
+}
+
+async fn setup_client() -> (BanksClient, Keypair) {
+    let program_id = weighted_multisig_ism_message_id_id();
+    let (banks_client, payer, _recent_blockhash) = ProgramTest::new(
+        "hyperlane_sealevel_weighted_multisig_ism_message_id",
+        program_id,
+        processor!(process_instruction),
+    )
+    .start()
+    .await;
+
+    (banks_client, payer)
+}
+
+async fn initialize(
+    program_id: Pubkey,
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+) -> Result<(Pubkey, u8), BanksClientError> {
+    process_test_instruction(
+        banks_client,
+        init_instruction(program_id, payer.pubkey()).unwrap(),
+        payer,
+        &[payer],
+    )
+    .await?;
+
+    Ok(Pubkey::find_program_address(
+        access_control_pda_seeds!(),
+        &program_id,
+    ))
+}
+
+async fn set_validators_and_threshold_weight(
+    program_id: Pubkey,
+    banks_client: &mut BanksClient,
+    owner: &Keypair,
+    domain: u32,
+    validators_and_threshold_weight: ValidatorsAndThresholdWeight,
+) -> Result<(Pubkey, u8), BanksClientError> {
+    process_test_instruction(
+        banks_client,
+        set_validators_and_threshold_weight_instruction(
+            program_id,
+            owner.pubkey(),
+            domain,
+            validators_and_threshold_weight,
+        )
+        .unwrap(),
+        owner,
+        &[owner],
+    )
+    .await?;
+
+    Ok(Pubkey::find_program_address(
+        domain_data_pda_seeds!(domain),
+        &program_id,
+    ))
+}
+
+async fn transfer_ownership(
+    program_id: Pubkey,
+    banks_client: &mut BanksClient,
+    owner: &Keypair,
+    new_owner: Option<Pubkey>,
+) -> Result<(), BanksClientError> {
+    let (access_control_pda_key, _) =
+        Pubkey::find_program_address(access_control_pda_seeds!(), &program_id);
+
+    process_test_instruction(
+        banks_client,
+        Instruction::new_with_bytes(
+            program_id,
+            &WeightedMultisigIsmProgramInstruction::TransferOwnership(new_owner)
+                .encode()
+                .unwrap(),
+            vec![
+                AccountMeta::new_readonly(owner.pubkey(), true),
+                AccountMeta::new(access_control_pda_key, false),
+            ],
+        ),
+        owner,
+        &[owner],
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn access_control_data(
+    banks_client: &mut BanksClient,
+    access_control_pda_key: Pubkey,
+) -> Box<AccessControlData> {
+    let access_control_account_data = banks_client
+        .get_account(access_control_pda_key)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    AccessControlAccount::fetch_data(&mut &access_control_account_data[..])
+        .unwrap()
+        .unwrap()
+}
+
+async fn domain_data(
+    banks_client: &mut BanksClient,
+    domain_data_pda_key: Pubkey,
+) -> Box<DomainData> {
+    let domain_data_account_data = banks_client
+        .get_account(domain_data_pda_key)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    DomainDataAccount::fetch_data(&mut &domain_data_account_data[..])
+        .unwrap()
+        .unwrap()
+}
+
+fn random_validators_and_threshold_weight(
+    weights: &[u64],
+    threshold_weight: u64,
+) -> ValidatorsAndThresholdWeight {
+    ValidatorsAndThresholdWeight {
+        validators: weights
+            .iter()
+            .map(|&weight| WeightedValidator {
+                validator: H160::random(),
+                weight,
+            })
+            .collect(),
+        threshold_weight,
+    }
+}
+
+#[tokio::test]
+async fn test_initialize() {
+    let program_id = weighted_multisig_ism_message_id_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    let (access_control_pda_key, access_control_pda_bump_seed) =
+        initialize(program_id, &mut banks_client, &payer)
+            .await
+            .unwrap();
+
+    assert_eq!(
+        access_control_data(&mut banks_client, access_control_pda_key).await,
+        Box::new(AccessControlData {
+            bump_seed: access_control_pda_bump_seed,
+            owner: Some(payer.pubkey()),
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_errors_if_called_twice() {
+    let program_id = weighted_multisig_ism_message_id_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(program_id, &mut banks_client, &payer)
+        .await
+        .unwrap();
+
+    // Use a new payer to get a new tx ID, because the instruction data is the same
+    let new_payer = new_funded_keypair(&mut banks_client, &payer, ONE_SOL_IN_LAMPORTS).await;
+    let result = initialize(program_id, &mut banks_client, &new_payer).await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigIsmError::AlreadyInitialized as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_get_owner() {
+    let program_id = weighted_multisig_ism_message_id_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    let (access_control_pda_key, _) = initialize(program_id, &mut banks_client, &payer)
+        .await
+        .unwrap();
+
+    let owner = simulate_instruction::<SimulationReturnData<Option<Pubkey>>>(
+        &mut banks_client,
+        &payer,
+        Instruction::new_with_bytes(
+            program_id,
+            &WeightedMultisigIsmProgramInstruction::GetOwner
+                .encode()
+                .unwrap(),
+            vec![AccountMeta::new_readonly(access_control_pda_key, false)],
+        ),
+    )
+    .await
+    .unwrap()
+    .unwrap()
+    .return_data;
+    assert_eq!(owner, Some(payer.pubkey()));
+}
+
+#[tokio::test]
+async fn test_transfer_ownership() {
+    let program_id = weighted_multisig_ism_message_id_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    let (access_control_pda_key, access_control_pda_bump_seed) =
+        initialize(program_id, &mut banks_client, &payer)
+            .await
+            .unwrap();
+
+    let new_owner = new_funded_keypair(&mut banks_client, &payer, ONE_SOL_IN_LAMPORTS).await;
+    transfer_ownership(
+        program_id,
+        &mut banks_client,
+        &payer,
+        Some(new_owner.pubkey()),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        access_control_data(&mut banks_client, access_control_pda_key).await,
+        Box::new(AccessControlData {
+            bump_seed: access_control_pda_bump_seed,
+            owner: Some(new_owner.pubkey()),
+        }),
+    );
+
+    // The previous owner can no longer transfer ownership
+    let result =
+        transfer_ownership(program_id, &mut banks_client, &payer, Some(payer.pubkey())).await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+
+    // Nor set the validators and threshold weight
+    let result = set_validators_and_threshold_weight(
+        program_id,
+        &mut banks_client,
+        &payer,
+        1234,
+        random_validators_and_threshold_weight(&[30, 70], 60),
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+
+    // Renouncing ownership leaves the program without an owner
+    transfer_ownership(program_id, &mut banks_client, &new_owner, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        access_control_data(&mut banks_client, access_control_pda_key)
+            .await
+            .owner,
+        None,
+    );
+}
+
+#[tokio::test]
+async fn test_transfer_ownership_errors_if_owner_not_signer() {
+    let program_id = weighted_multisig_ism_message_id_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    let (access_control_pda_key, _) = initialize(program_id, &mut banks_client, &payer)
+        .await
+        .unwrap();
+
+    let non_owner = new_funded_keypair(&mut banks_client, &payer, ONE_SOL_IN_LAMPORTS).await;
+
+    // The owner is passed in, but the transaction is only signed by the non-owner
+    let result = process_test_instruction(
+        &mut banks_client,
+        Instruction::new_with_bytes(
+            program_id,
+            &WeightedMultisigIsmProgramInstruction::TransferOwnership(Some(non_owner.pubkey()))
+                .encode()
+                .unwrap(),
+            vec![
+                AccountMeta::new_readonly(payer.pubkey(), false),
+                AccountMeta::new(access_control_pda_key, false),
+            ],
+        ),
+        &non_owner,
+        &[&non_owner],
+    )
+    .await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature),
+    );
+}
+
+#[tokio::test]
+async fn test_set_validators_and_threshold_weight_creates_pda_account() {
+    let program_id = weighted_multisig_ism_message_id_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(program_id, &mut banks_client, &payer)
+        .await
+        .unwrap();
+
+    let domain: u32 = 1234;
+    let validators_and_threshold_weight = random_validators_and_threshold_weight(&[20, 50, 30], 70);
+
+    let (domain_data_pda_key, domain_data_pda_bump_seed) = set_validators_and_threshold_weight(
+        program_id,
+        &mut banks_client,
+        &payer,
+        domain,
+        validators_and_threshold_weight.clone(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        domain_data(&mut banks_client, domain_data_pda_key).await,
+        Box::new(DomainData {
+            bump_seed: domain_data_pda_bump_seed,
+            validators_and_threshold_weight,
+        }),
+    );
+
+    // Set the validators and threshold weight again now that the domain data PDA exists
+    let validators_and_threshold_weight = random_validators_and_threshold_weight(&[10, 90], 100);
+
+    set_validators_and_threshold_weight(
+        program_id,
+        &mut banks_client,
+        &payer,
+        domain,
+        validators_and_threshold_weight.clone(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        domain_data(&mut banks_client, domain_data_pda_key).await,
+        Box::new(DomainData {
+            bump_seed: domain_data_pda_bump_seed,
+            validators_and_threshold_weight: validators_and_threshold_weight.clone(),
+        }),
+    );
+
+    // Query the validators and threshold weight the way the relayer does, using
+    // MultisigIsmInstruction::ValidatorsAndThresholdAccountMetas to fetch the account
+    // metas required for MultisigIsmInstruction::ValidatorsAndThresholdWeight.
+    let test_message = HyperlaneMessage {
+        version: 3,
+        nonce: 0,
+        origin: domain,
+        sender: H256::random(),
+        destination: domain + 1,
+        recipient: H256::random(),
+        body: vec![1, 2, 3, 4, 5],
+    };
+
+    let (account_metas_pda_key, _) = Pubkey::find_program_address(
+        VALIDATORS_AND_THRESHOLD_ACCOUNT_METAS_PDA_SEEDS,
+        &program_id,
+    );
+    let account_metas = get_account_metas(
+        &mut banks_client,
+        &payer,
+        Instruction::new_with_bytes(
+            program_id,
+            &MultisigIsmInstruction::ValidatorsAndThresholdAccountMetas(test_message.to_vec())
+                .encode()
+                .unwrap(),
+            vec![AccountMeta::new(account_metas_pda_key, false)],
+        ),
+    )
+    .await
+    .unwrap();
+
+    let queried = simulate_instruction::<SimulationReturnData<ValidatorsAndThresholdWeight>>(
+        &mut banks_client,
+        &payer,
+        Instruction::new_with_bytes(
+            program_id,
+            &MultisigIsmInstruction::ValidatorsAndThresholdWeight(test_message.to_vec())
+                .encode()
+                .unwrap(),
+            account_metas,
+        ),
+    )
+    .await
+    .unwrap()
+    .unwrap()
+    .return_data;
+    assert_eq!(queried, validators_and_threshold_weight);
+}
+
+#[tokio::test]
+async fn test_set_validators_and_threshold_weight_errors_if_not_owner() {
+    let program_id = weighted_multisig_ism_message_id_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(program_id, &mut banks_client, &payer)
+        .await
+        .unwrap();
+
+    let non_owner = new_funded_keypair(&mut banks_client, &payer, ONE_SOL_IN_LAMPORTS).await;
+    let result = set_validators_and_threshold_weight(
+        program_id,
+        &mut banks_client,
+        &non_owner,
+        1234,
+        random_validators_and_threshold_weight(&[30, 70], 60),
+    )
+    .await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+}
+
+#[tokio::test]
+async fn test_set_validators_and_threshold_weight_errors_if_owner_not_signer() {
+    let program_id = weighted_multisig_ism_message_id_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(program_id, &mut banks_client, &payer)
+        .await
+        .unwrap();
+
+    let non_owner = new_funded_keypair(&mut banks_client, &payer, ONE_SOL_IN_LAMPORTS).await;
+    let mut instruction = set_validators_and_threshold_weight_instruction(
+        program_id,
+        payer.pubkey(),
+        1234,
+        random_validators_and_threshold_weight(&[30, 70], 60),
+    )
+    .unwrap();
+    // The owner is passed in, but the transaction is only signed by the non-owner
+    instruction.accounts[0].is_signer = false;
+
+    let result =
+        process_test_instruction(&mut banks_client, instruction, &non_owner, &[&non_owner]).await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature),
+    );
+}
+
+#[tokio::test]
+async fn test_set_validators_and_threshold_weight_errors_if_invalid() {
+    let program_id = weighted_multisig_ism_message_id_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(program_id, &mut banks_client, &payer)
+        .await
+        .unwrap();
+
+    // The threshold weight exceeds the total weight of the validators
+    let result = set_validators_and_threshold_weight(
+        program_id,
+        &mut banks_client,
+        &payer,
+        1234,
+        random_validators_and_threshold_weight(&[30, 70], 101),
+    )
+    .await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigIsmError::InvalidValidatorsAndThreshold as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_ism_verify() {
+    let program_id = weighted_multisig_ism_message_id_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(program_id, &mut banks_client, &payer)
+        .await
+        .unwrap();
+
+    let MultisigIsmTestData {
+        message,
+        checkpoint,
+        validators,
+        signatures,
+    } = get_multisig_ism_test_data();
+
+    set_validators_and_threshold_weight(
+        program_id,
+        &mut banks_client,
+        &payer,
+        message.origin,
+        ValidatorsAndThresholdWeight {
+            validators: validators
+                .iter()
+                .zip([20, 50, 30])
+                .map(|(&validator, weight)| WeightedValidator { validator, weight })
+                .collect(),
+            threshold_weight: 70,
+        },
+    )
+    .await
+    .unwrap();
+
+    let verify_instruction = |signers: &[usize]| VerifyInstruction {
+        metadata: MultisigIsmMessageIdMetadata {
+            origin_merkle_tree_hook: checkpoint.merkle_tree_hook_address,
+            merkle_root: checkpoint.root,
+            merkle_index: checkpoint.index,
+            validator_signatures: signers
+                .iter()
+                .map(|&i| EcdsaSignature::from_bytes(&signatures[i]).unwrap())
+                .collect(),
+        }
+        .to_vec(),
+        message: message.to_vec(),
+    };
+
+    // First get the account metas needed
+    let (account_metas_pda_key, _) =
+        Pubkey::find_program_address(VERIFY_ACCOUNT_METAS_PDA_SEEDS, &program_id);
+    let account_metas = get_account_metas(
+        &mut banks_client,
+        &payer,
+        Instruction::new_with_bytes(
+            program_id,
+            &InterchainSecurityModuleInstruction::VerifyAccountMetas(verify_instruction(&[0, 1]))
+                .encode()
+                .unwrap(),
+            vec![AccountMeta::new(account_metas_pda_key, false)],
+        ),
+    )
+    .await
+    .unwrap();
+
+    let verify = |signers: &[usize]| {
+        Instruction::new_with_bytes(
+            program_id,
+            &InterchainSecurityModuleInstruction::Verify(verify_instruction(signers))
+                .encode()
+                .unwrap(),
+            account_metas.clone(),
+        )
+    };
+
+    // Signatures weighing at least the threshold weight, in the correct order
+    for signers in [[0, 1], [1, 2]] {
+        process_test_instruction(&mut banks_client, verify(&signers), &payer, &[&payer])
+            .await
+            .unwrap();
+    }
+
+    // Signatures in the correct order, but not weighing enough
+    let result =
+        process_test_instruction(&mut banks_client, verify(&[0, 2]), &payer, &[&payer]).await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigIsmError::ThresholdNotMet as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_ism_type() {
+    let program_id = weighted_multisig_ism_message_id_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    let type_u32 = simulate_instruction::<SimulationReturnData<u32>>(
+        &mut banks_client,
+        &payer,
+        Instruction::new_with_bytes(
+            program_id,
+            &InterchainSecurityModuleInstruction::Type.encode().unwrap(),
+            vec![],
+        ),
+    )
+    .await
+    .unwrap()
+    .unwrap()
+    .return_data;
+    assert_eq!(type_u32, ModuleType::WeightedMessageIdMultisig as u32);
+}
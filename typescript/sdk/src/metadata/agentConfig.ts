@@ -49,6 +49,7 @@ export enum AgentSignerKeyType {
   Hex = 'hexKey',
   Node = 'node',
   Cosmos = 'cosmosKey',
+  Remote = 'remote',
 }
 
 export enum AgentSealevelPriorityFeeOracleType {
@@ -98,18 +99,53 @@ const AgentSignerNodeSchema = z
     type: z.literal(AgentSignerKeyType.Node),
   })
   .describe('Assume the local node will sign on RPC calls automatically');
+const AgentSignerRemoteSchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.Remote),
+    url: z.string().url().describe('The base url of the signing service'),
+    address: ZHash.describe('The address of the key'),
+    identifier: z
+      .string()
+      .optional()
+      .describe(
+        'The identifier of the key in the signing service, defaults to its address',
+      ),
+    clientCert: z
+      .string()
+      .optional()
+      .describe('Path to the PEM client certificate for mutual TLS'),
+    clientKey: z
+      .string()
+      .optional()
+      .describe('Path to the PEM private key of the client certificate'),
+    caCert: z
+      .string()
+      .optional()
+      .describe('Path to the PEM certificate of the signing service CA'),
+    timeout: ZUint.optional().describe(
+      'Timeout of each request to the signing service, in seconds. Defaults to 10.',
+    ),
+    maxRetries: ZUint.optional().describe(
+      'How many times a failed request to the signing service is retried. Defaults to 3.',
+    ),
+  })
+  .describe(
+    'A key held by a signing service implementing the web3signer eth1 API',
+  );
 
 const AgentSignerSchema = z.union([
   AgentSignerHexKeySchema,
   AgentSignerAwsKeySchema,
   AgentSignerCosmosKeySchema,
   AgentSignerNodeSchema,
+  AgentSignerRemoteSchema,
 ]);
 
 export type AgentSignerHexKey = z.infer<typeof AgentSignerHexKeySchema>;
 export type AgentSignerAwsKey = z.infer<typeof AgentSignerAwsKeySchema>;
 export type AgentSignerCosmosKey = z.infer<typeof AgentSignerNodeSchema>;
 export type AgentSignerNode = z.infer<typeof AgentSignerNodeSchema>;
+export type AgentSignerRemote = z.infer<typeof AgentSignerRemoteSchema>;
 export type AgentSigner = z.infer<typeof AgentSignerSchema>;
 
 // Additional chain metadata for Cosmos chains required by the agents.
@@ -191,6 +227,12 @@ export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
     signer: AgentSignerSchema.optional().describe(
       'The signer to use for this chain',
     ),
+    signerPool: z
+      .array(AgentSignerSchema)
+      .optional()
+      .describe(
+        'Additional signers that submit transactions to this chain in parallel with `signer`, each with its own nonce. Only supported on EVM chains.',
+      ),
     index: z
       .object({
         from: ZUint.optional().describe(
@@ -317,8 +359,37 @@ export enum GasPaymentEnforcementPolicyType {
   None = 'none',
   Minimum = 'minimum',
   OnChainFeeQuoting = 'onChainFeeQuoting',
+  UsdValue = 'usdValue',
+  AllOf = 'allOf',
+  AnyOf = 'anyOf',
 }
 
+const TokenPriceSchema = z.object({
+  price: z.number().nonnegative().describe('USD price of one whole token.'),
+  decimals: ZUint.optional().describe('Decimals of the token. Defaults to 18.'),
+});
+
+const TokenPriceSourceSchema = z.union([
+  z.object({
+    type: z.literal('static').optional(),
+    prices: z
+      .record(TokenPriceSchema)
+      .describe('Native token prices keyed by domain id.'),
+  }),
+  z.object({
+    type: z.literal('file'),
+    path: z
+      .string()
+      .min(1)
+      .describe(
+        'Path to a JSON file with native token prices keyed by domain id.',
+      ),
+    refreshInterval: ZUint.optional().describe(
+      'How often to read the file again, in seconds. Prices that were not refreshed for 3 intervals are stale and not used. Defaults to 60.',
+    ),
+  }),
+]);
+
 const GasPaymentEnforcementBaseSchema = z.object({
   matchingList: MatchingListSchema.optional().describe(
     'An optional matching list, any message that matches will use this policy. By default all messages will match.',
@@ -339,6 +410,29 @@ const GasPaymentEnforcementSchema = z.union([
       .regex(/^\d+ ?\/ ?[1-9]\d*$/)
       .optional(),
   }),
+  GasPaymentEnforcementBaseSchema.extend({
+    type: z.literal(GasPaymentEnforcementPolicyType.UsdValue),
+    margin: z
+      .number()
+      .positive()
+      .optional()
+      .describe(
+        'The USD value of the payment must be at least this multiple of the USD value of the estimated transaction cost. Defaults to 1.',
+      ),
+    priceSource: TokenPriceSourceSchema,
+  }),
+  GasPaymentEnforcementBaseSchema.extend({
+    type: z.union([
+      z.literal(GasPaymentEnforcementPolicyType.AllOf),
+      z.literal(GasPaymentEnforcementPolicyType.AnyOf),
+    ]),
+    policies: z
+      .array(z.lazy((): z.ZodTypeAny => GasPaymentEnforcementSchema))
+      .nonempty()
+      .describe(
+        'The policies to combine. `allOf` requires every policy to be met, `anyOf` at least one. The matching lists of combined policies are ignored.',
+      ),
+  }),
 ]);
 export type GasPaymentEnforcement = z.infer<typeof GasPaymentEnforcementSchema>;
 
@@ -349,6 +443,45 @@ const MetricAppContextSchema = z.object({
   ),
 });
 
+const RateLimitSchema = z.object({
+  matchingList: MatchingListSchema.optional().describe(
+    'An optional matching list, any message that matches is subject to this limit. By default all messages match.',
+  ),
+  keyBy: z
+    .enum(['sender', 'appContext'])
+    .optional()
+    .describe(
+      'Whether each sender or each app context of an origin chain gets its own bucket. Defaults to `sender`.',
+    ),
+  maxMessages: ZNzUint.describe(
+    'The number of messages that can be relayed in a burst.',
+  ),
+  period: ZNzUint.describe(
+    'The number of seconds it takes to relay `maxMessages` messages once the burst is used up.',
+  ),
+});
+
+const PriorityLaneSchema = z.object({
+  destination: z
+    .string()
+    .min(1)
+    .describe('The name of the destination chain the lanes are used for.'),
+  premiumAppContexts: z
+    .array(z.string().min(1))
+    .optional()
+    .describe('App contexts whose messages are served from the premium lane.'),
+  minGasOverpayment: z
+    .number()
+    .positive()
+    .optional()
+    .describe(
+      'Messages that paid for at least this multiple of their estimated gas limit are served from the premium lane.',
+    ),
+  premiumWeight: ZNzUint.optional().describe(
+    'How many premium messages are served for every standard one while both lanes have messages. Defaults to 4.',
+  ),
+});
+
 export const RelayerAgentConfigSchema = AgentConfigSchema.extend({
   db: z
     .string()
@@ -392,12 +525,42 @@ export const RelayerAgentConfigSchema = AgentConfigSchema.extend({
     .describe(
       'If true, allows local storage based checkpoint syncers. Not intended for production use.',
     ),
+  subModuleMetadataTimeout: ZNzUint.optional().describe(
+    'How long, in seconds, building the metadata of a sub-module of an aggregation or routing ISM may take before it is abandoned. Defaults to 30.',
+  ),
   metricAppContexts: z
     .union([z.array(MetricAppContextSchema), z.string().min(1)])
     .optional()
     .describe(
       'A list of app contexts and their matching lists to use for metrics. A message will be classified as the first matching app context.',
     ),
+  deadLetterMaxRetries: ZUint.optional().describe(
+    'Number of retries after which an operation is moved to the dead-letter queue. If not set, operations are retried indefinitely.',
+  ),
+  deadLetterReasons: z
+    .string()
+    .optional()
+    .describe(
+      'Comma separated list of reprepare reasons (e.g. `ErrorEstimatingGas,ExceedsMaxGasLimit`) that immediately move an operation to the dead-letter queue.',
+    ),
+  rateLimits: z
+    .union([z.array(RateLimitSchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'Token bucket limits on how quickly the messages of a sender or app are relayed, as JSON.',
+    ),
+  priorityLanes: z
+    .union([z.array(PriorityLaneSchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'Destinations whose submission queues serve high-value messages from a premium lane, as JSON.',
+    ),
+  dryRun: z
+    .boolean()
+    .optional()
+    .describe(
+      'If true, messages are fully prepared, including metadata building, gas estimation and gas payment checks, but are only logged instead of being submitted.',
+    ),
 });
 
 export type RelayerConfig = z.infer<typeof RelayerAgentConfigSchema>;
@@ -411,63 +574,108 @@ export const ScraperAgentConfigSchema = AgentConfigSchema.extend({
 
 export type ScraperConfig = z.infer<typeof ScraperAgentConfigSchema>;
 
+const LocalStorageCheckpointSyncerSchema = z
+  .object({
+    type: z.literal('localStorage'),
+    path: z.string().min(1).describe('Path to the local storage location'),
+  })
+  .describe('A local checkpoint syncer');
+
+const S3CheckpointSyncerSchema = z
+  .object({
+    type: z.literal('s3'),
+    bucket: z.string().min(1),
+    region: z.string().min(1),
+    folder: z
+      .string()
+      .min(1)
+      .optional()
+      .describe(
+        'The folder/key-prefix to use, defaults to the root of the bucket',
+      ),
+  })
+  .describe('A checkpoint syncer that uses S3');
+
+const GcsCheckpointSyncerSchema = z
+  .object({
+    type: z.literal('gcs'),
+    bucket: z.string().min(1),
+    folder: z
+      .string()
+      .min(1)
+      .optional()
+      .describe('The folder to use, defaults to the root of the bucket'),
+    service_account_key: z
+      .string()
+      .min(1)
+      .optional()
+      .describe('The path to GCS service account key file'),
+    user_secrets: z
+      .string()
+      .min(1)
+      .optional()
+      .describe('The path to GCS user secret file'),
+  })
+  .describe('A checkpoint syncer that uses Google Cloud Storage');
+
+const CheckpointStorageSchema = z.discriminatedUnion('type', [
+  LocalStorageCheckpointSyncerSchema,
+  S3CheckpointSyncerSchema,
+  GcsCheckpointSyncerSchema,
+]);
+
 export const ValidatorAgentConfigSchema = AgentConfigSchema.extend({
   db: z
     .string()
     .min(1)
     .optional()
     .describe('The path to the validator database.'),
+  slashingProtectionDb: z
+    .string()
+    .min(1)
+    .optional()
+    .describe(
+      'The path to the slashing protection database, which must not be wiped along with the validator database. Defaults to the validator database path suffixed with `_slashing_protection`.',
+    ),
+  slashingProtectionImport: z
+    .string()
+    .min(1)
+    .optional()
+    .describe(
+      'The path to a slashing protection interchange file to import on startup.',
+    ),
   originChainName: z
     .string()
     .min(1)
     .describe('Name of the chain to validate messages on'),
   validator: AgentSignerSchema.describe('The validator attestation signer'),
   checkpointSyncer: z.discriminatedUnion('type', [
+    LocalStorageCheckpointSyncerSchema,
+    S3CheckpointSyncerSchema,
+    GcsCheckpointSyncerSchema,
     z
       .object({
-        type: z.literal('localStorage'),
-        path: z.string().min(1).describe('Path to the local storage location'),
-      })
-      .describe('A local checkpoint syncer'),
-    z
-      .object({
-        type: z.literal('s3'),
-        bucket: z.string().min(1),
-        region: z.string().min(1),
-        folder: z
-          .string()
+        type: z.literal('fanOut'),
+        syncers: z
+          .array(CheckpointStorageSchema)
           .min(1)
-          .optional()
           .describe(
-            'The folder/key-prefix to use, defaults to the root of the bucket',
+            'The checkpoint syncers to write to, in order of preference when reading. Every one of them is announced.',
           ),
+        writeQuorum: ZNzUint.optional().describe(
+          'How many checkpoint syncers a write must succeed on, defaults to all of them',
+        ),
       })
-      .describe('A checkpoint syncer that uses S3'),
-    z
-      .object({
-        type: z.literal('gcs'),
-        bucket: z.string().min(1),
-        folder: z
-          .string()
-          .min(1)
-          .optional()
-          .describe('The folder to use, defaults to the root of the bucket'),
-        service_account_key: z
-          .string()
-          .min(1)
-          .optional()
-          .describe('The path to GCS service account key file'),
-        user_secrets: z
-          .string()
-          .min(1)
-          .optional()
-          .describe('The path to GCS user secret file'),
-      })
-      .describe('A checkpoint syncer that uses Google Cloud Storage'),
+      .describe(
+        'A checkpoint syncer that writes to several checkpoint syncers',
+      ),
   ]),
   interval: ZUint.optional().describe(
     'How long to wait between checking for new checkpoints in seconds.',
   ),
+  selfAuditInterval: ZNzUint.optional().describe(
+    'How long to wait between audits of the published checkpoints in seconds. The published checkpoints are not audited if unset.',
+  ),
 });
 
 export type ValidatorConfig = z.infer<typeof ValidatorAgentConfigSchema>;
@@ -4,7 +4,7 @@
  */
 import { z } from 'zod';
 
-import { ZHash, ZNzUint } from './customZodTypes.js';
+import { ZHash, ZNzUint, ZUint } from './customZodTypes.js';
 
 const DomainSchema = z.union([
   z.literal('*'),
@@ -14,14 +14,45 @@ const DomainSchema = z.union([
 
 const AddressSchema = z.union([z.literal('*'), ZHash, z.array(ZHash)]);
 
-const MatchingListElementSchema = z.object({
+const BytesSchema = z.string().regex(/^(0x)?([0-9a-fA-F]{2})*$/);
+
+const BodyPatternSchema = z.object({
+  offset: ZUint.optional(),
+  bytes: BytesSchema,
+});
+
+const BaseMatchingListElementSchema = z.object({
   messageId: AddressSchema.optional(),
   originDomain: DomainSchema.optional(),
   senderAddress: AddressSchema.optional(),
   destinationDomain: DomainSchema.optional(),
   recipientAddress: AddressSchema.optional(),
+  bodyPrefix: BytesSchema.optional(),
+  bodyPattern: z
+    .union([BodyPatternSchema, z.array(BodyPatternSchema)])
+    .optional(),
+  minBodyLength: ZUint.optional(),
+  maxBodyLength: ZUint.optional(),
 });
 
+type MatchingListElementInput = z.infer<
+  typeof BaseMatchingListElementSchema
+> & {
+  not?: MatchingListElementInput | MatchingListElementInput[];
+};
+
+const MatchingListElementSchema: z.ZodType<MatchingListElementInput> =
+  BaseMatchingListElementSchema.extend({
+    not: z
+      .lazy(() =>
+        z.union([
+          MatchingListElementSchema,
+          z.array(MatchingListElementSchema),
+        ]),
+      )
+      .optional(),
+  });
+
 export const MatchingListSchema = z.array(MatchingListElementSchema);
 
 export type MatchingListElement = z.infer<typeof MatchingListElementSchema>;